@@ -0,0 +1,686 @@
+//! Authenticated API client for NEWM Admin
+//!
+//! Every admin endpoint call goes through the same steps: get a valid access
+//! token from the session, send the request inside a `Compat` wrapper, turn
+//! a 401 into a forced refresh and one retry, and map failures to
+//! [`ApiError`] using the server's error body. Feature modules (earnings,
+//! songs, cardano) only describe the request and the response type.
+//!
+//! The auth endpoints (login, refresh, logout) are called before there is a
+//! session, or with a token from outside one. They use an
+//! [`ApiClient::anonymous`] client and [`ApiClient::send_raw`], which share
+//! the retries below but hand back the final status and body as they are.
+//!
+//! Transient failures are retried with exponential backoff, but only when
+//! repeating the call cannot change anything twice: reads are always safe to
+//! repeat, writes only when the connection failed before the request went
+//! out. A write that fails after it was sent (a timeout, a dropped
+//! connection, a gateway error) may or may not have been applied, so it is
+//! reported as [`ApiError::Ambiguous`] for the admin to reconcile by hand.
+//! Repeating `POST /v1/earnings/admin/{songIdOrIsrc}` would pay twice.
+
+use std::time::Duration;
+
+use async_compat::Compat;
+use reqwest::{Client, Method, StatusCode};
+use serde::Deserialize;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+
+use crate::environment::Environment;
+use crate::session::{Session, SessionError};
+use crate::settings::Settings;
+
+/// Error body returned by newm-server
+#[derive(Debug, Deserialize)]
+pub struct ApiErrorResponse {
+    pub code: u16,
+    pub description: String,
+    pub cause: String,
+    /// Set on errors clients are expected to tell apart
+    #[serde(default, rename = "errorCode")]
+    pub error_code: Option<String>,
+}
+
+/// Machine-readable code from an error response body, if it carries one
+pub fn error_code(body: &str) -> Option<String> {
+    serde_json::from_str::<ApiErrorResponse>(body)
+        .ok()
+        .and_then(|error| error.error_code)
+}
+
+/// Human-readable message from an error response body.
+///
+/// Uses the `cause` of a server error body, or the raw text (an HTML page
+/// from a load balancer, say) if the body is something else.
+pub fn error_message(body: String) -> String {
+    match serde_json::from_str::<ApiErrorResponse>(&body) {
+        Ok(error) if !error.cause.is_empty() => error.cause,
+        Ok(error) => error.description,
+        Err(_) => body,
+    }
+}
+
+/// Error from an authenticated API call
+#[derive(Debug)]
+pub enum ApiError {
+    /// Session expired, user must re-login
+    SessionExpired(String),
+    /// API returned an error, or a response that could not be parsed
+    Api { status: u16, message: String },
+    /// Network or other error
+    Network(String),
+    /// A write was sent but its outcome is unknown; check the server before
+    /// trying it again
+    Ambiguous(String),
+}
+
+impl std::fmt::Display for ApiError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ApiError::SessionExpired(msg) => write!(f, "Session expired: {}", msg),
+            ApiError::Api { status, message } => write!(f, "API error {}: {}", status, message),
+            ApiError::Network(msg) => write!(f, "Network error: {}", msg),
+            ApiError::Ambiguous(msg) => {
+                write!(f, "Outcome unknown, check before retrying: {}", msg)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ApiError {}
+
+impl From<SessionError> for ApiError {
+    fn from(err: SessionError) -> Self {
+        match err {
+            SessionError::Expired(msg) => ApiError::SessionExpired(msg),
+            SessionError::Network(msg) => ApiError::Network(msg),
+        }
+    }
+}
+
+/// Parse a successful response body; an empty body reads as JSON `null`,
+/// so endpoints without a response can be called with `T = ()`
+fn parse_body<T: DeserializeOwned>(status: StatusCode, body: &str) -> Result<T, ApiError> {
+    let body = if body.trim().is_empty() { "null" } else { body };
+    serde_json::from_str(body).map_err(|e| ApiError::Api {
+        status: status.as_u16(),
+        message: format!("Failed to parse response: {}", e),
+    })
+}
+
+/// How many times to try an API call and how long to wait in between
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Attempts including the first one; `1` means no retries
+    pub max_attempts: u32,
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+}
+
+impl RetryPolicy {
+    /// Wait before retry number `retry` (starting at 1)
+    pub fn backoff(&self, retry: u32) -> Duration {
+        let factor = 2u32.saturating_pow(retry.saturating_sub(1));
+        self.initial_backoff
+            .saturating_mul(factor)
+            .min(self.max_backoff)
+    }
+}
+
+/// Whether repeating a request with `method` is harmless
+fn is_read(method: &Method) -> bool {
+    matches!(*method, Method::GET | Method::HEAD)
+}
+
+/// Statuses a load balancer or an overloaded server returns for requests
+/// that may succeed if sent again
+fn is_transient(status: StatusCode) -> bool {
+    matches!(
+        status,
+        StatusCode::TOO_MANY_REQUESTS
+            | StatusCode::BAD_GATEWAY
+            | StatusCode::SERVICE_UNAVAILABLE
+            | StatusCode::GATEWAY_TIMEOUT
+    )
+}
+
+/// Gateway statuses that do not say whether the server got the request
+fn is_gateway_failure(status: StatusCode) -> bool {
+    matches!(
+        status,
+        StatusCode::BAD_GATEWAY | StatusCode::GATEWAY_TIMEOUT
+    )
+}
+
+/// HTTP client bound to a session, or to an environment for the auth
+/// endpoints
+#[derive(Clone)]
+pub struct ApiClient {
+    client: Client,
+    environment: Environment,
+    session: Option<Session>,
+    retry: RetryPolicy,
+}
+
+impl ApiClient {
+    /// Client using the retry policy from the settings file
+    pub fn new(client: Client, session: &Session) -> Self {
+        Self {
+            client,
+            environment: session.environment().clone(),
+            session: Some(session.clone()),
+            retry: Settings::shared().retry_policy(),
+        }
+    }
+
+    /// Client for calls made without a session; requests carry no token
+    /// unless one is passed to [`ApiClient::send_raw`]
+    pub fn anonymous(client: Client, environment: &Environment) -> Self {
+        Self {
+            client,
+            environment: environment.clone(),
+            session: None,
+            retry: Settings::shared().retry_policy(),
+        }
+    }
+
+    #[cfg(test)]
+    pub fn with_retry(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// Wait before the next attempt, or `None` once attempts are used up
+    async fn backoff(&self, attempt: &mut u32) -> Option<()> {
+        if *attempt >= self.retry.max_attempts {
+            return None;
+        }
+        let delay = self.retry.backoff(*attempt);
+        *attempt += 1;
+        Compat::new(async { tokio::time::sleep(delay).await }).await;
+        Some(())
+    }
+
+    /// GET `path` and parse the JSON response
+    pub async fn get<T: DeserializeOwned>(&self, path: &str) -> Result<T, ApiError> {
+        self.send(Method::GET, path, None::<&()>).await
+    }
+
+    /// POST a JSON body to `path` and parse the JSON response
+    pub async fn post<B, T>(&self, path: &str, body: &B) -> Result<T, ApiError>
+    where
+        B: Serialize + ?Sized,
+        T: DeserializeOwned,
+    {
+        self.send(Method::POST, path, Some(body)).await
+    }
+
+    /// POST to `path` without a body and parse the JSON response
+    pub async fn post_empty<T: DeserializeOwned>(&self, path: &str) -> Result<T, ApiError> {
+        self.send(Method::POST, path, None::<&()>).await
+    }
+
+    /// DELETE with a JSON body and parse the JSON response
+    pub async fn delete<B, T>(&self, path: &str, body: &B) -> Result<T, ApiError>
+    where
+        B: Serialize + ?Sized,
+        T: DeserializeOwned,
+    {
+        self.send(Method::DELETE, path, Some(body)).await
+    }
+
+    /// Send an authenticated request to `path` (e.g. `/v1/songs/{id}`).
+    ///
+    /// A 401 forces a token refresh and the request is sent once more; a
+    /// second 401 means the session is over. Other failures are retried as
+    /// described in the module docs. An anonymous client sends no token.
+    pub async fn send<B, T>(
+        &self,
+        method: Method,
+        path: &str,
+        body: Option<&B>,
+    ) -> Result<T, ApiError>
+    where
+        B: Serialize + ?Sized,
+        T: DeserializeOwned,
+    {
+        let read = is_read(&method);
+
+        let mut access_token = match &self.session {
+            Some(session) => Some(session.get_valid_token().await?),
+            None => None,
+        };
+        let mut refreshed = false;
+        loop {
+            let (status, text) = self
+                .send_raw(method.clone(), path, access_token.as_deref(), &[], body)
+                .await?;
+
+            if status == StatusCode::UNAUTHORIZED
+                && let Some(session) = &self.session
+            {
+                if refreshed {
+                    return Err(ApiError::SessionExpired(
+                        "Unauthorized - please login again".to_string(),
+                    ));
+                }
+                // The token may have been revoked early; try once with a new one
+                tracing::info!("{} {} returned 401, refreshing token", method, path);
+                access_token = Some(session.refresh_now().await?);
+                refreshed = true;
+                continue;
+            }
+
+            if status.is_success() {
+                return parse_body(status, &text);
+            }
+
+            tracing::warn!("{} {} failed: {} - {}", method, path, status, text);
+            if !read && is_gateway_failure(status) {
+                return Err(ApiError::Ambiguous(format!(
+                    "{} {} returned {}: {}",
+                    method,
+                    path,
+                    status,
+                    error_message(text)
+                )));
+            }
+            return Err(ApiError::Api {
+                status: status.as_u16(),
+                message: error_message(text),
+            });
+        }
+    }
+
+    /// Send a request to `path` with an optional bearer token and query
+    /// parameters, returning the final status and body whatever the status.
+    ///
+    /// Connection failures and transient statuses are retried as described
+    /// in the module docs; the errors are [`ApiError::Network`] and
+    /// [`ApiError::Ambiguous`] only.
+    pub async fn send_raw<B>(
+        &self,
+        method: Method,
+        path: &str,
+        bearer: Option<&str>,
+        query: &[(&str, &str)],
+        body: Option<&B>,
+    ) -> Result<(StatusCode, String), ApiError>
+    where
+        B: Serialize + ?Sized,
+    {
+        let url = format!("{}{}", self.environment.base_url(), path);
+        let read = is_read(&method);
+
+        let mut attempt = 1;
+        loop {
+            let mut request = self.client.request(method.clone(), &url);
+            if let Some(token) = bearer {
+                request = request.header("Authorization", format!("Bearer {}", token));
+            }
+            if !query.is_empty() {
+                request = request.query(query);
+            }
+            if let Some(body) = body {
+                request = request.json(body);
+            }
+
+            // Built inside Compat: with timeouts configured, reqwest starts a
+            // Tokio timer as soon as the request future is created
+            let response = match Compat::new(async { request.send().await }).await {
+                Ok(response) => response,
+                // Nothing reached the server, so even a write can go again
+                Err(e) if read || e.is_connect() => {
+                    tracing::warn!("{} {} attempt {} failed: {}", method, path, attempt, e);
+                    if self.backoff(&mut attempt).await.is_some() {
+                        continue;
+                    }
+                    return Err(ApiError::Network(e.to_string()));
+                }
+                Err(e) => {
+                    tracing::error!("{} {} outcome unknown: {}", method, path, e);
+                    return Err(ApiError::Ambiguous(format!("{} {}: {}", method, path, e)));
+                }
+            };
+            let status = response.status();
+
+            if read && is_transient(status) {
+                tracing::warn!("{} {} attempt {} got {}", method, path, attempt, status);
+                if self.backoff(&mut attempt).await.is_some() {
+                    continue;
+                }
+            }
+
+            let text = match Compat::new(async { response.text().await }).await {
+                Ok(text) => text,
+                Err(e) if read => {
+                    tracing::warn!("{} {} attempt {} failed: {}", method, path, attempt, e);
+                    if self.backoff(&mut attempt).await.is_some() {
+                        continue;
+                    }
+                    return Err(ApiError::Network(e.to_string()));
+                }
+                // The server refused the request, so nothing was applied
+                Err(e) if status.is_client_error() => {
+                    tracing::warn!("{} {} got {}, body lost: {}", method, path, status, e);
+                    String::new()
+                }
+                Err(e) => {
+                    tracing::error!("{} {} response lost after {}: {}", method, path, status, e);
+                    return Err(ApiError::Ambiguous(format!(
+                        "{} {} returned {} but the response was lost: {}",
+                        method, path, status, e
+                    )));
+                }
+            };
+
+            return Ok((status, text));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ApiClient, ApiError, RetryPolicy, error_message, parse_body};
+    use crate::auth::LoginResponse;
+    use crate::cardano::Network;
+    use crate::environment::{Environment, EnvironmentEntry};
+    use crate::session::Session;
+    use base64::Engine;
+    use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+    use reqwest::StatusCode;
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    /// Unsigned admin token valid for an hour
+    fn token(id: &str) -> String {
+        let exp = chrono::Utc::now().timestamp() + 3600;
+        format!(
+            "{}.{}.signature",
+            URL_SAFE_NO_PAD.encode(r#"{"alg":"HS256","typ":"JWT"}"#),
+            URL_SAFE_NO_PAD.encode(format!(
+                r#"{{"admin":true,"type":"Access","exp":{},"jti":"{}"}}"#,
+                exp, id
+            ))
+        )
+    }
+
+    /// Local server replying with `responses` in order, recording the
+    /// request line and Authorization header of each request. An empty
+    /// status drops the connection without replying.
+    fn mock_server(
+        responses: Vec<(&'static str, String)>,
+    ) -> (Environment, Arc<Mutex<Vec<String>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let requests = Arc::new(Mutex::new(Vec::new()));
+
+        let seen = requests.clone();
+        std::thread::spawn(move || {
+            for ((status, body), stream) in responses.into_iter().zip(listener.incoming()) {
+                let mut stream = stream.unwrap();
+                let mut buf = [0u8; 8192];
+                let n = stream.read(&mut buf).unwrap_or(0);
+                let request = String::from_utf8_lossy(&buf[..n]).to_string();
+                let line = request.lines().next().unwrap_or_default().to_string();
+                let auth = request
+                    .lines()
+                    .find_map(|l| l.strip_prefix("authorization: Bearer "))
+                    .unwrap_or_default()
+                    .to_string();
+                seen.lock().unwrap().push(format!("{} {}", line, auth));
+                if status.is_empty() {
+                    continue;
+                }
+                let _ = write!(
+                    stream,
+                    "HTTP/1.1 {}\r\nContent-Type: application/json\r\n\
+                     Content-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    status,
+                    body.len(),
+                    body
+                );
+            }
+        });
+
+        (mock_environment(port), requests)
+    }
+
+    fn mock_environment(port: u16) -> Environment {
+        Environment::from_entry(EnvironmentEntry {
+            name: "Mock".to_string(),
+            base_url: format!("http://127.0.0.1:{}", port),
+            network: Network::Testnet,
+            color: None,
+            oauth: Default::default(),
+            token_verification: None,
+        })
+        .unwrap()
+    }
+
+    /// Client with short waits so retries do not slow the tests down
+    fn api(environment: Environment) -> ApiClient {
+        ApiClient::new(crate::http_client::shared(), &session(environment)).with_retry(
+            RetryPolicy {
+                max_attempts: 3,
+                initial_backoff: Duration::from_millis(1),
+                max_backoff: Duration::from_millis(10),
+            },
+        )
+    }
+
+    fn session(environment: Environment) -> Session {
+        Session::new(
+            LoginResponse {
+                access_token: token("first"),
+                refresh_token: "refresh-1".to_string(),
+            },
+            environment,
+        )
+    }
+
+    #[test]
+    fn test_error_message() {
+        let body = r#"{"code": 422, "description": "Unprocessable Entity", "cause": "Bad ISRC"}"#;
+        assert_eq!(error_message(body.to_string()), "Bad ISRC");
+        let body = r#"{"code": 500, "description": "Internal Server Error", "cause": ""}"#;
+        assert_eq!(error_message(body.to_string()), "Internal Server Error");
+        assert_eq!(error_message("<html>".to_string()), "<html>");
+    }
+
+    #[test]
+    fn test_parse_body() {
+        let values: Vec<u32> = parse_body(StatusCode::OK, "[1, 2]").unwrap();
+        assert_eq!(values, vec![1, 2]);
+        parse_body::<()>(StatusCode::NO_CONTENT, "").unwrap();
+        assert!(matches!(
+            parse_body::<Vec<u32>>(StatusCode::OK, "{}"),
+            Err(ApiError::Api { status: 200, .. })
+        ));
+    }
+
+    #[test]
+    fn test_retries_once_after_401() {
+        let fresh = token("second");
+        let (environment, requests) = mock_server(vec![
+            ("401 Unauthorized", String::new()),
+            (
+                "200 OK",
+                format!(
+                    r#"{{"accessToken":"{}","refreshToken":"refresh-2"}}"#,
+                    fresh
+                ),
+            ),
+            ("200 OK", "[1, 2, 3]".to_string()),
+        ]);
+        let api = ApiClient::new(crate::http_client::shared(), &session(environment));
+
+        let values: Vec<u32> = futures::executor::block_on(api.get("/v1/things")).unwrap();
+        assert_eq!(values, vec![1, 2, 3]);
+
+        let requests = requests.lock().unwrap();
+        assert_eq!(requests.len(), 3);
+        assert!(requests[0].starts_with("GET /v1/things "));
+        assert!(requests[0].ends_with(&token("first")));
+        assert!(requests[1].starts_with("GET /v1/auth/refresh "));
+        assert!(requests[2].ends_with(&fresh));
+    }
+
+    #[test]
+    fn test_second_401_expires_session() {
+        let (environment, _) = mock_server(vec![
+            ("401 Unauthorized", String::new()),
+            (
+                "200 OK",
+                format!(
+                    r#"{{"accessToken":"{}","refreshToken":"refresh-2"}}"#,
+                    token("second")
+                ),
+            ),
+            ("401 Unauthorized", String::new()),
+        ]);
+        let api = ApiClient::new(crate::http_client::shared(), &session(environment));
+
+        let result: Result<(), _> = futures::executor::block_on(api.post_empty("/v1/things"));
+        assert!(matches!(result, Err(ApiError::SessionExpired(_))));
+    }
+
+    #[test]
+    fn test_server_error_uses_cause() {
+        let (environment, _) = mock_server(vec![(
+            "409 Conflict",
+            r#"{"code": 409, "description": "Conflict", "cause": "Key name already exists"}"#
+                .to_string(),
+        )]);
+        let api = ApiClient::new(crate::http_client::shared(), &session(environment));
+
+        let result: Result<(), _> =
+            futures::executor::block_on(api.post("/v1/cardano/key", &["k"]));
+        match result {
+            Err(ApiError::Api { status, message }) => {
+                assert_eq!(status, 409);
+                assert_eq!(message, "Key name already exists");
+            }
+            other => panic!("unexpected {:?}", other.map(|_| ())),
+        }
+    }
+
+    #[test]
+    fn test_read_retries_transient_status() {
+        let (environment, requests) = mock_server(vec![
+            ("503 Service Unavailable", String::new()),
+            ("502 Bad Gateway", "<html>".to_string()),
+            ("200 OK", "[7]".to_string()),
+        ]);
+
+        let values: Vec<u32> =
+            futures::executor::block_on(api(environment).get("/v1/things")).unwrap();
+        assert_eq!(values, vec![7]);
+        assert_eq!(requests.lock().unwrap().len(), 3);
+    }
+
+    #[test]
+    fn test_read_gives_up_after_max_attempts() {
+        let (environment, requests) = mock_server(vec![
+            ("503 Service Unavailable", String::new()),
+            ("503 Service Unavailable", String::new()),
+            ("503 Service Unavailable", String::new()),
+        ]);
+
+        let result: Result<Vec<u32>, _> =
+            futures::executor::block_on(api(environment).get("/v1/things"));
+        assert!(matches!(result, Err(ApiError::Api { status: 503, .. })));
+        assert_eq!(requests.lock().unwrap().len(), 3);
+    }
+
+    #[test]
+    fn test_write_dropped_after_send_is_ambiguous() {
+        let (environment, requests) = mock_server(vec![("", String::new())]);
+
+        let result: Result<(), _> = futures::executor::block_on(
+            api(environment).post("/v1/earnings/admin/QZ1234567890", &[1]),
+        );
+        assert!(matches!(result, Err(ApiError::Ambiguous(_))));
+        assert_eq!(requests.lock().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_write_gateway_timeout_is_ambiguous() {
+        let (environment, requests) = mock_server(vec![("504 Gateway Timeout", String::new())]);
+
+        let result: Result<(), _> = futures::executor::block_on(
+            api(environment).post("/v1/earnings/admin/QZ1234567890", &[1]),
+        );
+        assert!(matches!(result, Err(ApiError::Ambiguous(_))));
+        assert_eq!(requests.lock().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_write_retried_when_never_sent() {
+        // Nothing listens on the port, so every attempt fails to connect
+        let port = TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+
+        let result: Result<(), _> = futures::executor::block_on(
+            api(mock_environment(port)).post("/v1/earnings/admin/QZ1234567890", &[1]),
+        );
+        assert!(matches!(result, Err(ApiError::Network(_))));
+    }
+
+    #[test]
+    fn test_send_raw_returns_final_status() {
+        let (environment, requests) = mock_server(vec![
+            ("503 Service Unavailable", String::new()),
+            (
+                "401 Unauthorized",
+                r#"{"code": 401, "description": "Unauthorized", "cause": "Token revoked"}"#
+                    .to_string(),
+            ),
+        ]);
+        let api = ApiClient::anonymous(crate::http_client::shared(), &environment).with_retry(
+            RetryPolicy {
+                max_attempts: 3,
+                initial_backoff: Duration::from_millis(1),
+                max_backoff: Duration::from_millis(10),
+            },
+        );
+
+        // Retried past the 503, then the 401 is handed back without a refresh
+        let (status, body) = futures::executor::block_on(api.send_raw(
+            reqwest::Method::GET,
+            "/v1/auth/code",
+            Some("refresh-1"),
+            &[("email", "admin+1@newm.io")],
+            None::<&()>,
+        ))
+        .unwrap();
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        assert_eq!(error_message(body), "Token revoked");
+
+        let requests = requests.lock().unwrap();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(
+            requests[1],
+            "GET /v1/auth/code?email=admin%2B1%40newm.io HTTP/1.1 refresh-1"
+        );
+    }
+
+    #[test]
+    fn test_backoff_doubles_up_to_max() {
+        let policy = RetryPolicy {
+            max_attempts: 5,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_millis(350),
+        };
+        assert_eq!(policy.backoff(1), Duration::from_millis(100));
+        assert_eq!(policy.backoff(2), Duration::from_millis(200));
+        assert_eq!(policy.backoff(3), Duration::from_millis(350));
+        assert_eq!(policy.backoff(40), Duration::from_millis(350));
+    }
+}
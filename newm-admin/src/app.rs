@@ -1,60 +1,125 @@
+use std::time::{Duration, Instant};
+
+use async_compat::Compat;
 use gpui::*;
 use gpui_component::Root;
 
-use crate::auth::{Environment, LoginResponse};
-use crate::session::{Session, SessionExpiredEvent};
+use crate::audit::{self, AuditAction, AuditEntry};
+use crate::auth::{AuthClient, AuthError, LoginResponse};
+use crate::colors;
+use crate::environment::{self, Environment};
+use crate::oauth::OAuthProvider;
+use crate::remember::{RememberedSessions, RestoredSession};
+use crate::session::{Session, SessionExpiredEvent, SessionExpiringEvent, SessionWatcher};
+use crate::settings::Settings;
+use crate::views::cardano::CardanoView;
 use crate::views::dashboard::DashboardView;
+use crate::views::encryption::EncryptionView;
+use crate::views::keys::KeysView;
+use crate::views::lock::{LockView, UnlockEvent};
 use crate::views::login::LoginView;
+use crate::views::reprocess::ReprocessView;
+use crate::views::song_earnings::SongEarningsView;
+use crate::views::whitelist::WhitelistView;
 
 /// Current view state of the application
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum AppView {
+    /// Trying a remembered session before showing the login screen
     #[default]
+    Restoring,
     Login,
     Dashboard,
 }
 
+/// How often to check whether the console has been idle too long
+const IDLE_CHECK_INTERVAL: Duration = Duration::from_secs(15);
+
 pub struct AdminApp {
     current_view: AppView,
     login_view: Entity<LoginView>,
     dashboard_view: Entity<DashboardView>,
     session: Option<Session>,
+    remembered: RememberedSessions,
+    settings: Settings,
+    /// Last keyboard or mouse input in the window
+    last_activity: Instant,
+    /// How the admin signed in; `None` for a remembered session
+    sign_in: Option<SignInMethod>,
+    /// Lock screen covering the dashboard, while locked
+    lock_view: Option<Entity<LockView>>,
+    _idle_task: Task<()>,
 }
 
 impl AdminApp {
     pub fn new(window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let login_view = Self::new_login_view(window, cx);
+        let dashboard_view = Self::new_dashboard_view(window, cx);
+
+        let idle_task = cx.spawn_in(window, async move |this, cx| {
+            loop {
+                cx.background_executor().timer(IDLE_CHECK_INTERVAL).await;
+                if this
+                    .update_in(cx, |app, window, cx| app.check_idle(window, cx))
+                    .is_err()
+                {
+                    break;
+                }
+            }
+        });
+
+        let mut app = Self {
+            current_view: AppView::default(),
+            login_view,
+            dashboard_view,
+            session: None,
+            remembered: RememberedSessions::new(),
+            settings: Settings::shared().clone(),
+            last_activity: Instant::now(),
+            sign_in: None,
+            lock_view: None,
+            _idle_task: idle_task,
+        };
+        app.restore_session(window, cx);
+        app
+    }
+
+    fn new_login_view(window: &mut Window, cx: &mut Context<Self>) -> Entity<LoginView> {
         let login_view = cx.new(|cx| LoginView::new(window, cx));
-        let dashboard_view = cx.new(|cx| DashboardView::new(window, cx));
 
         // Subscribe to login success events
         cx.subscribe(
             &login_view,
             |this, _login, event: &LoginSuccessEvent, cx| {
-                // Create session from login response
-                this.session = Some(Session::new(
+                this.sign_in = Some(event.method.clone());
+                this.start_session(
                     event.login_response.clone(),
-                    event.environment,
-                ));
-
-                // Update dashboard with session
-                this.dashboard_view.update(cx, |dashboard, cx| {
-                    dashboard.set_session(this.session.clone(), cx);
-                });
-
-                this.current_view = AppView::Dashboard;
-                cx.notify();
+                    event.environment.clone(),
+                    event.remember,
+                    cx,
+                );
             },
         )
         .detach();
 
+        login_view
+    }
+
+    fn new_dashboard_view(window: &mut Window, cx: &mut Context<Self>) -> Entity<DashboardView> {
+        let dashboard_view = cx.new(|cx| DashboardView::new(window, cx));
+
         // Subscribe to session expired events from dashboard
         cx.subscribe(
             &dashboard_view,
             |this, _dashboard, event: &SessionExpiredEvent, cx| {
                 tracing::warn!("Session expired: {}", event.message);
 
-                // Clear session and return to login
-                this.session = None;
+                // Clear session and return to login. The refresh token is
+                // no longer accepted, so don't try it again on next launch.
+                if let Some(session) = this.session.take() {
+                    this.forget_in_background(session.environment().clone(), cx);
+                }
+                this.lock_view = None;
                 this.current_view = AppView::Login;
 
                 cx.notify();
@@ -62,23 +127,266 @@ impl AdminApp {
         )
         .detach();
 
-        Self {
-            current_view: AppView::default(),
-            login_view,
-            dashboard_view,
-            session: None,
+        // Subscribe to logout requests from the sidebar
+        cx.subscribe_in(
+            &dashboard_view,
+            window,
+            |this, _dashboard, _event: &LogoutEvent, window, cx| {
+                this.logout(window, cx);
+            },
+        )
+        .detach();
+
+        dashboard_view
+    }
+
+    /// End the session here and on the server, and start over at the login screen
+    fn logout(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if let Some(session) = self.session.take() {
+            tracing::info!("Logging out of {}", session.environment().display_name());
+            self.forget_in_background(session.environment().clone(), cx);
+            cx.spawn(async move |_this, _cx| {
+                if let Err(e) = session.logout().await {
+                    tracing::warn!("Server logout failed: {}", e);
+                }
+            })
+            .detach();
+        }
+
+        // Fresh views drop every cached list, selection and form
+        self.lock_view = None;
+        self.sign_in = None;
+        self.dashboard_view = Self::new_dashboard_view(window, cx);
+        self.login_view = Self::new_login_view(window, cx);
+        self.current_view = AppView::Login;
+        cx.notify();
+    }
+
+    /// Lock the console if there has been no input for the configured time
+    fn check_idle(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(timeout) = self.settings.idle_timeout() else {
+            return;
+        };
+        if self.last_activity.elapsed() >= timeout {
+            let reason = format!("idle for {} min", timeout.as_secs() / 60);
+            self.lock(reason, window, cx);
+        }
+    }
+
+    /// Cover the dashboard with the lock screen, keeping its state underneath
+    fn lock(&mut self, reason: String, window: &mut Window, cx: &mut Context<Self>) {
+        if self.lock_view.is_some() || self.current_view != AppView::Dashboard {
+            return;
+        }
+        let Some(session) = self.session.clone() else {
+            return;
+        };
+
+        audit::record(AuditEntry::new(
+            AuditAction::Locked,
+            session.environment().display_name(),
+            Some(reason),
+        ));
+        // A remembered session must come back locked if the app is quit now
+        self.set_locked_in_background(session.environment().clone(), true, cx);
+
+        let sign_in = self.sign_in.clone();
+        let lock_view = cx.new(|cx| LockView::new(session, sign_in, window, cx));
+        cx.subscribe_in(
+            &lock_view,
+            window,
+            |this, _lock, event: &UnlockEvent, _window, cx| {
+                this.unlock(event.login_response.clone(), cx);
+            },
+        )
+        .detach();
+        cx.subscribe_in(
+            &lock_view,
+            window,
+            |this, _lock, _event: &LogoutEvent, window, cx| {
+                this.logout(window, cx);
+            },
+        )
+        .detach();
+
+        self.lock_view = Some(lock_view);
+        cx.notify();
+    }
+
+    /// Remove the lock screen after the admin has logged in again
+    fn unlock(&mut self, login_response: LoginResponse, cx: &mut Context<Self>) {
+        let Some(session) = &self.session else {
+            return;
+        };
+        // The fresh login restarts the session clock
+        session.replace_tokens(login_response);
+        audit::record(AuditEntry::new(
+            AuditAction::Unlocked,
+            session.environment().display_name(),
+            None,
+        ));
+        self.set_locked_in_background(session.environment().clone(), false, cx);
+
+        self.lock_view = None;
+        self.last_activity = Instant::now();
+        cx.notify();
+    }
+
+    /// Create the session and switch to the dashboard
+    fn start_session(
+        &mut self,
+        login_response: LoginResponse,
+        environment: Environment,
+        remember: bool,
+        cx: &mut Context<Self>,
+    ) {
+        let mut session = Session::new(login_response.clone(), environment.clone());
+        if remember {
+            session = session.with_remembered(self.remembered.clone());
+            let remembered = self.remembered.clone();
+            cx.background_spawn(async move {
+                if let Err(e) = remembered.save(&environment, &login_response.refresh_token) {
+                    tracing::warn!("Failed to remember session: {}", e);
+                }
+            })
+            .detach();
+        } else {
+            self.forget_in_background(environment, cx);
+        }
+        self.session = Some(session);
+        self.last_activity = Instant::now();
+
+        // Update dashboard with session
+        self.dashboard_view.update(cx, |dashboard, cx| {
+            dashboard.set_session(self.session.clone(), cx);
+        });
+
+        self.current_view = AppView::Dashboard;
+        cx.notify();
+    }
+
+    /// Remove an environment's remembered refresh token
+    fn forget_in_background(&self, environment: Environment, cx: &mut Context<Self>) {
+        let remembered = self.remembered.clone();
+        cx.background_spawn(async move {
+            if let Err(e) = remembered.forget(&environment) {
+                tracing::warn!("Failed to forget remembered session: {}", e);
+            }
+        })
+        .detach();
+    }
+
+    /// Record whether a remembered session is locked
+    fn set_locked_in_background(
+        &self,
+        environment: Environment,
+        locked: bool,
+        cx: &mut Context<Self>,
+    ) {
+        let remembered = self.remembered.clone();
+        cx.background_spawn(async move {
+            if let Err(e) = remembered.set_locked(&environment, locked) {
+                tracing::warn!("Failed to record lock state: {}", e);
+            }
+        })
+        .detach();
+    }
+
+    /// Log in silently with a remembered refresh token, if there is one
+    fn restore_session(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let remembered = self.remembered.clone();
+        cx.spawn_in(window, async move |this, cx| {
+            let restored = cx
+                .background_spawn(async move { remembered.restore(&environment::load()) })
+                .await;
+
+            let result = match restored {
+                Some(restored) => {
+                    let client = AuthClient::new();
+                    let result =
+                        Compat::new(client.refresh(&restored.refresh_token, &restored.environment))
+                            .await;
+                    Some((restored, result))
+                }
+                None => None,
+            };
+
+            this.update_in(cx, |app, window, cx| app.finish_restore(result, window, cx))
+                .ok();
+        })
+        .detach();
+    }
+
+    fn finish_restore(
+        &mut self,
+        result: Option<(RestoredSession, Result<LoginResponse, AuthError>)>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        match result {
+            Some((restored, Ok(login_response))) => {
+                let environment = restored.environment;
+                tracing::info!(
+                    "Restored remembered session for {}",
+                    environment.display_name()
+                );
+                self.start_session(login_response, environment, true, cx);
+                // Quitting while locked must not get past the lock screen
+                if restored.locked {
+                    self.lock(
+                        "locked when the app was last closed".to_string(),
+                        window,
+                        cx,
+                    );
+                }
+                return;
+            }
+            Some((RestoredSession { environment, .. }, Err(e))) => {
+                tracing::warn!("Could not restore remembered session: {}", e);
+                // A network error may clear up; anything else means the token is no good
+                if !matches!(e, AuthError::Network(_)) {
+                    self.forget_in_background(environment, cx);
+                }
+            }
+            None => {}
         }
+        self.current_view = AppView::Login;
+        cx.notify();
     }
 }
 
 impl Render for AdminApp {
     fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
         div()
+            .relative()
             .size_full()
+            // Any input counts as activity; no re-render needed
+            .capture_key_down(cx.listener(|this, _: &KeyDownEvent, _window, _cx| {
+                this.last_activity = Instant::now();
+            }))
+            .capture_any_mouse_down(cx.listener(|this, _: &MouseDownEvent, _window, _cx| {
+                this.last_activity = Instant::now();
+            }))
+            .on_mouse_move(cx.listener(|this, _: &MouseMoveEvent, _window, _cx| {
+                this.last_activity = Instant::now();
+            }))
+            .on_scroll_wheel(cx.listener(|this, _: &ScrollWheelEvent, _window, _cx| {
+                this.last_activity = Instant::now();
+            }))
             .child(match self.current_view {
+                AppView::Restoring => div()
+                    .size_full()
+                    .flex()
+                    .items_center()
+                    .justify_center()
+                    .bg(colors::bg_primary())
+                    .text_color(colors::text_secondary())
+                    .child("Restoring session...")
+                    .into_any_element(),
                 AppView::Login => self.login_view.clone().into_any_element(),
                 AppView::Dashboard => self.dashboard_view.clone().into_any_element(),
             })
+            .children(self.lock_view.clone())
             // Render notification layer on top
             .children(Root::render_notification_layer(window, cx))
     }
@@ -88,7 +396,32 @@ impl Render for AdminApp {
 pub struct LoginSuccessEvent {
     pub login_response: LoginResponse,
     pub environment: Environment,
+    pub method: SignInMethod,
+    /// Keep the refresh token for the next launch
+    pub remember: bool,
+}
+
+/// How the admin signed in, so the lock screen can ask for the same again
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SignInMethod {
+    Password { email: String },
+    OAuth(OAuthProvider),
 }
 
+/// Event emitted when the admin chooses to log out
+pub struct LogoutEvent;
+
 impl EventEmitter<LoginSuccessEvent> for LoginView {}
+impl EventEmitter<LogoutEvent> for DashboardView {}
+impl EventEmitter<LogoutEvent> for LockView {}
+impl EventEmitter<UnlockEvent> for LockView {}
 impl EventEmitter<SessionExpiredEvent> for DashboardView {}
+impl EventEmitter<SessionExpiredEvent> for SessionWatcher {}
+impl EventEmitter<SessionExpiringEvent> for SessionWatcher {}
+impl EventEmitter<SessionExpiredEvent> for ReprocessView {}
+impl EventEmitter<SessionExpiredEvent> for CardanoView {}
+impl EventEmitter<SessionExpiredEvent> for WhitelistView {}
+impl EventEmitter<SessionExpiredEvent> for KeysView {}
+impl EventEmitter<SessionExpiredEvent> for EncryptionView {}
+impl EventEmitter<SessionExpiredEvent> for SongEarningsView {}
+impl EventEmitter<DismissEvent> for SongEarningsView {}
@@ -0,0 +1,105 @@
+//! Local Audit Log
+//!
+//! Security-relevant events on this machine (console locks and unlocks) are
+//! appended as JSON lines to `audit.log` in the data directory.
+
+use std::path::Path;
+
+use chrono::Utc;
+use serde::Serialize;
+
+use crate::storage;
+
+/// Name of the audit log in the data directory
+const LOG_FILE: &str = "audit.log";
+
+/// What happened
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditAction {
+    /// The console locked after a period of inactivity
+    Locked,
+    /// The admin re-authenticated and unlocked the console
+    Unlocked,
+    /// An unlock attempt was rejected
+    UnlockFailed,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AuditEntry {
+    /// RFC 3339, UTC
+    pub timestamp: String,
+    pub action: AuditAction,
+    pub environment: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+}
+
+impl AuditEntry {
+    pub fn new(action: AuditAction, environment: &str, detail: Option<String>) -> Self {
+        Self {
+            timestamp: Utc::now().to_rfc3339(),
+            action,
+            environment: environment.to_string(),
+            detail,
+        }
+    }
+}
+
+/// Append an entry to the audit log; failures are logged, never fatal
+pub fn record(entry: AuditEntry) {
+    tracing::info!(
+        "Audit: {:?} on {}{}",
+        entry.action,
+        entry.environment,
+        entry
+            .detail
+            .as_deref()
+            .map(|detail| format!(" ({})", detail))
+            .unwrap_or_default()
+    );
+    let Some(path) = storage::data_file(LOG_FILE) else {
+        return;
+    };
+    if let Err(e) = append(&path, &entry) {
+        tracing::warn!("Failed to write audit log: {}", e);
+    }
+}
+
+fn append(path: &Path, entry: &AuditEntry) -> Result<(), String> {
+    storage::append_json_line(path, entry)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AuditAction, AuditEntry, append};
+
+    #[test]
+    fn test_entries_are_appended_as_json_lines() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("audit.log");
+
+        append(
+            &path,
+            &AuditEntry::new(AuditAction::Locked, "Studio", Some("idle 15 min".into())),
+        )
+        .unwrap();
+        append(
+            &path,
+            &AuditEntry::new(AuditAction::Unlocked, "Studio", None),
+        )
+        .unwrap();
+
+        let contents = std::fs::read_to_string(&path).unwrap();
+        let lines: Vec<serde_json::Value> = contents
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0]["action"], "locked");
+        assert_eq!(lines[0]["environment"], "Studio");
+        assert_eq!(lines[0]["detail"], "idle 15 min");
+        assert_eq!(lines[1]["action"], "unlocked");
+        assert!(lines[1].get("detail").is_none());
+    }
+}
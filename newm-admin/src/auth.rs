@@ -1,77 +1,49 @@
 //! Authentication module for NEWM API
 //!
-//! Handles login requests to Garage and Studio environments with
+//! Handles login requests to the configured environments with
 //! JWT token management.
 //!
-//! Uses async reqwest with async-compat for Tokio compatibility
-//! within GPUI's async executor.
+//! Requests go through [`ApiClient`], so they get the same retries and
+//! error mapping as the admin endpoints.
 
+use crate::api::{self, ApiClient, ApiError, ApiErrorResponse};
+use crate::environment::Environment;
 use crate::http_client;
-use reqwest::Client;
+use crate::jwt::JwtClaims;
+use crate::oauth::{OAuthCode, OAuthProvider};
+use reqwest::{Client, Method, StatusCode};
 use serde::{Deserialize, Serialize};
 use std::fmt;
 
-/// Environment selection for API requests
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
-pub enum Environment {
-    #[default]
-    Garage,
-    Studio,
-}
-
-impl Environment {
-    /// Get the base URL for this environment
-    pub fn base_url(&self) -> &'static str {
-        match self {
-            Environment::Garage => "https://garage.newm.io",
-            Environment::Studio => "https://studio.newm.io",
-        }
-    }
-
-    /// Get the login endpoint URL
-    pub fn login_url(&self) -> String {
-        format!("{}/v1/auth/login", self.base_url())
-    }
+/// Auth endpoints, relative to the environment's base URL
+const LOGIN_PATH: &str = "/v1/auth/login";
+const CODE_PATH: &str = "/v1/auth/code";
+const JWT_PATH: &str = "/v1/auth/jwt";
+const REFRESH_PATH: &str = "/v1/auth/refresh";
 
-    /// Get the token refresh endpoint URL
-    #[allow(dead_code)]
-    pub fn refresh_url(&self) -> String {
-        format!("{}/v1/auth/refresh", self.base_url())
-    }
-
-    /// Get environment from index (for RadioGroup)
-    pub fn from_index(index: usize) -> Self {
-        match index {
-            0 => Environment::Garage,
-            1 => Environment::Studio,
-            _ => Environment::Garage,
-        }
-    }
-
-    /// Get index for RadioGroup
-    #[allow(dead_code)]
-    pub fn to_index(self) -> usize {
-        match self {
-            Environment::Garage => 0,
-            Environment::Studio => 1,
-        }
-    }
-
-    /// Display name for UI
-    #[allow(dead_code)]
-    pub fn display_name(&self) -> &'static str {
-        match self {
-            Environment::Garage => "Garage",
-            Environment::Studio => "Studio",
-        }
-    }
+/// OAuth login endpoint for a provider
+fn oauth_login_path(provider: OAuthProvider) -> String {
+    format!("{}/{}", LOGIN_PATH, provider.path())
 }
 
 /// Login request payload
 #[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
 pub struct LoginRequest {
     pub email: String,
     pub password: String,
+    /// Two-factor code sent by email
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub auth_code: Option<String>,
+}
+
+/// OAuth login request payload
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OAuthLoginRequest {
+    pub code: String,
+    pub redirect_uri: String,
+    pub code_verifier: String,
 }
 
 /// Successful login response with JWT tokens
@@ -82,14 +54,6 @@ pub struct LoginResponse {
     pub refresh_token: String,
 }
 
-/// API error response
-#[derive(Debug, Deserialize)]
-pub struct ApiErrorResponse {
-    pub code: u16,
-    pub description: String,
-    pub cause: String,
-}
-
 /// Authentication errors
 #[derive(Debug)]
 pub enum AuthError {
@@ -101,6 +65,14 @@ pub enum AuthError {
     Parse(String),
     /// User is not an admin
     NotAdmin,
+    /// Browser sign-in failed or was abandoned
+    OAuth(String),
+    /// Password accepted, but the server wants a two-factor code too
+    CodeRequired,
+    /// Two-factor code was wrong or has expired
+    InvalidCode,
+    /// Token failed the environment's signature or claim checks
+    UntrustedToken(String),
 }
 
 impl fmt::Display for AuthError {
@@ -110,12 +82,31 @@ impl fmt::Display for AuthError {
             AuthError::Http { status, message } => write!(f, "Error {}: {}", status, message),
             AuthError::Parse(msg) => write!(f, "Parse error: {}", msg),
             AuthError::NotAdmin => write!(f, "Access denied: Admin privileges required"),
+            AuthError::OAuth(msg) => write!(f, "Sign-in failed: {}", msg),
+            AuthError::UntrustedToken(msg) => write!(f, "Untrusted token: {}", msg),
+            AuthError::CodeRequired => write!(f, "A verification code is required"),
+            AuthError::InvalidCode => {
+                write!(f, "The verification code is incorrect or has expired")
+            }
         }
     }
 }
 
 impl std::error::Error for AuthError {}
 
+impl From<ApiError> for AuthError {
+    fn from(err: ApiError) -> Self {
+        match err {
+            ApiError::Network(msg) | ApiError::Ambiguous(msg) => AuthError::Network(msg),
+            ApiError::Api { status, message } => AuthError::Http { status, message },
+            ApiError::SessionExpired(msg) => AuthError::Http {
+                status: 401,
+                message: msg,
+            },
+        }
+    }
+}
+
 /// HTTP client for authentication API calls.
 /// Methods are async and should be called within an `async_compat::Compat` wrapper
 /// when used from GPUI's async context.
@@ -134,10 +125,15 @@ impl AuthClient {
     /// Create a new authentication client
     pub fn new() -> Self {
         Self {
-            client: http_client::new_client(),
+            client: http_client::shared(),
         }
     }
 
+    /// API client for `environment`'s auth endpoints
+    fn api(&self, environment: &Environment) -> ApiClient {
+        ApiClient::anonymous(self.client.clone(), environment)
+    }
+
     /// Attempt to login with email and password.
     ///
     /// This is an async method that uses reqwest. When called from GPUI,
@@ -146,116 +142,348 @@ impl AuthClient {
         &self,
         email: &str,
         password: &str,
-        environment: Environment,
+        environment: &Environment,
     ) -> Result<LoginResponse, AuthError> {
-        let url = environment.login_url();
+        self.login_with_code(email, password, None, environment)
+            .await
+    }
 
-        tracing::info!("Attempting login to {} for email: {}", url, email);
+    /// Attempt to login with email, password and, if the server asked for
+    /// one, the two-factor code from [`AuthClient::request_code`].
+    ///
+    /// This is an async method that uses reqwest. When called from GPUI,
+    /// wrap the call in `async_compat::Compat::new()` to enable Tokio compatibility.
+    pub async fn login_with_code(
+        &self,
+        email: &str,
+        password: &str,
+        code: Option<&str>,
+        environment: &Environment,
+    ) -> Result<LoginResponse, AuthError> {
+        tracing::info!(
+            "Attempting login to {} for email: {}",
+            environment.display_name(),
+            email
+        );
 
         let request = LoginRequest {
             email: email.to_string(),
             password: password.to_string(),
+            auth_code: code.map(|code| code.trim().to_string()),
         };
 
-        let response = self
-            .client
-            .post(&url)
-            .json(&request)
-            .send()
-            .await
-            .map_err(|e| AuthError::Network(e.to_string()))?;
+        let (status, body) = self
+            .api(environment)
+            .send_raw(Method::POST, LOGIN_PATH, None, &[], Some(&request))
+            .await?;
 
-        let status = response.status();
+        Self::admin_login_response(status, body, email, environment)
+    }
+
+    /// Ask the server to email a two-factor code to an existing user.
+    ///
+    /// This is an async method that uses reqwest. When called from GPUI,
+    /// wrap the call in `async_compat::Compat::new()` to enable Tokio compatibility.
+    pub async fn request_code(
+        &self,
+        email: &str,
+        environment: &Environment,
+    ) -> Result<(), AuthError> {
+        tracing::info!(
+            "Requesting verification code from {} for {}",
+            environment.display_name(),
+            email
+        );
+
+        let (status, body) = self
+            .api(environment)
+            .send_raw(
+                Method::GET,
+                CODE_PATH,
+                None,
+                &[("email", email), ("mustExists", "true")],
+                None::<&()>,
+            )
+            .await?;
 
         if status.is_success() {
-            let login_response: LoginResponse = response
-                .json()
-                .await
-                .map_err(|e| AuthError::Parse(e.to_string()))?;
+            return Ok(());
+        }
+
+        tracing::warn!("Verification code request failed: {}", body);
+        Err(login_error(status.as_u16(), body))
+    }
+
+    /// Log in with an authorization code from an OAuth provider.
+    ///
+    /// The server exchanges the code for the provider's tokens, along with
+    /// the redirect URI and PKCE verifier it was issued for. The result goes
+    /// through the same admin check as a password login.
+    pub async fn login_oauth(
+        &self,
+        code: &OAuthCode,
+        environment: &Environment,
+    ) -> Result<LoginResponse, AuthError> {
+        let provider = code.provider;
+
+        tracing::info!(
+            "Attempting {} login to {}",
+            provider.label(),
+            environment.display_name()
+        );
+
+        let request = OAuthLoginRequest {
+            code: code.code.clone(),
+            redirect_uri: code.redirect_uri.clone(),
+            code_verifier: code.code_verifier.clone(),
+        };
+
+        let (status, body) = self
+            .api(environment)
+            .send_raw(
+                Method::POST,
+                &oauth_login_path(provider),
+                None,
+                &[],
+                Some(&request),
+            )
+            .await?;
+
+        Self::admin_login_response(
+            status,
+            body,
+            &format!("{} account", provider.label()),
+            environment,
+        )
+    }
+
+    /// Parse a login response, rejecting users without the admin claim
+    fn admin_login_response(
+        status: StatusCode,
+        body: String,
+        user: &str,
+        environment: &Environment,
+    ) -> Result<LoginResponse, AuthError> {
+        if status.is_success() {
+            let login_response: LoginResponse =
+                serde_json::from_str(&body).map_err(|e| AuthError::Parse(e.to_string()))?;
 
             // Validate that the user is an admin
-            let claims =
-                crate::jwt::parse_claims(&login_response.access_token).map_err(AuthError::Parse)?;
+            let claims = access_claims(&login_response.access_token, environment)?;
 
             if claims.admin != Some(true) {
-                tracing::warn!("Login rejected for {}: user is not an admin", email);
+                tracing::warn!("Login rejected for {}: user is not an admin", user);
                 return Err(AuthError::NotAdmin);
             }
 
-            tracing::info!("Admin login successful for {}", email);
+            tracing::info!("Admin login successful for {}", user);
             Ok(login_response)
         } else {
-            // Try to parse error response
-            let error_text = response
-                .text()
-                .await
-                .unwrap_or_else(|_| "Unknown error".to_string());
-
             // Try to parse as API error
-            if let Ok(api_error) = serde_json::from_str::<ApiErrorResponse>(&error_text) {
+            if let Ok(api_error) = serde_json::from_str::<ApiErrorResponse>(&body) {
                 tracing::warn!(
                     "Login failed: [{}] {} - {}",
                     api_error.code,
                     api_error.description,
                     api_error.cause
                 );
-                Err(AuthError::Http {
-                    status: status.as_u16(),
-                    message: api_error.cause,
-                })
             } else {
-                tracing::warn!("Login failed with status {}: {}", status, error_text);
-                Err(AuthError::Http {
+                tracing::warn!("Login failed with status {}: {}", status, body);
+            }
+            Err(login_error(status.as_u16(), body))
+        }
+    }
+
+    /// Invalidate both tokens on the server.
+    ///
+    /// Servers without the logout endpoints answer 404 or 405; the tokens
+    /// then stay valid until they expire, which is logged but not an error.
+    ///
+    /// This is an async method that uses reqwest. When called from GPUI,
+    /// wrap the call in `async_compat::Compat::new()` to enable Tokio compatibility.
+    pub async fn logout(
+        &self,
+        access_token: &str,
+        refresh_token: &str,
+        environment: &Environment,
+    ) -> Result<(), AuthError> {
+        let api = self.api(environment);
+        for (path, token) in [(JWT_PATH, access_token), (REFRESH_PATH, refresh_token)] {
+            let (status, body) = api
+                .send_raw(Method::DELETE, path, Some(token), &[], None::<&()>)
+                .await?;
+
+            if status == StatusCode::NOT_FOUND || status == StatusCode::METHOD_NOT_ALLOWED {
+                tracing::info!(
+                    "{} does not support logout; tokens expire on their own",
+                    environment.display_name()
+                );
+                return Ok(());
+            }
+            // An already expired or blacklisted token is as good as logged out
+            if !status.is_success() && status != StatusCode::UNAUTHORIZED {
+                return Err(AuthError::Http {
                     status: status.as_u16(),
-                    message: error_text,
-                })
+                    message: api::error_message(body),
+                });
             }
         }
+
+        tracing::info!("Logged out of {}", environment.display_name());
+        Ok(())
     }
 
     /// Refresh access token using refresh token.
     ///
     /// This is an async method that uses reqwest. When called from GPUI,
     /// wrap the call in `async_compat::Compat::new()` to enable Tokio compatibility.
-    #[allow(dead_code)]
     pub async fn refresh(
         &self,
         refresh_token: &str,
-        environment: Environment,
+        environment: &Environment,
     ) -> Result<LoginResponse, AuthError> {
-        let url = environment.refresh_url();
-
         tracing::info!("Refreshing token for {}", environment.display_name());
 
-        let response = self
-            .client
-            .get(&url)
-            .header("Authorization", format!("Bearer {}", refresh_token))
-            .send()
-            .await
-            .map_err(|e| AuthError::Network(e.to_string()))?;
-
-        let status = response.status();
+        let (status, body) = self
+            .api(environment)
+            .send_raw(
+                Method::GET,
+                REFRESH_PATH,
+                Some(refresh_token),
+                &[],
+                None::<&()>,
+            )
+            .await?;
 
         if status.is_success() {
-            let login_response: LoginResponse = response
-                .json()
-                .await
-                .map_err(|e| AuthError::Parse(e.to_string()))?;
+            let login_response: LoginResponse =
+                serde_json::from_str(&body).map_err(|e| AuthError::Parse(e.to_string()))?;
+
+            // Admin rights may have been revoked since the token was issued
+            let claims = access_claims(&login_response.access_token, environment)?;
+
+            if claims.admin != Some(true) {
+                tracing::warn!("Token refresh rejected: user is no longer an admin");
+                return Err(AuthError::NotAdmin);
+            }
 
             tracing::info!("Token refresh successful");
             Ok(login_response)
         } else {
-            let error_text = response
-                .text()
-                .await
-                .unwrap_or_else(|_| "Unknown error".to_string());
-
-            tracing::warn!("Token refresh failed: {}", error_text);
+            tracing::warn!("Token refresh failed: {}", body);
             Err(AuthError::Http {
                 status: status.as_u16(),
-                message: error_text,
+                message: api::error_message(body),
             })
         }
     }
 }
+
+/// Claims of a freshly issued access token, checked against the
+/// environment's keys when it has token verification set up
+fn access_claims(token: &str, environment: &Environment) -> Result<JwtClaims, AuthError> {
+    match environment.token_verification() {
+        Some(verification) => verification.verify(token).map_err(|e| {
+            tracing::warn!("Rejected token from {}: {}", environment.display_name(), e);
+            AuthError::UntrustedToken(e)
+        }),
+        None => crate::jwt::parse_claims(token).map_err(AuthError::Parse),
+    }
+}
+
+/// Error code the server sends when an admin login needs a 2FA code
+const ERROR_CODE_2FA_REQUIRED: &str = "2FA_REQUIRED";
+/// Error code the server sends when the 2FA code was wrong or expired
+const ERROR_CODE_2FA_FAILED: &str = "2FA_FAILED";
+
+/// Map a failed login response to an error, recognising the two-factor
+/// rejections by their error code
+fn login_error(status: u16, body: String) -> AuthError {
+    match (status, api::error_code(&body).as_deref()) {
+        (403, Some(ERROR_CODE_2FA_REQUIRED)) => AuthError::CodeRequired,
+        (403, Some(ERROR_CODE_2FA_FAILED)) => AuthError::InvalidCode,
+        _ => AuthError::Http {
+            status,
+            message: api::error_message(body),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AuthError, LoginRequest, OAuthProvider, login_error, oauth_login_path};
+
+    #[test]
+    fn test_login_request_omits_missing_code() {
+        let request = LoginRequest {
+            email: "a@newm.io".to_string(),
+            password: "pw".to_string(),
+            auth_code: None,
+        };
+        assert_eq!(
+            serde_json::to_string(&request).unwrap(),
+            r#"{"email":"a@newm.io","password":"pw"}"#
+        );
+
+        let request = LoginRequest {
+            auth_code: Some("123456".to_string()),
+            ..request
+        };
+        assert!(
+            serde_json::to_string(&request)
+                .unwrap()
+                .ends_with(r#""authCode":"123456"}"#)
+        );
+    }
+
+    #[test]
+    fn test_oauth_login_path() {
+        assert_eq!(
+            oauth_login_path(OAuthProvider::Google),
+            "/v1/auth/login/google"
+        );
+    }
+
+    #[test]
+    fn test_login_error() {
+        let body = |cause: &str| {
+            format!(
+                r#"{{"code": 403, "description": "Forbidden", "cause": "{}"}}"#,
+                cause
+            )
+        };
+        let coded = |cause: &str, code: &str| {
+            format!(
+                r#"{{"code": 403, "description": "Forbidden", "cause": "{}", "errorCode": "{}"}}"#,
+                cause, code
+            )
+        };
+        assert!(matches!(
+            login_error(403, coded("2FA code required", "2FA_REQUIRED")),
+            AuthError::CodeRequired
+        ));
+        assert!(matches!(
+            login_error(403, coded("2FA failed", "2FA_FAILED")),
+            AuthError::InvalidCode
+        ));
+        // The message alone is not enough
+        match login_error(403, body("2FA code required")) {
+            AuthError::Http { status, message } => {
+                assert_eq!(status, 403);
+                assert_eq!(message, "2FA code required");
+            }
+            e => panic!("unexpected {}", e),
+        }
+        match login_error(401, body("Invalid password")) {
+            AuthError::Http { status, message } => {
+                assert_eq!(status, 401);
+                assert_eq!(message, "Invalid password");
+            }
+            e => panic!("unexpected {}", e),
+        }
+        match login_error(502, "Bad Gateway".to_string()) {
+            AuthError::Http { message, .. } => assert_eq!(message, "Bad Gateway"),
+            e => panic!("unexpected {}", e),
+        }
+    }
+}
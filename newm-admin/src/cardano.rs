@@ -0,0 +1,411 @@
+//! Cardano Address Helpers
+//!
+//! Local decoding of bech32 stake and base addresses, so malformed addresses
+//! and addresses for the wrong network are caught before anything is sent
+//! to the server.
+
+use bech32::primitives::decode::CheckedHrpstring;
+use bech32::{Bech32, Hrp};
+use serde::Deserialize;
+
+/// Length of a stake or payment credential hash (Blake2b-224)
+const CREDENTIAL_LENGTH: usize = 28;
+
+/// Length of a stake address: header byte + stake credential
+const STAKE_ADDRESS_LENGTH: usize = 1 + CREDENTIAL_LENGTH;
+
+/// Length of a base address: header byte + payment credential + stake credential
+const BASE_ADDRESS_LENGTH: usize = 1 + 2 * CREDENTIAL_LENGTH;
+
+/// Address type nibble of an enterprise address with a key hash credential
+const ENTERPRISE_KEY_TYPE: u8 = 0b0110;
+
+/// Address type nibble of a stake address with a key hash credential
+const STAKE_KEY_TYPE: u8 = 0b1110;
+
+/// Address type nibble of a stake address with a script hash credential
+const STAKE_SCRIPT_TYPE: u8 = 0b1111;
+
+/// Cardano network an address belongs to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Network {
+    Testnet,
+    Mainnet,
+}
+
+impl Network {
+    const ALL: [Network; 2] = [Network::Testnet, Network::Mainnet];
+
+    /// Network ID stored in the low nibble of the address header
+    pub fn id(&self) -> u8 {
+        match self {
+            Network::Testnet => 0,
+            Network::Mainnet => 1,
+        }
+    }
+
+    fn from_id(id: u8) -> Option<Self> {
+        match id {
+            0 => Some(Network::Testnet),
+            1 => Some(Network::Mainnet),
+            _ => None,
+        }
+    }
+
+    /// Bech32 prefix for stake addresses on this network
+    pub fn stake_hrp(&self) -> &'static str {
+        match self {
+            Network::Testnet => "stake_test",
+            Network::Mainnet => "stake",
+        }
+    }
+
+    /// Bech32 prefix for payment addresses on this network
+    pub fn address_hrp(&self) -> &'static str {
+        match self {
+            Network::Testnet => "addr_test",
+            Network::Mainnet => "addr",
+        }
+    }
+}
+
+impl std::fmt::Display for Network {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Network::Testnet => write!(f, "Testnet"),
+            Network::Mainnet => write!(f, "Mainnet"),
+        }
+    }
+}
+
+/// Kind of credential behind a stake address
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StakeCredential {
+    Key,
+    Script,
+}
+
+/// Error from decoding or checking a Cardano address
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CardanoError {
+    /// Not valid bech32 (bad characters, mixed case or checksum)
+    Bech32(String),
+    /// Bech32 prefix is not the expected kind of address
+    UnexpectedPrefix(String),
+    /// Decoded payload has the wrong number of bytes
+    InvalidLength { expected: usize, actual: usize },
+    /// Header byte does not describe the expected kind of address
+    InvalidHeader(u8),
+    /// Address belongs to a different network than expected
+    NetworkMismatch { expected: Network, actual: Network },
+}
+
+impl std::fmt::Display for CardanoError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CardanoError::Bech32(msg) => write!(f, "Invalid bech32: {}", msg),
+            CardanoError::UnexpectedPrefix(prefix) => {
+                write!(f, "Unexpected address prefix '{}'", prefix)
+            }
+            CardanoError::InvalidLength { expected, actual } => {
+                write!(f, "Address has {} bytes, expected {}", actual, expected)
+            }
+            CardanoError::InvalidHeader(header) => {
+                write!(f, "Unexpected address header 0x{:02x}", header)
+            }
+            CardanoError::NetworkMismatch { expected, actual } => {
+                write!(
+                    f,
+                    "Address is for {} but the session is connected to {}",
+                    actual, expected
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for CardanoError {}
+
+/// A decoded stake (reward) address
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StakeAddress {
+    pub network: Network,
+    pub credential: StakeCredential,
+    pub hash: [u8; CREDENTIAL_LENGTH],
+}
+
+impl StakeAddress {
+    /// Decode a bech32 `stake1...` or `stake_test1...` address.
+    ///
+    /// Checks the checksum, the payload length, the header type and that the
+    /// header's network ID agrees with the prefix.
+    pub fn parse(address: &str) -> Result<Self, CardanoError> {
+        let (hrp, bytes) = decode(address.trim())?;
+
+        let prefix_network = Network::ALL
+            .into_iter()
+            .find(|n| n.stake_hrp() == hrp.as_str())
+            .ok_or_else(|| CardanoError::UnexpectedPrefix(hrp.to_string()))?;
+        if bytes.len() != STAKE_ADDRESS_LENGTH {
+            return Err(CardanoError::InvalidLength {
+                expected: STAKE_ADDRESS_LENGTH,
+                actual: bytes.len(),
+            });
+        }
+
+        let header = bytes[0];
+        let credential = match header >> 4 {
+            STAKE_KEY_TYPE => StakeCredential::Key,
+            STAKE_SCRIPT_TYPE => StakeCredential::Script,
+            _ => return Err(CardanoError::InvalidHeader(header)),
+        };
+        let network = Network::from_id(header & 0x0f).ok_or(CardanoError::InvalidHeader(header))?;
+        if network != prefix_network {
+            return Err(CardanoError::InvalidHeader(header));
+        }
+
+        let mut hash = [0u8; CREDENTIAL_LENGTH];
+        hash.copy_from_slice(&bytes[1..]);
+        Ok(Self {
+            network,
+            credential,
+            hash,
+        })
+    }
+
+    /// Header byte for this address
+    fn header(&self) -> u8 {
+        let kind = match self.credential {
+            StakeCredential::Key => STAKE_KEY_TYPE,
+            StakeCredential::Script => STAKE_SCRIPT_TYPE,
+        };
+        (kind << 4) | self.network.id()
+    }
+
+    /// Fail if the address is not for the given network
+    pub fn check_network(&self, expected: Network) -> Result<(), CardanoError> {
+        if self.network == expected {
+            Ok(())
+        } else {
+            Err(CardanoError::NetworkMismatch {
+                expected,
+                actual: self.network,
+            })
+        }
+    }
+}
+
+impl std::fmt::Display for StakeAddress {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut bytes = Vec::with_capacity(STAKE_ADDRESS_LENGTH);
+        bytes.push(self.header());
+        bytes.extend_from_slice(&self.hash);
+        let hrp = Hrp::parse_unchecked(self.network.stake_hrp());
+        let encoded = bech32::encode::<Bech32>(hrp, &bytes).map_err(|_| std::fmt::Error)?;
+        f.write_str(&encoded)
+    }
+}
+
+/// Derive the stake address of a base address (`addr1...` / `addr_test1...`).
+///
+/// Mirrors the server's `extractStakeAddress`: the stake credential is taken
+/// from the last 28 bytes and always encoded with a key-hash header for
+/// `network`. The address itself must also be for `network`.
+pub fn extract_stake_address(
+    address: &str,
+    network: Network,
+) -> Result<StakeAddress, CardanoError> {
+    let (hrp, bytes) = decode(address.trim())?;
+
+    let prefix_network = Network::ALL
+        .into_iter()
+        .find(|n| n.address_hrp() == hrp.as_str())
+        .ok_or_else(|| CardanoError::UnexpectedPrefix(hrp.to_string()))?;
+    if bytes.len() != BASE_ADDRESS_LENGTH {
+        return Err(CardanoError::InvalidLength {
+            expected: BASE_ADDRESS_LENGTH,
+            actual: bytes.len(),
+        });
+    }
+
+    let header = bytes[0];
+    // Types 0-3 are base addresses (key or script for each part)
+    if header >> 4 > 3 || Network::from_id(header & 0x0f) != Some(prefix_network) {
+        return Err(CardanoError::InvalidHeader(header));
+    }
+    if prefix_network != network {
+        return Err(CardanoError::NetworkMismatch {
+            expected: network,
+            actual: prefix_network,
+        });
+    }
+
+    let mut hash = [0u8; CREDENTIAL_LENGTH];
+    hash.copy_from_slice(&bytes[1 + CREDENTIAL_LENGTH..]);
+    Ok(StakeAddress {
+        network,
+        credential: StakeCredential::Key,
+        hash,
+    })
+}
+
+/// Resolve user input to a stake address on `network`.
+///
+/// Stake addresses are decoded as-is; base addresses have their stake address
+/// extracted (like the server's `asStakeAddress`). Either way the result must
+/// be for `network`.
+pub fn as_stake_address(address: &str, network: Network) -> Result<StakeAddress, CardanoError> {
+    let address = address.trim();
+    if address.to_lowercase().starts_with("stake") {
+        let stake_address = StakeAddress::parse(address)?;
+        stake_address.check_network(network)?;
+        Ok(stake_address)
+    } else {
+        extract_stake_address(address, network)
+    }
+}
+
+/// Encode the enterprise (no stake part) address of a payment key hash.
+///
+/// This is the address the server derives for keys it holds.
+pub fn enterprise_address(key_hash: &[u8; CREDENTIAL_LENGTH], network: Network) -> String {
+    let mut bytes = Vec::with_capacity(1 + CREDENTIAL_LENGTH);
+    bytes.push((ENTERPRISE_KEY_TYPE << 4) | network.id());
+    bytes.extend_from_slice(key_hash);
+    let hrp = Hrp::parse_unchecked(network.address_hrp());
+    bech32::encode::<Bech32>(hrp, &bytes).expect("enterprise address is within bech32 limits")
+}
+
+/// Decode a bech32 string (Cardano uses the original bech32 checksum)
+fn decode(value: &str) -> Result<(Hrp, Vec<u8>), CardanoError> {
+    let checked =
+        CheckedHrpstring::new::<Bech32>(value).map_err(|e| CardanoError::Bech32(e.to_string()))?;
+    Ok((checked.hrp(), checked.byte_iter().collect()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Test vectors from CIP-19
+    const BASE_MAINNET: &str = "addr1qx2fxv2umyhttkxyxp8x0dlpdt3k6cwng5pxj3jhsydzer3n0d3vllmyqwsx5wktcd8cc3sq835lu7drv2xwl2wywfgse35a3x";
+    const BASE_SCRIPT_KEY_MAINNET: &str = "addr1z8phkx6acpnf78fuvxn0mkew3l0fd058hzquvz7w36x4gten0d3vllmyqwsx5wktcd8cc3sq835lu7drv2xwl2wywfgs9yc0hh";
+    const BASE_TESTNET: &str = "addr_test1qz2fxv2umyhttkxyxp8x0dlpdt3k6cwng5pxj3jhsydzer3n0d3vllmyqwsx5wktcd8cc3sq835lu7drv2xwl2wywfgs68faae";
+    const STAKE_MAINNET: &str = "stake1uyehkck0lajq8gr28t9uxnuvgcqrc6070x3k9r8048z8y5gh6ffgw";
+    const STAKE_TESTNET: &str = "stake_test1uqehkck0lajq8gr28t9uxnuvgcqrc6070x3k9r8048z8y5gssrtvn";
+    const STAKE_SCRIPT_MAINNET: &str =
+        "stake178phkx6acpnf78fuvxn0mkew3l0fd058hzquvz7w36x4gtcccycj5";
+    const ENTERPRISE_MAINNET: &str = "addr1vx2fxv2umyhttkxyxp8x0dlpdt3k6cwng5pxj3jhsydzers66hrl8";
+    const ENTERPRISE_TESTNET: &str =
+        "addr_test1vz2fxv2umyhttkxyxp8x0dlpdt3k6cwng5pxj3jhsydzerspjrlsz";
+
+    #[test]
+    fn test_parse_stake_address() {
+        let mainnet = StakeAddress::parse(STAKE_MAINNET).unwrap();
+        assert_eq!(mainnet.network, Network::Mainnet);
+        assert_eq!(mainnet.credential, StakeCredential::Key);
+        assert_eq!(mainnet.to_string(), STAKE_MAINNET);
+
+        let testnet = StakeAddress::parse(STAKE_TESTNET).unwrap();
+        assert_eq!(testnet.network, Network::Testnet);
+        assert_eq!(testnet.hash, mainnet.hash);
+        assert_eq!(testnet.to_string(), STAKE_TESTNET);
+
+        let script = StakeAddress::parse(STAKE_SCRIPT_MAINNET).unwrap();
+        assert_eq!(script.credential, StakeCredential::Script);
+        assert_eq!(script.to_string(), STAKE_SCRIPT_MAINNET);
+    }
+
+    #[test]
+    fn test_parse_stake_address_rejects_invalid() {
+        // Bad checksum (last character changed)
+        assert!(matches!(
+            StakeAddress::parse("stake1uyehkck0lajq8gr28t9uxnuvgcqrc6070x3k9r8048z8y5gh6ffgx"),
+            Err(CardanoError::Bech32(_))
+        ));
+        // A base address is not a stake address
+        assert!(matches!(
+            StakeAddress::parse(BASE_MAINNET),
+            Err(CardanoError::UnexpectedPrefix(_))
+        ));
+        // Mainnet payload under the testnet prefix
+        let mainnet = StakeAddress::parse(STAKE_MAINNET).unwrap();
+        let mut bytes = vec![mainnet.header()];
+        bytes.extend_from_slice(&mainnet.hash);
+        let relabelled =
+            bech32::encode::<Bech32>(Hrp::parse_unchecked("stake_test"), &bytes).unwrap();
+        assert!(matches!(
+            StakeAddress::parse(&relabelled),
+            Err(CardanoError::InvalidHeader(0xe1))
+        ));
+        assert!(StakeAddress::parse("").is_err());
+    }
+
+    #[test]
+    fn test_check_network() {
+        let mainnet = StakeAddress::parse(STAKE_MAINNET).unwrap();
+        assert!(mainnet.check_network(Network::Mainnet).is_ok());
+        assert_eq!(
+            mainnet.check_network(Network::Testnet),
+            Err(CardanoError::NetworkMismatch {
+                expected: Network::Testnet,
+                actual: Network::Mainnet,
+            })
+        );
+    }
+
+    #[test]
+    fn test_extract_stake_address() {
+        let stake = extract_stake_address(BASE_MAINNET, Network::Mainnet).unwrap();
+        assert_eq!(stake.to_string(), STAKE_MAINNET);
+
+        let stake = extract_stake_address(BASE_SCRIPT_KEY_MAINNET, Network::Mainnet).unwrap();
+        assert_eq!(stake.to_string(), STAKE_MAINNET);
+
+        let stake = extract_stake_address(BASE_TESTNET, Network::Testnet).unwrap();
+        assert_eq!(stake.to_string(), STAKE_TESTNET);
+
+        assert!(matches!(
+            extract_stake_address(BASE_MAINNET, Network::Testnet),
+            Err(CardanoError::NetworkMismatch { .. })
+        ));
+        assert!(matches!(
+            extract_stake_address(STAKE_MAINNET, Network::Mainnet),
+            Err(CardanoError::UnexpectedPrefix(_))
+        ));
+    }
+
+    #[test]
+    fn test_as_stake_address() {
+        assert_eq!(
+            as_stake_address(STAKE_TESTNET, Network::Testnet)
+                .unwrap()
+                .to_string(),
+            STAKE_TESTNET
+        );
+        assert_eq!(
+            as_stake_address(BASE_TESTNET, Network::Testnet)
+                .unwrap()
+                .to_string(),
+            STAKE_TESTNET
+        );
+        assert!(as_stake_address(STAKE_TESTNET, Network::Mainnet).is_err());
+    }
+
+    #[test]
+    fn test_enterprise_address() {
+        let (_, bytes) = decode(ENTERPRISE_MAINNET).unwrap();
+        let mut hash = [0u8; CREDENTIAL_LENGTH];
+        hash.copy_from_slice(&bytes[1..]);
+
+        assert_eq!(
+            enterprise_address(&hash, Network::Mainnet),
+            ENTERPRISE_MAINNET
+        );
+        assert_eq!(
+            enterprise_address(&hash, Network::Testnet),
+            ENTERPRISE_TESTNET
+        );
+    }
+}
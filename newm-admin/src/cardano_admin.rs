@@ -0,0 +1,651 @@
+//! Cardano admin API client for NEWM Admin
+//!
+//! Handles the admin-only `/v1/cardano` endpoints with automatic session
+//! management, plus the local record of what has been whitelisted and which
+//! keys have been uploaded. Encryption parameters are never recorded.
+
+use std::collections::BTreeMap;
+
+use reqwest::Client;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use crate::api::{ApiClient, ApiError};
+use crate::cardano::{self, CardanoError, Network, StakeAddress};
+use crate::cli_key::{CliKeyPair, KeyError};
+use crate::environment::Environment;
+use crate::http_client;
+use crate::session::Session;
+use crate::songs::is_uuid;
+use crate::storage;
+
+/// Request body for adding a script address to the whitelist
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ScriptAddressWhitelistRequest<'a> {
+    script_address: &'a str,
+}
+
+/// Error from Cardano admin API operations
+#[derive(Debug)]
+pub enum CardanoAdminError {
+    /// Session expired, user must re-login
+    SessionExpired(String),
+    /// API returned an error
+    Api { status: u16, message: String },
+    /// Network or other error
+    Network(String),
+    /// A write was sent but its outcome is unknown
+    Ambiguous(String),
+    /// Request was rejected before being sent
+    Invalid(String),
+}
+
+impl std::fmt::Display for CardanoAdminError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CardanoAdminError::SessionExpired(msg) => write!(f, "Session expired: {}", msg),
+            CardanoAdminError::Api { status, message } => {
+                write!(f, "API error {}: {}", status, message)
+            }
+            CardanoAdminError::Network(msg) => write!(f, "Network error: {}", msg),
+            CardanoAdminError::Ambiguous(msg) => {
+                write!(f, "Outcome unknown, check before retrying: {}", msg)
+            }
+            CardanoAdminError::Invalid(msg) => write!(f, "Invalid request: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for CardanoAdminError {}
+
+impl From<ApiError> for CardanoAdminError {
+    fn from(err: ApiError) -> Self {
+        match err {
+            ApiError::SessionExpired(msg) => CardanoAdminError::SessionExpired(msg),
+            ApiError::Api { status, message } => CardanoAdminError::Api { status, message },
+            ApiError::Network(msg) => CardanoAdminError::Network(msg),
+            ApiError::Ambiguous(msg) => CardanoAdminError::Ambiguous(msg),
+        }
+    }
+}
+
+impl From<CardanoError> for CardanoAdminError {
+    fn from(err: CardanoError) -> Self {
+        CardanoAdminError::Invalid(err.to_string())
+    }
+}
+
+impl From<KeyError> for CardanoAdminError {
+    fn from(err: KeyError) -> Self {
+        CardanoAdminError::Invalid(err.to_string())
+    }
+}
+
+/// What the server will whitelist for an entered address
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WhitelistTarget {
+    /// Address as entered (trimmed)
+    pub address: String,
+    /// Stake address the server whitelists alongside it
+    pub stake_address: StakeAddress,
+}
+
+impl WhitelistTarget {
+    /// Validate an address the way the server will process it.
+    ///
+    /// Stake addresses are whitelisted as-is. Anything else must be a base
+    /// address, whose stake address the server extracts and whitelists too.
+    pub fn resolve(address: &str, network: Network) -> Result<Self, CardanoError> {
+        let address = address.trim();
+        let stake_address = cardano::as_stake_address(address, network)?;
+        Ok(Self {
+            address: address.to_string(),
+            stake_address,
+        })
+    }
+}
+
+/// Minimum salt length in hex characters accepted by the server (8 bytes)
+const MIN_SALT_HEX_LENGTH: usize = 16;
+
+/// Recommended salt length in hex characters (16 bytes)
+const RECOMMENDED_SALT_HEX_LENGTH: usize = 32;
+
+/// The server requires passwords strictly longer than this
+const MIN_PASSWORD_LENGTH: usize = 30;
+
+/// Recommended number of distinct characters in the password
+const RECOMMENDED_DISTINCT_CHARACTERS: usize = 15;
+
+/// One strength check on the encryption salt or password
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StrengthCheck {
+    pub label: &'static str,
+    pub passed: bool,
+    /// Required checks mirror the server's validation; the rest are advice
+    pub required: bool,
+}
+
+/// Salt and password the server uses to encrypt the keys it holds.
+///
+/// Both values are secrets: the type never prints them and nothing in the
+/// app logs or persists them.
+#[derive(Clone, Serialize)]
+pub struct EncryptionParams {
+    s: String,
+    password: String,
+}
+
+impl std::fmt::Debug for EncryptionParams {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("EncryptionParams { .. }")
+    }
+}
+
+impl EncryptionParams {
+    /// Run every strength check on a salt and password
+    pub fn checks(salt: &str, password: &str) -> Vec<StrengthCheck> {
+        let is_hex = !salt.is_empty()
+            && salt.len().is_multiple_of(2)
+            && salt.chars().all(|c| c.is_ascii_hexdigit());
+        let length = password.chars().count();
+        let mut distinct: Vec<char> = password.chars().collect();
+        distinct.sort_unstable();
+        distinct.dedup();
+
+        vec![
+            StrengthCheck {
+                label: "Salt is hex (an even number of 0-9, a-f)",
+                passed: is_hex,
+                required: true,
+            },
+            StrengthCheck {
+                label: "Salt is at least 8 bytes (16 hex characters)",
+                passed: salt.len() >= MIN_SALT_HEX_LENGTH,
+                required: true,
+            },
+            StrengthCheck {
+                label: "Salt is at least 16 bytes (32 hex characters)",
+                passed: salt.len() >= RECOMMENDED_SALT_HEX_LENGTH,
+                required: false,
+            },
+            StrengthCheck {
+                label: "Password is longer than 30 characters",
+                passed: length > MIN_PASSWORD_LENGTH,
+                required: true,
+            },
+            StrengthCheck {
+                label: "Password has upper case, lower case and a number",
+                passed: password.chars().any(|c| c.is_ascii_uppercase())
+                    && password.chars().any(|c| c.is_ascii_lowercase())
+                    && password.chars().any(|c| c.is_ascii_digit()),
+                required: true,
+            },
+            StrengthCheck {
+                label: "Password has no line breaks or control characters",
+                passed: !password.chars().any(char::is_control),
+                required: true,
+            },
+            StrengthCheck {
+                label: "Password has a symbol",
+                passed: password
+                    .chars()
+                    .any(|c| !c.is_alphanumeric() && !c.is_whitespace()),
+                required: false,
+            },
+            StrengthCheck {
+                label: "Password has at least 15 distinct characters",
+                passed: distinct.len() >= RECOMMENDED_DISTINCT_CHARACTERS,
+                required: false,
+            },
+        ]
+    }
+
+    /// Build the request, failing with the first required check that does not pass
+    pub fn new(salt: &str, password: &str) -> Result<Self, String> {
+        if let Some(failed) = Self::checks(salt, password)
+            .into_iter()
+            .find(|check| check.required && !check.passed)
+        {
+            return Err(failed.label.to_string());
+        }
+        Ok(Self {
+            s: salt.to_string(),
+            password: password.to_string(),
+        })
+    }
+}
+
+/// A record kept in an [`EnvironmentHistory`]
+pub trait HistoryRecord: Clone + Serialize + DeserializeOwned {
+    /// File in the app data directory holding the history
+    const FILE: &'static str;
+
+    /// Whether two records describe the same thing
+    fn same_entry(&self, other: &Self) -> bool;
+}
+
+/// A script address that was successfully whitelisted from this machine
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WhitelistRecord {
+    pub address: String,
+    pub stake_address: String,
+    /// Local time the server accepted the request ("YYYY-MM-DD HH:MM:SS")
+    pub whitelisted_at: String,
+}
+
+impl HistoryRecord for WhitelistRecord {
+    const FILE: &'static str = "whitelist_history.json";
+
+    fn same_entry(&self, other: &Self) -> bool {
+        self.address.eq_ignore_ascii_case(&other.address)
+    }
+}
+
+/// A key pair that was successfully uploaded from this machine
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KeyRecord {
+    pub name: String,
+    pub address: String,
+    /// Local time the server accepted the key ("YYYY-MM-DD HH:MM:SS")
+    pub uploaded_at: String,
+}
+
+impl HistoryRecord for KeyRecord {
+    const FILE: &'static str = "key_history.json";
+
+    // Key names are unique (case-sensitive) on the server
+    fn same_entry(&self, other: &Self) -> bool {
+        self.name == other.name
+    }
+}
+
+/// Local record of admin actions, keyed by environment
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(bound = "R: HistoryRecord")]
+pub struct EnvironmentHistory<R> {
+    environments: BTreeMap<String, Vec<R>>,
+}
+
+pub type WhitelistHistory = EnvironmentHistory<WhitelistRecord>;
+pub type KeyHistory = EnvironmentHistory<KeyRecord>;
+
+impl<R> Default for EnvironmentHistory<R> {
+    fn default() -> Self {
+        Self {
+            environments: BTreeMap::new(),
+        }
+    }
+}
+
+impl<R: HistoryRecord> EnvironmentHistory<R> {
+    /// Load the history from the app data directory.
+    ///
+    /// A missing or unreadable file yields an empty history; the record is a
+    /// convenience and must never block the action itself.
+    pub fn load() -> Self {
+        let Some(path) = storage::data_file(R::FILE) else {
+            return Self::default();
+        };
+        match storage::read_json(&path) {
+            Ok(history) => history.unwrap_or_default(),
+            Err(e) => {
+                tracing::warn!("Ignoring {}: {}", R::FILE, e);
+                Self::default()
+            }
+        }
+    }
+
+    /// Save the history to the app data directory
+    pub fn save(&self) -> Result<(), String> {
+        let path =
+            storage::data_file(R::FILE).ok_or_else(|| "No data directory available".to_string())?;
+        storage::write_json(&path, self)
+    }
+
+    /// Records for an environment, most recent first
+    pub fn records(&self, environment: &Environment) -> &[R] {
+        self.environments
+            .get(environment.display_name())
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    /// Add a record for an environment.
+    ///
+    /// Repeating an action moves its record to the top instead of adding a
+    /// duplicate entry.
+    pub fn add(&mut self, environment: &Environment, record: R) {
+        let records = self
+            .environments
+            .entry(environment.display_name().to_string())
+            .or_default();
+        records.retain(|r| !r.same_entry(&record));
+        records.insert(0, record);
+    }
+}
+
+/// Client for Cardano admin API operations
+#[derive(Clone)]
+pub struct CardanoAdminClient {
+    client: Client,
+}
+
+impl Default for CardanoAdminClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CardanoAdminClient {
+    /// Create a new Cardano admin client
+    pub fn new() -> Self {
+        Self {
+            client: http_client::shared(),
+        }
+    }
+
+    fn api(&self, session: &Session) -> ApiClient {
+        ApiClient::new(self.client.clone(), session)
+    }
+
+    /// Add a script address to the server's claim whitelist.
+    ///
+    /// The address is validated against the session's network first. For a
+    /// base address the server also whitelists its stake address.
+    ///
+    /// # Arguments
+    /// * `session` - The authenticated session (will auto-refresh token if needed)
+    /// * `address` - Stake or base address to whitelist
+    ///
+    /// # Returns
+    /// * `Ok(WhitelistTarget)` describing what the server whitelisted
+    /// * `Err(CardanoAdminError::Invalid)` if the address is malformed or for the wrong network
+    /// * `Err(CardanoAdminError::SessionExpired)` if token refresh fails
+    /// * `Err(CardanoAdminError::Api)` for API errors
+    pub async fn whitelist_script_address(
+        &self,
+        session: &Session,
+        address: &str,
+    ) -> Result<WhitelistTarget, CardanoAdminError> {
+        let target = WhitelistTarget::resolve(address, session.environment().network())?;
+
+        tracing::info!(
+            "Whitelisting script address {} (stake address {})",
+            target.address,
+            target.stake_address
+        );
+
+        let body = ScriptAddressWhitelistRequest {
+            script_address: &target.address,
+        };
+        self.api(session)
+            .post::<_, ()>("/v1/cardano/scriptAddressWhitelist", &body)
+            .await?;
+
+        tracing::info!("Whitelisted {}", target.address);
+        Ok(target)
+    }
+
+    /// Set the encryption salt and password for an environment.
+    ///
+    /// This is a one-time bootstrap step: the server refuses if either value
+    /// is already configured.
+    ///
+    /// # Arguments
+    /// * `session` - The authenticated session (will auto-refresh token if needed)
+    /// * `params` - Validated salt and password, see [`EncryptionParams::new`]
+    ///
+    /// # Returns
+    /// * `Ok(())` once the server has stored the parameters
+    /// * `Err(CardanoAdminError::SessionExpired)` if token refresh fails
+    /// * `Err(CardanoAdminError::Api)` for API errors
+    pub async fn save_encryption_params(
+        &self,
+        session: &Session,
+        params: &EncryptionParams,
+    ) -> Result<(), CardanoAdminError> {
+        tracing::info!(
+            "Submitting encryption parameters to {}",
+            session.environment().display_name()
+        );
+
+        self.api(session)
+            .post::<_, ()>("/v1/cardano/encryption", params)
+            .await?;
+
+        tracing::info!("Encryption parameters saved");
+        Ok(())
+    }
+
+    /// Upload a named key pair for the server to hold (encrypted at rest).
+    ///
+    /// The server refuses a name that is already taken.
+    ///
+    /// # Arguments
+    /// * `session` - The authenticated session (will auto-refresh token if needed)
+    /// * `key_pair` - Validated key pair, see [`CliKeyPair::new`]
+    ///
+    /// # Returns
+    /// * `Ok(())` once the server has stored the key
+    /// * `Err(CardanoAdminError::SessionExpired)` if token refresh fails
+    /// * `Err(CardanoAdminError::Api)` for API errors, including duplicate names
+    pub async fn upload_key(
+        &self,
+        session: &Session,
+        key_pair: &CliKeyPair,
+    ) -> Result<(), CardanoAdminError> {
+        tracing::info!("Uploading key pair '{}'", key_pair.name);
+
+        self.api(session)
+            .post::<_, ()>("/v1/cardano/key", key_pair)
+            .await?;
+
+        tracing::info!("Uploaded key pair '{}'", key_pair.name);
+        Ok(())
+    }
+
+    /// Download a key pair, including its decrypted signing key.
+    ///
+    /// The returned pair is named after the key ID.
+    ///
+    /// # Arguments
+    /// * `session` - The authenticated session (will auto-refresh token if needed)
+    /// * `key_id` - UUID of the key
+    ///
+    /// # Returns
+    /// * `Ok(CliKeyPair)` with the signing and verification keys
+    /// * `Err(CardanoAdminError::Invalid)` if `key_id` is not a UUID
+    /// * `Err(CardanoAdminError::SessionExpired)` if token refresh fails
+    /// * `Err(CardanoAdminError::Api)` for API errors
+    pub async fn get_key(
+        &self,
+        session: &Session,
+        key_id: &str,
+    ) -> Result<CliKeyPair, CardanoAdminError> {
+        let key_id = key_id.trim();
+        if !is_uuid(key_id) {
+            return Err(CardanoAdminError::Invalid(format!(
+                "'{}' is not a valid key ID",
+                key_id
+            )));
+        }
+
+        tracing::info!("Downloading key {}", key_id);
+
+        let key_pair = self
+            .api(session)
+            .get(&format!("/v1/cardano/key/{}", key_id))
+            .await?;
+        Ok(key_pair)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // CIP-19 test vectors
+    const STAKE: &str = "stake1uyehkck0lajq8gr28t9uxnuvgcqrc6070x3k9r8048z8y5gh6ffgw";
+    const STAKE_TEST: &str = "stake_test1uqehkck0lajq8gr28t9uxnuvgcqrc6070x3k9r8048z8y5gssrtvn";
+    const BASE_SCRIPT_KEY: &str = "addr1z8phkx6acpnf78fuvxn0mkew3l0fd058hzquvz7w36x4gten0d3vllmyqwsx5wktcd8cc3sq835lu7drv2xwl2wywfgs9yc0hh";
+
+    fn record(address: &str) -> WhitelistRecord {
+        WhitelistRecord {
+            address: address.to_string(),
+            stake_address: STAKE.to_string(),
+            whitelisted_at: "2025-01-31 12:00:00".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_resolve_stake_address_is_whitelisted_as_is() {
+        let target = WhitelistTarget::resolve(&format!("  {}\n", STAKE), Network::Mainnet).unwrap();
+        assert_eq!(target.address, STAKE);
+        assert_eq!(target.stake_address.to_string(), STAKE);
+    }
+
+    #[test]
+    fn test_resolve_script_address_adds_its_stake_address() {
+        let target = WhitelistTarget::resolve(BASE_SCRIPT_KEY, Network::Mainnet).unwrap();
+        assert_eq!(target.address, BASE_SCRIPT_KEY);
+        assert_eq!(target.stake_address.to_string(), STAKE);
+    }
+
+    #[test]
+    fn test_resolve_rejects_wrong_network() {
+        assert!(matches!(
+            WhitelistTarget::resolve(STAKE_TEST, Network::Mainnet),
+            Err(CardanoError::NetworkMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn test_resolve_rejects_garbage() {
+        assert!(WhitelistTarget::resolve("not-an-address", Network::Mainnet).is_err());
+    }
+
+    const SALT: &str = "00112233445566778899aabbccddeeff";
+    const PASSWORD: &str = "Correct-Horse-Battery-Staple-2025!";
+
+    #[test]
+    fn test_encryption_params_accepts_strong_values() {
+        let checks = EncryptionParams::checks(SALT, PASSWORD);
+        assert!(checks.iter().all(|check| check.passed));
+        assert!(EncryptionParams::new(SALT, PASSWORD).is_ok());
+    }
+
+    #[test]
+    fn test_encryption_params_mirrors_server_rules() {
+        // Not hex / odd length
+        assert!(EncryptionParams::new("00112233445566zz", PASSWORD).is_err());
+        assert!(EncryptionParams::new("00112233445566778", PASSWORD).is_err());
+        // Too short
+        assert!(EncryptionParams::new("0011223344556677", PASSWORD).is_ok());
+        assert!(EncryptionParams::new("00112233445566", PASSWORD).is_err());
+        // Exactly 30 characters is not enough
+        assert!(EncryptionParams::new(SALT, "Abcdefghij1234567890abcdefghij").is_err());
+        assert!(EncryptionParams::new(SALT, "Abcdefghij1234567890abcdefghijk").is_ok());
+        // Missing a character class
+        assert!(EncryptionParams::new(SALT, "abcdefghij1234567890abcdefghijk").is_err());
+        assert!(EncryptionParams::new(SALT, "ABCDEFGHIJ1234567890ABCDEFGHIJk").is_ok());
+        assert!(EncryptionParams::new(SALT, "Abcdefghijklmnopqrstuvwxyzabcdef").is_err());
+        // Line breaks never match the server's pattern
+        assert!(EncryptionParams::new(SALT, "Correct-Horse-Battery\nStaple-2025!").is_err());
+    }
+
+    #[test]
+    fn test_encryption_params_advice_does_not_block() {
+        let weak = "Aa1Aa1Aa1Aa1Aa1Aa1Aa1Aa1Aa1Aa1Aa1";
+        let checks = EncryptionParams::checks("0011223344556677", weak);
+        let advice: Vec<&str> = checks
+            .iter()
+            .filter(|check| !check.passed)
+            .map(|check| check.label)
+            .collect();
+        assert_eq!(advice.len(), 3);
+        assert!(
+            checks
+                .iter()
+                .filter(|check| !check.passed)
+                .all(|check| !check.required)
+        );
+        assert!(EncryptionParams::new("0011223344556677", weak).is_ok());
+    }
+
+    #[test]
+    fn test_encryption_params_never_print_secrets() {
+        let params = EncryptionParams::new(SALT, PASSWORD).unwrap();
+        let debug = format!("{:?}", params);
+        assert!(!debug.contains(SALT) && !debug.contains(PASSWORD));
+
+        let json = serde_json::to_value(&params).unwrap();
+        assert_eq!(json["s"], SALT);
+        assert_eq!(json["password"], PASSWORD);
+    }
+
+    #[test]
+    fn test_history_is_per_environment_and_newest_first() {
+        let mut history = WhitelistHistory::default();
+        history.add(&Environment::studio(), record("addr1a"));
+        history.add(&Environment::studio(), record("addr1b"));
+        history.add(&Environment::garage(), record("addr_test1c"));
+
+        let studio: Vec<&str> = history
+            .records(&Environment::studio())
+            .iter()
+            .map(|r| r.address.as_str())
+            .collect();
+        assert_eq!(studio, vec!["addr1b", "addr1a"]);
+        assert_eq!(history.records(&Environment::garage()).len(), 1);
+    }
+
+    #[test]
+    fn test_history_rewhitelisting_moves_to_top() {
+        let mut history = WhitelistHistory::default();
+        history.add(&Environment::studio(), record("addr1a"));
+        history.add(&Environment::studio(), record("addr1b"));
+        history.add(&Environment::studio(), record("ADDR1A"));
+
+        let studio: Vec<&str> = history
+            .records(&Environment::studio())
+            .iter()
+            .map(|r| r.address.as_str())
+            .collect();
+        assert_eq!(studio, vec!["ADDR1A", "addr1b"]);
+    }
+
+    #[test]
+    fn test_key_history_names_are_case_sensitive() {
+        let key = |name: &str| KeyRecord {
+            name: name.to_string(),
+            address: "addr1v".to_string(),
+            uploaded_at: "2025-01-31 12:00:00".to_string(),
+        };
+        let mut history = KeyHistory::default();
+        history.add(&Environment::garage(), key("treasury"));
+        history.add(&Environment::garage(), key("Treasury"));
+        history.add(&Environment::garage(), key("treasury"));
+
+        let names: Vec<&str> = history
+            .records(&Environment::garage())
+            .iter()
+            .map(|r| r.name.as_str())
+            .collect();
+        assert_eq!(names, vec!["treasury", "Treasury"]);
+    }
+
+    #[test]
+    fn test_history_round_trips_through_json() {
+        let mut history = WhitelistHistory::default();
+        history.add(&Environment::garage(), record("addr_test1c"));
+
+        let json = serde_json::to_string(&history).unwrap();
+        assert!(json.contains("\"whitelistedAt\""));
+        let parsed: WhitelistHistory = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, history);
+    }
+}
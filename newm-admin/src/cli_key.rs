@@ -0,0 +1,415 @@
+//! cardano-cli Key Files
+//!
+//! Reads and checks `.skey`/`.vkey` TextEnvelope files as written by
+//! `cardano-cli address key-gen`, so only well-formed, matching payment key
+//! pairs are uploaded to the server.
+
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use blake2::Blake2b;
+use blake2::digest::Digest;
+use blake2::digest::consts::U28;
+use ed25519_dalek::SigningKey;
+use serde::{Deserialize, Serialize};
+
+use crate::cardano::{self, Network};
+
+/// TextEnvelope type of a Shelley payment signing key
+pub const SIGNING_KEY_TYPE: &str = "PaymentSigningKeyShelley_ed25519";
+
+/// TextEnvelope type of a Shelley payment verification key
+pub const VERIFICATION_KEY_TYPE: &str = "PaymentVerificationKeyShelley_ed25519";
+
+/// Length of an ed25519 seed or public key
+const KEY_LENGTH: usize = 32;
+
+/// CBOR header of a 32-byte byte string (major type 2, one-byte length)
+const CBOR_BYTES_32: [u8; 2] = [0x58, KEY_LENGTH as u8];
+
+/// Error from reading or checking a key file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KeyError {
+    /// File could not be read
+    Read(String),
+    /// File is not a TextEnvelope JSON document
+    Parse(String),
+    /// TextEnvelope has a different key type than expected
+    WrongType {
+        expected: &'static str,
+        actual: String,
+    },
+    /// `cborHex` is not a hex-encoded 32-byte CBOR byte string
+    InvalidCbor(String),
+    /// Verification key does not belong to the signing key
+    Mismatch,
+    /// Key pair name is empty
+    MissingName,
+}
+
+impl std::fmt::Display for KeyError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            KeyError::Read(msg) => write!(f, "Failed to read key file: {}", msg),
+            KeyError::Parse(msg) => write!(f, "Not a cardano-cli key file: {}", msg),
+            KeyError::WrongType { expected, actual } => {
+                write!(f, "Key type is '{}', expected '{}'", actual, expected)
+            }
+            KeyError::InvalidCbor(msg) => write!(f, "Invalid cborHex: {}", msg),
+            KeyError::Mismatch => write!(f, "Verification key does not match the signing key"),
+            KeyError::MissingName => write!(f, "Key name is required"),
+        }
+    }
+}
+
+impl std::error::Error for KeyError {}
+
+/// A cardano-cli TextEnvelope, mirroring the server's `CliKey`
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CliKey {
+    #[serde(rename = "type")]
+    pub key_type: String,
+    pub description: String,
+    pub cbor_hex: String,
+}
+
+// Signing keys must never end up in logs
+impl std::fmt::Debug for CliKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CliKey")
+            .field("key_type", &self.key_type)
+            .field("description", &self.description)
+            .field("cbor_hex", &"<redacted>")
+            .finish()
+    }
+}
+
+impl CliKey {
+    /// Parse a TextEnvelope JSON document
+    pub fn from_json(json: &str) -> Result<Self, KeyError> {
+        serde_json::from_str(json).map_err(|e| KeyError::Parse(e.to_string()))
+    }
+
+    /// Read a TextEnvelope file
+    pub fn read_file(path: &Path) -> Result<Self, KeyError> {
+        let json = std::fs::read_to_string(path)
+            .map_err(|e| KeyError::Read(format!("{}: {}", path.display(), e)))?;
+        Self::from_json(&json)
+    }
+
+    /// Check the envelope type and return the 32 key bytes inside the CBOR
+    fn key_bytes(&self, expected_type: &'static str) -> Result<[u8; KEY_LENGTH], KeyError> {
+        if self.key_type != expected_type {
+            return Err(KeyError::WrongType {
+                expected: expected_type,
+                actual: self.key_type.clone(),
+            });
+        }
+
+        let cbor =
+            hex::decode(self.cbor_hex.trim()).map_err(|e| KeyError::InvalidCbor(e.to_string()))?;
+        let key = cbor.strip_prefix(&CBOR_BYTES_32).ok_or_else(|| {
+            KeyError::InvalidCbor("expected a 32-byte CBOR byte string".to_string())
+        })?;
+        key.try_into().map_err(|_| {
+            KeyError::InvalidCbor(format!(
+                "expected {} bytes, got {}",
+                KEY_LENGTH + CBOR_BYTES_32.len(),
+                cbor.len()
+            ))
+        })
+    }
+}
+
+/// A named payment key pair, mirroring the server's `CliKeyPair`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CliKeyPair {
+    pub name: String,
+    pub vkey: CliKey,
+    pub skey: Option<CliKey>,
+}
+
+impl CliKeyPair {
+    /// Build a key pair for upload, checking both envelopes and that the
+    /// verification key is the public key of the signing key.
+    pub fn new(name: &str, skey: CliKey, vkey: CliKey) -> Result<Self, KeyError> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(KeyError::MissingName);
+        }
+
+        let seed = skey.key_bytes(SIGNING_KEY_TYPE)?;
+        let public_key = vkey.key_bytes(VERIFICATION_KEY_TYPE)?;
+        if SigningKey::from_bytes(&seed).verifying_key().to_bytes() != public_key {
+            return Err(KeyError::Mismatch);
+        }
+
+        Ok(Self {
+            name: name.to_string(),
+            vkey,
+            skey: Some(skey),
+        })
+    }
+
+    /// Blake2b-224 hash of the verification key
+    pub fn key_hash(&self) -> Result<[u8; 28], KeyError> {
+        let public_key = self.vkey.key_bytes(VERIFICATION_KEY_TYPE)?;
+        Ok(Blake2b::<U28>::digest(public_key).into())
+    }
+
+    /// Enterprise address the server will record for this key
+    pub fn address(&self, network: Network) -> Result<String, KeyError> {
+        Ok(cardano::enterprise_address(&self.key_hash()?, network))
+    }
+}
+
+/// Check that a key name is a plain file name, so keys named by the server
+/// cannot be written outside the chosen folder
+fn file_stem(name: &str) -> Result<&str, String> {
+    let mut components = Path::new(name).components();
+    let is_plain = matches!(
+        (components.next(), components.next()),
+        (Some(std::path::Component::Normal(_)), None)
+    );
+    if !is_plain || name.contains(['/', '\\', ':', '\0']) {
+        return Err(format!("'{}' cannot be used as a file name", name));
+    }
+    Ok(name)
+}
+
+/// Write a key pair as `<name>.skey` and `<name>.vkey` TextEnvelope files.
+///
+/// Existing files are never overwritten, and a failed write removes the files
+/// it created so the next attempt starts clean. On Unix the files are only
+/// readable by the current user.
+pub fn write_key_files(dir: &Path, key_pair: &CliKeyPair) -> Result<Vec<PathBuf>, String> {
+    let name = file_stem(&key_pair.name)?;
+    let mut files = vec![(dir.join(format!("{}.vkey", name)), &key_pair.vkey)];
+    if let Some(skey) = &key_pair.skey {
+        files.push((dir.join(format!("{}.skey", name)), skey));
+    }
+    if let Some((path, _)) = files.iter().find(|(path, _)| path.exists()) {
+        return Err(format!("{} already exists", path.display()));
+    }
+
+    let mut written: Vec<PathBuf> = Vec::new();
+    for (path, key) in &files {
+        if let Err(e) = write_new_key_file(path, key) {
+            for path in &written {
+                if let Err(e) = std::fs::remove_file(path) {
+                    tracing::warn!("Failed to remove {}: {}", path.display(), e);
+                }
+            }
+            return Err(e);
+        }
+        written.push(path.clone());
+    }
+
+    Ok(written)
+}
+
+/// Create `path` holding one key, removing it again if writing fails
+fn write_new_key_file(path: &Path, key: &CliKey) -> Result<(), String> {
+    let json = serde_json::to_string_pretty(key)
+        .map_err(|e| format!("Failed to serialize {}: {}", path.display(), e))?;
+
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options
+        .open(path)
+        .map_err(|e| format!("Failed to create {}: {}", path.display(), e))?;
+    writeln!(file, "{}", json).map_err(|e| {
+        let _ = std::fs::remove_file(path);
+        format!("Failed to write {}: {}", path.display(), e)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // RFC 8032 test 1
+    const SEED: &str = "9d61b19deffd5a60ba844af492ec2cc44449c5697b326919703bac031cae7f60";
+    const PUBLIC_KEY: &str = "d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a";
+    const PUBLIC_KEY_HASH: &str = "35dedd2982a03cf39e7dce03c839994ffdec2ec6b04f1cf2d40e61a3";
+
+    fn skey(seed: &str) -> CliKey {
+        CliKey {
+            key_type: SIGNING_KEY_TYPE.to_string(),
+            description: "Payment Signing Key".to_string(),
+            cbor_hex: format!("5820{}", seed),
+        }
+    }
+
+    fn vkey(public_key: &str) -> CliKey {
+        CliKey {
+            key_type: VERIFICATION_KEY_TYPE.to_string(),
+            description: "Payment Verification Key".to_string(),
+            cbor_hex: format!("5820{}", public_key),
+        }
+    }
+
+    #[test]
+    fn test_parse_text_envelope() {
+        let key = CliKey::from_json(&format!(
+            r#"{{
+                "type": "PaymentVerificationKeyShelley_ed25519",
+                "description": "Payment Verification Key",
+                "cborHex": "5820{}"
+            }}"#,
+            PUBLIC_KEY
+        ))
+        .unwrap();
+        assert_eq!(key, vkey(PUBLIC_KEY));
+
+        assert!(matches!(
+            CliKey::from_json("not json"),
+            Err(KeyError::Parse(_))
+        ));
+    }
+
+    #[test]
+    fn test_matching_pair() {
+        let pair = CliKeyPair::new("  treasury ", skey(SEED), vkey(PUBLIC_KEY)).unwrap();
+        assert_eq!(pair.name, "treasury");
+        assert_eq!(hex::encode(pair.key_hash().unwrap()), PUBLIC_KEY_HASH);
+        assert!(
+            pair.address(Network::Mainnet)
+                .unwrap()
+                .starts_with("addr1v")
+        );
+        assert!(
+            pair.address(Network::Testnet)
+                .unwrap()
+                .starts_with("addr_test1v")
+        );
+    }
+
+    #[test]
+    fn test_mismatched_pair() {
+        let other_seed = "4ccd089b28ff96da9db6c346ec114e0f5b8a319f35aba624da8cf6ed4fb8a6fb";
+        assert_eq!(
+            CliKeyPair::new("treasury", skey(other_seed), vkey(PUBLIC_KEY)),
+            Err(KeyError::Mismatch)
+        );
+    }
+
+    #[test]
+    fn test_rejects_swapped_or_wrong_types() {
+        assert!(matches!(
+            CliKeyPair::new("treasury", vkey(PUBLIC_KEY), skey(SEED)),
+            Err(KeyError::WrongType { .. })
+        ));
+
+        let mut extended = skey(SEED);
+        extended.key_type = "PaymentExtendedSigningKeyShelley_ed25519_bip32".to_string();
+        assert!(matches!(
+            CliKeyPair::new("treasury", extended, vkey(PUBLIC_KEY)),
+            Err(KeyError::WrongType { .. })
+        ));
+    }
+
+    #[test]
+    fn test_rejects_bad_cbor() {
+        let mut bad = skey(SEED);
+        bad.cbor_hex = "58".to_string() + &"zz".repeat(33);
+        assert!(matches!(
+            CliKeyPair::new("treasury", bad, vkey(PUBLIC_KEY)),
+            Err(KeyError::InvalidCbor(_))
+        ));
+
+        // Missing the CBOR byte string header
+        let mut raw = skey(SEED);
+        raw.cbor_hex = SEED.to_string();
+        assert!(matches!(
+            CliKeyPair::new("treasury", raw, vkey(PUBLIC_KEY)),
+            Err(KeyError::InvalidCbor(_))
+        ));
+
+        // Truncated key
+        let mut short = skey(SEED);
+        short.cbor_hex.truncate(40);
+        assert!(matches!(
+            CliKeyPair::new("treasury", short, vkey(PUBLIC_KEY)),
+            Err(KeyError::InvalidCbor(_))
+        ));
+    }
+
+    #[test]
+    fn test_requires_name() {
+        assert_eq!(
+            CliKeyPair::new("   ", skey(SEED), vkey(PUBLIC_KEY)),
+            Err(KeyError::MissingName)
+        );
+    }
+
+    #[test]
+    fn test_debug_redacts_key_material() {
+        let debug = format!("{:?}", skey(SEED));
+        assert!(!debug.contains(SEED));
+    }
+
+    #[test]
+    fn test_write_key_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut pair = CliKeyPair::new("treasury", skey(SEED), vkey(PUBLIC_KEY)).unwrap();
+        pair.name = "8d5e8a0c-9c1f-4a51-9d7e-2f3a2b1c0d4e".to_string();
+
+        let paths = write_key_files(dir.path(), &pair).unwrap();
+        assert_eq!(paths.len(), 2);
+
+        let written = CliKey::read_file(&dir.path().join(format!("{}.skey", pair.name))).unwrap();
+        assert_eq!(Some(written), pair.skey);
+        let json = std::fs::read_to_string(&paths[0]).unwrap();
+        assert!(json.contains("\"cborHex\""));
+
+        // Never overwrites
+        assert!(write_key_files(dir.path(), &pair).is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_failed_write_leaves_no_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let pair = CliKeyPair::new("treasury", skey(SEED), vkey(PUBLIC_KEY)).unwrap();
+
+        // A dangling link passes the existence check but cannot be created
+        std::os::unix::fs::symlink(dir.path().join("missing"), dir.path().join("treasury.skey"))
+            .unwrap();
+        assert!(write_key_files(dir.path(), &pair).is_err());
+        assert!(!dir.path().join("treasury.vkey").exists());
+
+        std::fs::remove_file(dir.path().join("treasury.skey")).unwrap();
+        assert_eq!(write_key_files(dir.path(), &pair).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_write_key_files_rejects_paths() {
+        let root = tempfile::tempdir().unwrap();
+        let dir = root.path().join("keys");
+        std::fs::create_dir(&dir).unwrap();
+        let mut pair = CliKeyPair::new("treasury", skey(SEED), vkey(PUBLIC_KEY)).unwrap();
+
+        for name in [
+            "../treasury",
+            "a/b",
+            "..",
+            ".",
+            "/tmp/treasury",
+            "a\\b",
+            "C:key",
+            "",
+        ] {
+            pair.name = name.to_string();
+            assert!(write_key_files(&dir, &pair).is_err(), "{}", name);
+        }
+        assert!(!root.path().join("treasury.skey").exists());
+        assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 0);
+    }
+}
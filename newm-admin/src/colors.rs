@@ -56,3 +56,6 @@ pub fn error() -> Rgba {
 pub fn success() -> Rgba {
     rgb(0x22c55e)
 } // Success text
+pub fn warning() -> Rgba {
+    rgb(0xeab308)
+} // Pending, advisory text
@@ -2,14 +2,22 @@
 //!
 //! Handles parsing CSV files for batch earnings creation and writing result files.
 //! Supports CSV files with or without headers.
+//!
+//! Two formats are supported: song royalties (`songId_or_isrc,amount[,currency]`)
+//! and rewards not tied to a song (`stake_address,amount,memo[,start[,end]]`).
 
 use std::path::{Path, PathBuf};
 
+use crate::cardano::{Network, as_stake_address};
+use crate::earnings::{Earning, RoyaltyCurrency, parse_amount, parse_reward_date};
+
 /// A row from the input CSV
 #[derive(Debug, Clone)]
 pub struct CsvRow {
     pub song_id_or_isrc: String,
-    pub amount_usd: String,
+    pub amount: String,
+    /// Currency of `amount` (defaults to USD when the column is absent or empty)
+    pub currency: RoyaltyCurrency,
 }
 
 /// Result for a processed row
@@ -19,6 +27,46 @@ pub struct CsvResult {
     pub result: String, // "Success" or error message
 }
 
+/// A row from a rewards CSV
+#[derive(Debug, Clone)]
+pub struct RewardCsvRow {
+    pub stake_address: String,
+    /// NEWM amount as entered (e.g., "10.50")
+    pub amount: String,
+    pub memo: String,
+    /// Optional start of the claim window
+    pub start: String,
+    /// Optional end of the claim window
+    pub end: String,
+}
+
+impl RewardCsvRow {
+    /// Convert to a validated reward earning for `network`
+    ///
+    /// The address column may also hold a base address, in which case its
+    /// stake address is used.
+    pub fn to_earning(&self, network: Network) -> Result<Earning, String> {
+        if self.stake_address.is_empty() {
+            return Err("Stake address is required".to_string());
+        }
+        let stake_address = as_stake_address(&self.stake_address, network)
+            .map_err(|e| format!("Invalid stake address - {}", e))?;
+        let amount = parse_amount(&self.amount).map_err(|e| format!("Invalid amount - {}", e))?;
+        let start = parse_reward_date(&self.start)?;
+        let end = parse_reward_date(&self.end)?;
+        let earning = Earning::reward(&stake_address.to_string(), amount, &self.memo, start, end);
+        earning.validate_new(network)?;
+        Ok(earning)
+    }
+}
+
+/// Result for a processed reward row
+#[derive(Debug, Clone)]
+pub struct RewardCsvResult {
+    pub row: RewardCsvRow,
+    pub result: String,
+}
+
 /// Summary of CSV import operation
 #[derive(Debug, Clone)]
 #[allow(dead_code)]
@@ -26,6 +74,8 @@ pub struct CsvImportSummary {
     pub total: usize,
     pub succeeded: usize,
     pub failed: usize,
+    /// Sent, but whether they were booked is unknown
+    pub unconfirmed: usize,
     pub output_path: PathBuf,
 }
 
@@ -63,6 +113,7 @@ fn is_header_row(col1: &str, col2: &str) -> bool {
 
     let col2_is_header = col2_lower.contains("amount")
         || col2_lower.contains("usd")
+        || col2_lower.contains("newm")
         || col2_lower.contains("price")
         || col2_lower == "amount_usd";
 
@@ -74,9 +125,10 @@ fn is_header_row(col1: &str, col2: &str) -> bool {
 /// Supports CSV files with or without headers. If a header row is detected,
 /// it will be skipped automatically.
 ///
-/// Expected format (2 columns):
+/// Expected format (2 or 3 columns):
 /// - Column 1: Song ID or ISRC
-/// - Column 2: Amount in USD (e.g., "10.50")
+/// - Column 2: Amount (e.g., "10.50")
+/// - Column 3 (optional): Currency of the amount, "USD" or "NEWM" (defaults to USD)
 pub fn parse_csv(path: &Path) -> Result<Vec<CsvRow>, CsvError> {
     let mut reader = csv::ReaderBuilder::new()
         .has_headers(false) // We handle headers manually
@@ -118,9 +170,23 @@ pub fn parse_csv(path: &Path) -> Result<Vec<CsvRow>, CsvError> {
             }
         }
 
+        let col3 = record.get(2).unwrap_or("").trim();
+        let currency = if col3.is_empty() {
+            RoyaltyCurrency::Usd
+        } else {
+            RoyaltyCurrency::parse(col3).ok_or_else(|| {
+                CsvError::InvalidFormat(format!(
+                    "Line {}: Unknown currency '{}', expected USD or NEWM",
+                    line_num + 1,
+                    col3
+                ))
+            })?
+        };
+
         rows.push(CsvRow {
             song_id_or_isrc: col1.to_string(),
-            amount_usd: col2.to_string(),
+            amount: col2.to_string(),
+            currency,
         });
     }
 
@@ -134,12 +200,78 @@ pub fn parse_csv(path: &Path) -> Result<Vec<CsvRow>, CsvError> {
     Ok(rows)
 }
 
-/// Write results to a new CSV file
+/// Parse a rewards CSV file into rows
 ///
-/// Creates a new file with "_results" appended to the original filename.
-/// Output format: songId_or_isrc,amount_usd,result
-pub fn write_results(input_path: &Path, results: &[CsvResult]) -> Result<PathBuf, CsvError> {
-    // Generate output filename
+/// Supports CSV files with or without headers.
+///
+/// Expected format (3 to 5 columns):
+/// - Column 1: Stake address
+/// - Column 2: NEWM amount (e.g., "10.50")
+/// - Column 3: Memo
+/// - Column 4 (optional): Start date, "YYYY-MM-DD" or "YYYY-MM-DDTHH:MM"
+/// - Column 5 (optional): End date, same format
+///
+/// Values are not validated here; see [`RewardCsvRow::to_earning`].
+pub fn parse_rewards_csv(path: &Path) -> Result<Vec<RewardCsvRow>, CsvError> {
+    let mut reader = csv::ReaderBuilder::new()
+        .has_headers(false)
+        .flexible(true)
+        .trim(csv::Trim::All)
+        .from_path(path)
+        .map_err(|e| CsvError::IoError(e.to_string()))?;
+
+    let mut rows = Vec::new();
+    let mut is_first_row = true;
+
+    for (line_num, result) in reader.records().enumerate() {
+        let record =
+            result.map_err(|e| CsvError::ParseError(format!("Line {}: {}", line_num + 1, e)))?;
+
+        let col = |i: usize| record.get(i).unwrap_or("").trim().to_string();
+
+        // Skip empty rows
+        if record.iter().all(|field| field.trim().is_empty()) {
+            continue;
+        }
+
+        if record.len() < 3 {
+            return Err(CsvError::InvalidFormat(format!(
+                "Line {}: Expected at least 3 columns, found {}",
+                line_num + 1,
+                record.len()
+            )));
+        }
+
+        // Check if first row is a header
+        if is_first_row {
+            is_first_row = false;
+            if col(0).to_lowercase().contains("stake") && col(1).to_lowercase().contains("amount") {
+                tracing::info!("Detected header row, skipping");
+                continue;
+            }
+        }
+
+        rows.push(RewardCsvRow {
+            stake_address: col(0),
+            amount: col(1),
+            memo: col(2),
+            start: col(3),
+            end: col(4),
+        });
+    }
+
+    if rows.is_empty() {
+        return Err(CsvError::InvalidFormat(
+            "CSV file contains no data rows".to_string(),
+        ));
+    }
+
+    tracing::info!("Parsed {} reward rows from CSV", rows.len());
+    Ok(rows)
+}
+
+/// Path of the results file for an input CSV ("_results" appended to the file name)
+fn results_path(input_path: &Path) -> PathBuf {
     let stem = input_path
         .file_stem()
         .and_then(|s| s.to_str())
@@ -149,24 +281,32 @@ pub fn write_results(input_path: &Path, results: &[CsvResult]) -> Result<PathBuf
         .and_then(|s| s.to_str())
         .unwrap_or("csv");
 
-    let output_filename = format!("{}_results.{}", stem, extension);
-    let output_path = input_path.with_file_name(output_filename);
+    input_path.with_file_name(format!("{}_results.{}", stem, extension))
+}
+
+/// Write results to a new CSV file
+///
+/// Creates a new file with "_results" appended to the original filename.
+/// Output format: songId_or_isrc,amount,currency,result
+pub fn write_results(input_path: &Path, results: &[CsvResult]) -> Result<PathBuf, CsvError> {
+    let output_path = results_path(input_path);
 
     let mut writer =
         csv::Writer::from_path(&output_path).map_err(|e| CsvError::IoError(e.to_string()))?;
 
     // Write header
     writer
-        .write_record(["songId_or_isrc", "amount_usd", "result"])
+        .write_record(["songId_or_isrc", "amount", "currency", "result"])
         .map_err(|e| CsvError::IoError(e.to_string()))?;
 
     // Write data rows
     for result in results {
         writer
             .write_record([
-                &result.row.song_id_or_isrc,
-                &result.row.amount_usd,
-                &result.result,
+                result.row.song_id_or_isrc.as_str(),
+                result.row.amount.as_str(),
+                result.row.currency.code(),
+                result.result.as_str(),
             ])
             .map_err(|e| CsvError::IoError(e.to_string()))?;
     }
@@ -179,6 +319,43 @@ pub fn write_results(input_path: &Path, results: &[CsvResult]) -> Result<PathBuf
     Ok(output_path)
 }
 
+/// Write reward results to a new CSV file
+///
+/// Output format: stake_address,amount,memo,start,end,result
+pub fn write_reward_results(
+    input_path: &Path,
+    results: &[RewardCsvResult],
+) -> Result<PathBuf, CsvError> {
+    let output_path = results_path(input_path);
+
+    let mut writer =
+        csv::Writer::from_path(&output_path).map_err(|e| CsvError::IoError(e.to_string()))?;
+
+    writer
+        .write_record(["stake_address", "amount", "memo", "start", "end", "result"])
+        .map_err(|e| CsvError::IoError(e.to_string()))?;
+
+    for result in results {
+        writer
+            .write_record([
+                result.row.stake_address.as_str(),
+                result.row.amount.as_str(),
+                result.row.memo.as_str(),
+                result.row.start.as_str(),
+                result.row.end.as_str(),
+                result.result.as_str(),
+            ])
+            .map_err(|e| CsvError::IoError(e.to_string()))?;
+    }
+
+    writer
+        .flush()
+        .map_err(|e| CsvError::IoError(e.to_string()))?;
+
+    tracing::info!("Wrote reward results to {:?}", output_path);
+    Ok(output_path)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -205,9 +382,10 @@ mod tests {
             rows[0].song_id_or_isrc,
             "550e8400-e29b-41d4-a716-446655440000"
         );
-        assert_eq!(rows[0].amount_usd, "10.50");
+        assert_eq!(rows[0].amount, "10.50");
+        assert_eq!(rows[0].currency, RoyaltyCurrency::Usd);
         assert_eq!(rows[1].song_id_or_isrc, "USRC12345678");
-        assert_eq!(rows[1].amount_usd, "25.00");
+        assert_eq!(rows[1].amount, "25.00");
     }
 
     #[test]
@@ -222,7 +400,97 @@ mod tests {
             rows[0].song_id_or_isrc,
             "550e8400-e29b-41d4-a716-446655440000"
         );
-        assert_eq!(rows[0].amount_usd, "10.50");
+        assert_eq!(rows[0].amount, "10.50");
+    }
+
+    #[test]
+    fn test_parse_csv_with_currency_column() {
+        let csv_content = "songId_or_isrc,amount,currency\n\
+                           550e8400-e29b-41d4-a716-446655440000,10.50,NEWM\n\
+                           USRC12345678,25.00,usd\n\
+                           USRC12345679,5.00,";
+        let file = create_temp_csv(csv_content);
+
+        let rows = parse_csv(file.path()).unwrap();
+        assert_eq!(rows.len(), 3);
+        assert_eq!(rows[0].currency, RoyaltyCurrency::Newm);
+        assert_eq!(rows[1].currency, RoyaltyCurrency::Usd);
+        assert_eq!(rows[2].currency, RoyaltyCurrency::Usd);
+    }
+
+    #[test]
+    fn test_parse_csv_unknown_currency() {
+        let csv_content = "USRC12345678,25.00,ADA";
+        let file = create_temp_csv(csv_content);
+
+        assert!(matches!(
+            parse_csv(file.path()),
+            Err(CsvError::InvalidFormat(_))
+        ));
+    }
+
+    // CIP-19 test vectors
+    const STAKE: &str = "stake1uyehkck0lajq8gr28t9uxnuvgcqrc6070x3k9r8048z8y5gh6ffgw";
+    const BASE_ADDRESS: &str = "addr1qx2fxv2umyhttkxyxp8x0dlpdt3k6cwng5pxj3jhsydzer3n0d3vllmyqwsx5wktcd8cc3sq835lu7drv2xwl2wywfgse35a3x";
+
+    #[test]
+    fn test_parse_rewards_csv() {
+        let csv_content = format!(
+            "stake_address,amount,memo,start,end\n\
+             {STAKE},10.50,Learn to earn,2025-02-01,2025-03-01\n\
+             {STAKE},1,\"Marketing, Q1\"\n"
+        );
+        let file = create_temp_csv(&csv_content);
+
+        let rows = parse_rewards_csv(file.path()).unwrap();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].stake_address, STAKE);
+        assert_eq!(rows[0].start, "2025-02-01");
+        assert_eq!(rows[1].memo, "Marketing, Q1");
+        assert_eq!(rows[1].end, "");
+
+        let earning = rows[0].to_earning(Network::Mainnet).unwrap();
+        assert_eq!(earning.amount, 10_500_000);
+        assert_eq!(earning.end_date.as_deref(), Some("2025-03-01T00:00:00"));
+        assert!(rows[1].to_earning(Network::Mainnet).is_ok());
+    }
+
+    #[test]
+    fn test_parse_rewards_csv_too_few_columns() {
+        let file = create_temp_csv(&format!("{STAKE},10.50"));
+        assert!(matches!(
+            parse_rewards_csv(file.path()),
+            Err(CsvError::InvalidFormat(_))
+        ));
+    }
+
+    #[test]
+    fn test_reward_row_validation() {
+        let row = |stake: &str, amount: &str, start: &str| RewardCsvRow {
+            stake_address: stake.to_string(),
+            amount: amount.to_string(),
+            memo: "memo".to_string(),
+            start: start.to_string(),
+            end: String::new(),
+        };
+        assert!(
+            row("stake1abc", "1", "")
+                .to_earning(Network::Mainnet)
+                .is_err()
+        );
+        assert!(row(STAKE, "1e3", "").to_earning(Network::Mainnet).is_err());
+        assert!(
+            row(STAKE, "1", "March 1")
+                .to_earning(Network::Mainnet)
+                .is_err()
+        );
+        assert!(row(STAKE, "1", "").to_earning(Network::Testnet).is_err());
+
+        // Base addresses are converted to their stake address
+        let earning = row(BASE_ADDRESS, "1", "")
+            .to_earning(Network::Mainnet)
+            .unwrap();
+        assert_eq!(earning.stake_address, STAKE);
     }
 
     #[test]
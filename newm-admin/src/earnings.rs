@@ -2,34 +2,246 @@
 //!
 //! Handles earnings-related API calls with automatic session management.
 
-use async_compat::Compat;
+use chrono::NaiveDateTime;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
 
+use crate::api::{ApiClient, ApiError};
+use crate::cardano::{Network, StakeAddress};
 use crate::http_client;
-use crate::session::{Session, SessionError};
+use crate::session::Session;
+
+/// Currency a royalty was paid out in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RoyaltyCurrency {
+    /// USD, converted to NEWM by the server at the current exchange rate
+    #[default]
+    Usd,
+    /// NEWM tokens, booked as-is
+    Newm,
+}
+
+impl RoyaltyCurrency {
+    /// Parse a currency code (case-insensitive, e.g. "usd" or "NEWM")
+    pub fn parse(code: &str) -> Option<Self> {
+        match code.trim().to_uppercase().as_str() {
+            "USD" => Some(RoyaltyCurrency::Usd),
+            "NEWM" => Some(RoyaltyCurrency::Newm),
+            _ => None,
+        }
+    }
+
+    /// Currency code for UI and result files
+    pub fn code(&self) -> &'static str {
+        match self {
+            RoyaltyCurrency::Usd => "USD",
+            RoyaltyCurrency::Newm => "NEWM",
+        }
+    }
+
+    /// Get currency from index (for RadioGroup)
+    pub fn from_index(index: usize) -> Self {
+        match index {
+            1 => RoyaltyCurrency::Newm,
+            _ => RoyaltyCurrency::Usd,
+        }
+    }
+}
+
+impl std::fmt::Display for RoyaltyCurrency {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.code())
+    }
+}
 
-/// Request to add royalties to a song
+/// Request to add royalties to a song.
+///
+/// The server expects exactly one of `newmAmount` or `usdAmount`.
 #[derive(Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct AddSongRoyaltyRequest {
+    /// Amount in NEWM with 6 decimal places (e.g., 10.5 NEWM = 10500000)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub newm_amount: Option<i64>,
     /// Amount in USD with 6 decimal places (e.g., 10.50 USD = 10500000)
-    pub usd_amount: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub usd_amount: Option<i64>,
+}
+
+impl AddSongRoyaltyRequest {
+    /// Create a request for an amount (6 decimal places) in the given currency
+    pub fn new(currency: RoyaltyCurrency, amount: i64) -> Self {
+        match currency {
+            RoyaltyCurrency::Usd => Self {
+                newm_amount: None,
+                usd_amount: Some(amount),
+            },
+            RoyaltyCurrency::Newm => Self {
+                newm_amount: Some(amount),
+                usd_amount: None,
+            },
+        }
+    }
+
+    /// Check that exactly one amount is set
+    pub fn validate(&self) -> Result<(), String> {
+        match (self.newm_amount, self.usd_amount) {
+            (Some(_), Some(_)) => Err("Only one of NEWM or USD amount may be set".to_string()),
+            (None, None) => Err("Either a NEWM or USD amount is required".to_string()),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl std::fmt::Display for AddSongRoyaltyRequest {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (self.newm_amount, self.usd_amount) {
+            (Some(amount), _) => write!(f, "{} NEWM", amount),
+            (None, Some(amount)) => write!(f, "{} USD", amount),
+            (None, None) => write!(f, "no amount"),
+        }
+    }
 }
 
 /// Earning record from the API
+///
+/// Also the request body for bulk creation, so optional fields are omitted
+/// rather than sent as null.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Earning {
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub song_id: Option<String>,
     pub stake_address: String,
     pub amount: i64,
     pub memo: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start_date: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub end_date: Option<String>,
     #[serde(default)]
     pub claimed: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub claimed_at: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub claim_order_id: Option<String>,
     pub created_at: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nft_policy_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nft_asset_name: Option<String>,
+}
+
+impl Earning {
+    /// Whether the earning can currently be claimed.
+    ///
+    /// Mirrors the server's `Earning.isActive`: an earning is active once its start
+    /// date has passed and until its end date, with missing dates leaving that side open.
+    pub fn is_active(&self) -> bool {
+        self.is_active_at(chrono::Utc::now().naive_utc())
+    }
+
+    /// Same as [`Earning::is_active`], evaluated at the given time.
+    ///
+    /// Dates that cannot be parsed are treated as absent.
+    pub fn is_active_at(&self, now: NaiveDateTime) -> bool {
+        let started = self
+            .start_date
+            .as_deref()
+            .and_then(parse_server_datetime)
+            .is_none_or(|start| start < now);
+        let not_ended = self
+            .end_date
+            .as_deref()
+            .and_then(parse_server_datetime)
+            .is_none_or(|end| end > now);
+        started && not_ended
+    }
+}
+
+impl Earning {
+    /// Build a reward earning that is not tied to a song (learn-to-earn, marketing, ...)
+    ///
+    /// `amount` is in NEWM with 6 decimal places.
+    pub fn reward(
+        stake_address: &str,
+        amount: i64,
+        memo: &str,
+        start_date: Option<NaiveDateTime>,
+        end_date: Option<NaiveDateTime>,
+    ) -> Self {
+        Self {
+            id: None,
+            song_id: None,
+            stake_address: stake_address.trim().to_string(),
+            amount,
+            memo: Some(memo.trim().to_string()),
+            start_date: start_date.map(format_server_datetime),
+            end_date: end_date.map(format_server_datetime),
+            claimed: false,
+            claimed_at: None,
+            claim_order_id: None,
+            created_at: format_server_datetime(chrono::Utc::now().naive_utc()),
+            nft_policy_id: None,
+            nft_asset_name: None,
+        }
+    }
+
+    /// Check a new earning before it is sent to the bulk create endpoint
+    ///
+    /// The stake address must decode and belong to `network`.
+    pub fn validate_new(&self, network: Network) -> Result<(), String> {
+        if self.stake_address.trim().is_empty() {
+            return Err("Stake address is required".to_string());
+        }
+        StakeAddress::parse(&self.stake_address)
+            .and_then(|address| address.check_network(network))
+            .map_err(|e| format!("Invalid stake address: {}", e))?;
+        if self.amount <= 0 {
+            return Err("Amount must be greater than zero".to_string());
+        }
+        if self.memo.as_deref().is_none_or(|m| m.trim().is_empty()) {
+            return Err("Memo is required".to_string());
+        }
+        let start = self.start_date.as_deref().and_then(parse_server_datetime);
+        let end = self.end_date.as_deref().and_then(parse_server_datetime);
+        if let (Some(start), Some(end)) = (start, end)
+            && start >= end
+        {
+            return Err("Start date must be before end date".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// Parse an admin-entered date for a reward window.
+///
+/// Accepts "YYYY-MM-DD" (midnight) or "YYYY-MM-DDTHH:MM[:SS]"; empty input means no bound.
+pub fn parse_reward_date(value: &str) -> Result<Option<NaiveDateTime>, String> {
+    let value = value.trim();
+    if value.is_empty() {
+        return Ok(None);
+    }
+    if let Some(datetime) = parse_server_datetime(value) {
+        return Ok(Some(datetime));
+    }
+    chrono::NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .map(|date| Some(date.and_time(chrono::NaiveTime::MIN)))
+        .map_err(|_| format!("Invalid date '{}', expected YYYY-MM-DD", value))
+}
+
+/// Format a timestamp the way the server's `LocalDateTime` serializer expects
+fn format_server_datetime(datetime: NaiveDateTime) -> String {
+    datetime.format("%Y-%m-%dT%H:%M:%S").to_string()
+}
+
+/// Parse a server `LocalDateTime` (e.g. "2025-01-31T12:00:00.123456")
+pub fn parse_server_datetime(value: &str) -> Option<NaiveDateTime> {
+    NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M:%S%.f")
+        .or_else(|_| NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M"))
+        .ok()
 }
 
 /// Error from earnings API operations
@@ -41,6 +253,10 @@ pub enum EarningsError {
     Api { status: u16, message: String },
     /// Network or other error
     Network(String),
+    /// A write was sent but its outcome is unknown
+    Ambiguous(String),
+    /// Request was rejected before being sent
+    Invalid(String),
 }
 
 impl std::fmt::Display for EarningsError {
@@ -51,17 +267,23 @@ impl std::fmt::Display for EarningsError {
                 write!(f, "API error {}: {}", status, message)
             }
             EarningsError::Network(msg) => write!(f, "Network error: {}", msg),
+            EarningsError::Ambiguous(msg) => {
+                write!(f, "Outcome unknown, check before retrying: {}", msg)
+            }
+            EarningsError::Invalid(msg) => write!(f, "Invalid request: {}", msg),
         }
     }
 }
 
 impl std::error::Error for EarningsError {}
 
-impl From<SessionError> for EarningsError {
-    fn from(err: SessionError) -> Self {
+impl From<ApiError> for EarningsError {
+    fn from(err: ApiError) -> Self {
         match err {
-            SessionError::Expired(msg) => EarningsError::SessionExpired(msg),
-            SessionError::Network(msg) => EarningsError::Network(msg),
+            ApiError::SessionExpired(msg) => EarningsError::SessionExpired(msg),
+            ApiError::Api { status, message } => EarningsError::Api { status, message },
+            ApiError::Network(msg) => EarningsError::Network(msg),
+            ApiError::Ambiguous(msg) => EarningsError::Ambiguous(msg),
         }
     }
 }
@@ -82,75 +304,82 @@ impl EarningsClient {
     /// Create a new earnings client
     pub fn new() -> Self {
         Self {
-            client: http_client::new_client(),
+            client: http_client::shared(),
         }
     }
 
+    fn api(&self, session: &Session) -> ApiClient {
+        ApiClient::new(self.client.clone(), session)
+    }
+
     /// Add earnings for a song.
     ///
     /// # Arguments
     /// * `session` - The authenticated session (will auto-refresh token if needed)
     /// * `song_id_or_isrc` - UUID or ISRC identifier for the song
-    /// * `usd_amount` - Amount in USD with 6 decimal places
+    /// * `royalty` - NEWM or USD amount with 6 decimal places
     ///
     /// # Returns
     /// * `Ok(())` on success
     /// * `Err(EarningsError::SessionExpired)` if token refresh fails
     /// * `Err(EarningsError::Api)` for API errors
+    /// * `Err(EarningsError::Invalid)` if the request does not set exactly one amount
+    /// * `Err(EarningsError::Ambiguous)` if the request was sent but may or may not
+    ///   have been booked; it is never retried, as that could pay twice
     pub async fn add_earnings(
         &self,
         session: &Session,
         song_id_or_isrc: &str,
-        usd_amount: i64,
+        royalty: &AddSongRoyaltyRequest,
     ) -> Result<(), EarningsError> {
-        let access_token = session.get_valid_token().await?;
-
-        let url = format!(
-            "{}/v1/earnings/admin/{}",
-            session.environment().base_url(),
-            song_id_or_isrc
-        );
-
-        let request_body = AddSongRoyaltyRequest { usd_amount };
+        royalty.validate().map_err(EarningsError::Invalid)?;
 
         tracing::info!(
             "Adding earnings for {} with amount {}",
             song_id_or_isrc,
-            usd_amount
+            royalty
         );
 
-        let response = Compat::new(async {
-            self.client
-                .post(&url)
-                .header("Authorization", format!("Bearer {}", access_token))
-                .json(&request_body)
-                .send()
-                .await
-        })
-        .await
-        .map_err(|e| EarningsError::Network(e.to_string()))?;
-
-        let status = response.status();
-
-        if status.is_success() {
-            tracing::info!("Earnings added successfully");
-            Ok(())
-        } else if status.as_u16() == 401 {
-            // Token was invalid despite refresh - session expired
-            Err(EarningsError::SessionExpired(
-                "Unauthorized - please login again".to_string(),
-            ))
-        } else {
-            let error_text = Compat::new(async { response.text().await })
-                .await
-                .unwrap_or_else(|_| "Unknown error".to_string());
-
-            tracing::warn!("Add earnings failed: {} - {}", status, error_text);
-            Err(EarningsError::Api {
-                status: status.as_u16(),
-                message: error_text,
-            })
+        self.api(session)
+            .post::<_, ()>(&format!("/v1/earnings/admin/{}", song_id_or_isrc), royalty)
+            .await?;
+
+        tracing::info!("Earnings added successfully");
+        Ok(())
+    }
+
+    /// Create arbitrary earning records in one batch.
+    ///
+    /// Used for rewards that are not tied to a song. Every record is validated
+    /// first, including that its stake address is for the session's network;
+    /// nothing is sent if any record is invalid.
+    ///
+    /// # Returns
+    /// * `Ok(())` on success
+    /// * `Err(EarningsError::Invalid)` naming the first invalid record
+    /// * `Err(EarningsError)` on API failure
+    pub async fn add_rewards(
+        &self,
+        session: &Session,
+        earnings: &[Earning],
+    ) -> Result<(), EarningsError> {
+        if earnings.is_empty() {
+            return Err(EarningsError::Invalid("No earnings to create".to_string()));
         }
+        for (i, earning) in earnings.iter().enumerate() {
+            earning
+                .validate_new(session.environment().network())
+                .map_err(|e| EarningsError::Invalid(format!("Record {}: {}", i + 1, e)))?;
+        }
+
+        tracing::info!("Creating {} reward earnings", earnings.len());
+
+        self.api(session)
+            .post::<_, ()>("/v1/earnings/admin", earnings)
+            .await?;
+
+        tracing::info!("Created {} reward earnings", earnings.len());
+        Ok(())
     }
 
     /// Get all earnings
@@ -162,49 +391,41 @@ impl EarningsClient {
     /// * `Ok(Vec<Earning>)` on success
     /// * `Err(EarningsError)` on failure
     pub async fn get_earnings(&self, session: &Session) -> Result<Vec<Earning>, EarningsError> {
-        let access_token = session.get_valid_token().await?;
+        tracing::info!("Fetching all earnings");
 
-        let url = format!("{}/v1/earnings/admin", session.environment().base_url());
+        let earnings: Vec<Earning> = self.api(session).get("/v1/earnings/admin").await?;
 
-        tracing::info!("Fetching all earnings");
+        tracing::info!("Fetched {} earnings", earnings.len());
+        Ok(earnings)
+    }
 
-        let response = Compat::new(async {
-            self.client
-                .get(&url)
-                .header("Authorization", format!("Bearer {}", access_token))
-                .send()
-                .await
-        })
-        .await
-        .map_err(|e| EarningsError::Network(e.to_string()))?;
-
-        let status = response.status();
-
-        if status.is_success() {
-            let earnings = Compat::new(async { response.json::<Vec<Earning>>().await })
-                .await
-                .map_err(|e| EarningsError::Api {
-                    status: 200,
-                    message: format!("Failed to parse response: {}", e),
-                })?;
-
-            tracing::info!("Fetched {} earnings", earnings.len());
-            Ok(earnings)
-        } else if status.as_u16() == 401 {
-            Err(EarningsError::SessionExpired(
-                "Unauthorized - please login again".to_string(),
-            ))
-        } else {
-            let error_text = Compat::new(async { response.text().await })
-                .await
-                .unwrap_or_else(|_| "Unknown error".to_string());
-
-            tracing::warn!("Get earnings failed: {} - {}", status, error_text);
-            Err(EarningsError::Api {
-                status: status.as_u16(),
-                message: error_text,
-            })
-        }
+    /// Get earnings for a single song
+    ///
+    /// # Arguments
+    /// * `session` - The authenticated session
+    /// * `song_id_or_isrc` - UUID or ISRC identifier for the song
+    ///
+    /// # Returns
+    /// * `Ok(Vec<Earning>)` on success
+    /// * `Err(EarningsError)` on failure
+    pub async fn get_song_earnings(
+        &self,
+        session: &Session,
+        song_id_or_isrc: &str,
+    ) -> Result<Vec<Earning>, EarningsError> {
+        tracing::info!("Fetching earnings for {}", song_id_or_isrc);
+
+        let earnings: Vec<Earning> = self
+            .api(session)
+            .get(&format!("/v1/earnings/admin/{}", song_id_or_isrc))
+            .await?;
+
+        tracing::info!(
+            "Fetched {} earnings for {}",
+            earnings.len(),
+            song_id_or_isrc
+        );
+        Ok(earnings)
     }
 
     /// Delete earnings by IDs
@@ -221,79 +442,130 @@ impl EarningsClient {
         session: &Session,
         earning_ids: Vec<String>,
     ) -> Result<(), EarningsError> {
-        let access_token = session.get_valid_token().await?;
+        tracing::info!("Deleting {} earnings", earning_ids.len());
 
-        let url = format!("{}/v1/earnings/admin", session.environment().base_url());
+        self.api(session)
+            .delete::<_, ()>("/v1/earnings/admin", &earning_ids)
+            .await?;
 
-        tracing::info!("Deleting {} earnings", earning_ids.len());
+        tracing::info!("Successfully deleted {} earnings", earning_ids.len());
+        Ok(())
+    }
+}
 
-        let response = Compat::new(async {
-            self.client
-                .delete(&url)
-                .header("Authorization", format!("Bearer {}", access_token))
-                .json(&earning_ids)
-                .send()
-                .await
-        })
-        .await
-        .map_err(|e| EarningsError::Network(e.to_string()))?;
-
-        let status = response.status();
-
-        if status.is_success() {
-            tracing::info!("Successfully deleted {} earnings", earning_ids.len());
-            Ok(())
-        } else if status.as_u16() == 401 {
-            Err(EarningsError::SessionExpired(
-                "Unauthorized - please login again".to_string(),
-            ))
-        } else {
-            let error_text = Compat::new(async { response.text().await })
-                .await
-                .unwrap_or_else(|_| "Unknown error".to_string());
-
-            tracing::warn!("Delete earnings failed: {} - {}", status, error_text);
-            Err(EarningsError::Api {
-                status: status.as_u16(),
-                message: error_text,
-            })
+/// Number of decimal places used for USD and NEWM amounts
+pub const AMOUNT_DECIMALS: usize = 6;
+
+/// Scale factor between whole units and 6-decimal amounts
+const AMOUNT_SCALE: i64 = 1_000_000;
+
+/// Format a 6-decimal integer amount with commas (e.g., 1000000 -> "1.000000",
+/// 1234567890 -> "1,234.567890")
+pub fn format_amount(amount: i64) -> String {
+    let sign = if amount < 0 { "-" } else { "" };
+    let abs_amount = amount.abs();
+    let integer_part = abs_amount / AMOUNT_SCALE;
+    let decimal_part = abs_amount % AMOUNT_SCALE;
+
+    let integer_str = integer_part.to_string();
+    let mut formatted_integer = String::new();
+    for (i, c) in integer_str.chars().rev().enumerate() {
+        if i > 0 && i % 3 == 0 {
+            formatted_integer.push(',');
         }
+        formatted_integer.push(c);
     }
+    let formatted_integer: String = formatted_integer.chars().rev().collect();
+
+    format!("{}{}.{:06}", sign, formatted_integer, decimal_part)
 }
 
-/// Convert a USD decimal string to 6-decimal integer
+/// Parse a non-negative decimal string into an exact 6-decimal integer.
 ///
-/// Validates that input has at most 6 decimal places.
+/// Used for both USD and NEWM amounts, e.g. `"10.50"` -> `10_500_000`. Parsing is
+/// done on the digits directly (no floating point), so every accepted input maps
+/// to exactly one amount.
 ///
-/// Examples:
-/// * "10.50" -> 10500000
-/// * "100" -> 100000000
-/// * "0.000001" -> 1
-pub fn usd_to_amount(usd_str: &str) -> Result<i64, String> {
-    let trimmed = usd_str.trim();
-
-    // Check decimal places
-    if let Some(dot_pos) = trimmed.find('.') {
-        let decimals = trimmed.len() - dot_pos - 1;
-        if decimals > 6 {
-            return Err(format!(
-                "Maximum 6 decimal places allowed (got {})",
-                decimals
-            ));
-        }
+/// Accepted: plain digits with an optional fraction of at most 6 digits
+/// (`"10"`, `"10.5"`, `".5"`, `"10."`), and comma thousands separators in the
+/// integer part when every group after the first has exactly 3 digits
+/// (`"1,234,567.89"`).
+///
+/// Rejected: signs, exponents (`"1e3"`), `"inf"`/`"NaN"`, misplaced separators
+/// (`"1,23"`), more than 6 decimals, and values that overflow `i64`.
+pub fn parse_amount(amount_str: &str) -> Result<i64, String> {
+    let trimmed = amount_str.trim();
+
+    if trimmed.is_empty() {
+        return Err("Amount is required".to_string());
+    }
+    if trimmed.starts_with('-') {
+        return Err("Amount cannot be negative".to_string());
     }
 
-    let value: f64 = trimmed
-        .parse()
-        .map_err(|_| "Invalid number format".to_string())?;
+    let (integer_part, fraction_part) = match trimmed.split_once('.') {
+        Some((integer, fraction)) => (integer, fraction),
+        None => (trimmed, ""),
+    };
 
-    if value < 0.0 {
-        return Err("Amount cannot be negative".to_string());
+    if integer_part.is_empty() && fraction_part.is_empty() {
+        return Err("Invalid number format".to_string());
+    }
+    if !fraction_part.chars().all(|c| c.is_ascii_digit()) {
+        return Err("Invalid number format".to_string());
     }
+    if fraction_part.len() > AMOUNT_DECIMALS {
+        return Err(format!(
+            "Maximum {} decimal places allowed (got {})",
+            AMOUNT_DECIMALS,
+            fraction_part.len()
+        ));
+    }
+
+    let integer_digits = strip_thousands_separators(integer_part)?;
 
-    // Multiply by 1,000,000 for 6 decimal places
-    let amount = (value * 1_000_000.0).round() as i64;
-    Ok(amount)
+    let mut whole: i64 = 0;
+    for digit in integer_digits.bytes() {
+        whole = whole
+            .checked_mul(10)
+            .and_then(|w| w.checked_add(i64::from(digit - b'0')))
+            .ok_or_else(|| "Amount is too large".to_string())?;
+    }
+
+    let mut fraction: i64 = 0;
+    for digit in fraction_part.bytes() {
+        fraction = fraction * 10 + i64::from(digit - b'0');
+    }
+    fraction *= 10_i64.pow((AMOUNT_DECIMALS - fraction_part.len()) as u32);
+
+    whole
+        .checked_mul(AMOUNT_SCALE)
+        .and_then(|w| w.checked_add(fraction))
+        .ok_or_else(|| "Amount is too large".to_string())
+}
+
+/// Validate comma thousands separators and return the bare integer digits
+fn strip_thousands_separators(integer_part: &str) -> Result<String, String> {
+    if !integer_part.contains(',') {
+        return if integer_part.chars().all(|c| c.is_ascii_digit()) {
+            Ok(integer_part.to_string())
+        } else {
+            Err("Invalid number format".to_string())
+        };
+    }
+
+    let groups: Vec<&str> = integer_part.split(',').collect();
+    let first_ok = matches!(groups[0].len(), 1..=3);
+    let rest_ok = groups[1..].iter().all(|g| g.len() == 3);
+    let digits_ok = groups.iter().all(|g| g.chars().all(|c| c.is_ascii_digit()));
+
+    if !digits_ok {
+        return Err("Invalid number format".to_string());
+    }
+    if !first_ok || !rest_ok {
+        return Err("Invalid thousands separator placement".to_string());
+    }
+    Ok(groups.concat())
 }
 
 #[cfg(test)]
@@ -301,26 +573,294 @@ mod tests {
     use super::*;
 
     #[test]
-    fn test_usd_to_amount() {
-        assert_eq!(usd_to_amount("10.50").unwrap(), 10_500_000);
-        assert_eq!(usd_to_amount("100").unwrap(), 100_000_000);
-        assert_eq!(usd_to_amount("0.000001").unwrap(), 1);
-        assert_eq!(usd_to_amount("1.234567").unwrap(), 1_234_567);
+    fn test_format_amount() {
+        assert_eq!(format_amount(1_000_000), "1.000000");
+        assert_eq!(format_amount(1_234_567_890), "1,234.567890");
+        assert_eq!(format_amount(-1_500_000), "-1.500000");
+        assert_eq!(
+            parse_amount(&format_amount(1_234_567_890)).unwrap(),
+            1_234_567_890
+        );
+    }
+
+    #[test]
+    fn test_parse_amount() {
+        assert_eq!(parse_amount("10.50").unwrap(), 10_500_000);
+        assert_eq!(parse_amount("100").unwrap(), 100_000_000);
+        assert_eq!(parse_amount("0.000001").unwrap(), 1);
+        assert_eq!(parse_amount("1.234567").unwrap(), 1_234_567);
+    }
+
+    #[test]
+    fn test_parse_amount_too_many_decimals() {
+        assert!(parse_amount("10.1234567").is_err());
+        assert!(parse_amount("1.0000001").is_err());
+    }
+
+    #[test]
+    fn test_parse_amount_negative() {
+        assert!(parse_amount("-10").is_err());
+    }
+
+    #[test]
+    fn test_parse_amount_invalid() {
+        assert!(parse_amount("abc").is_err());
+    }
+
+    #[test]
+    fn test_parse_amount_rejects_float_syntax() {
+        for input in [
+            "1e3", "1E3", "1.5e2", "inf", "-inf", "NaN", "+10", "", " ", ".", "1.2.3", "0x10",
+        ] {
+            assert!(parse_amount(input).is_err(), "accepted {:?}", input);
+        }
+    }
+
+    #[test]
+    fn test_parse_amount_thousands_separators() {
+        assert_eq!(parse_amount("1,234").unwrap(), 1_234_000_000);
+        assert_eq!(parse_amount("1,234,567.89").unwrap(), 1_234_567_890_000);
+        for input in ["1,23", "12,3456", ",123", "1,,234", "1234,567", "1,234.5,6"] {
+            assert!(parse_amount(input).is_err(), "accepted {:?}", input);
+        }
+    }
+
+    #[test]
+    fn test_parse_amount_edge_forms() {
+        assert_eq!(parse_amount(".5").unwrap(), 500_000);
+        assert_eq!(parse_amount("5.").unwrap(), 5_000_000);
+        assert_eq!(parse_amount(" 007.25 ").unwrap(), 7_250_000);
+    }
+
+    #[test]
+    fn test_parse_amount_overflow() {
+        // i64::MAX / 1_000_000 = 9_223_372_036_854
+        assert_eq!(parse_amount("9223372036854.775807").unwrap(), i64::MAX);
+        assert!(parse_amount("9223372036854.775808").is_err());
+        assert!(parse_amount("9223372036855").is_err());
+        assert!(parse_amount("99999999999999999999999").is_err());
+    }
+
+    fn earning_with_window(start: Option<&str>, end: Option<&str>) -> Earning {
+        serde_json::from_value(serde_json::json!({
+            "stakeAddress": "stake1u8test",
+            "amount": 1_000_000,
+            "memo": "test",
+            "startDate": start,
+            "endDate": end,
+            "createdAt": "2025-01-01T00:00:00"
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_earning_deserializes_all_fields() {
+        let earning: Earning = serde_json::from_str(
+            r#"{
+                "id": "6f2d1c9a-0000-4000-8000-000000000001",
+                "songId": "6f2d1c9a-0000-4000-8000-000000000002",
+                "stakeAddress": "stake1u8test",
+                "amount": 2500000,
+                "memo": "Royalty",
+                "startDate": "2025-02-01T00:00:00",
+                "endDate": "2025-03-01T00:00:00.123456",
+                "claimed": true,
+                "claimedAt": "2025-02-15T10:30:00",
+                "claimOrderId": "6f2d1c9a-0000-4000-8000-000000000003",
+                "createdAt": "2025-01-31T12:00:00",
+                "nftPolicyId": "46e607b3046a34c95e7c29e47047618dbf5e10de777ba56c590cfd5c",
+                "nftAssetName": "4e45574d"
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(earning.start_date.as_deref(), Some("2025-02-01T00:00:00"));
+        assert_eq!(
+            earning.claim_order_id.as_deref(),
+            Some("6f2d1c9a-0000-4000-8000-000000000003")
+        );
+        assert_eq!(earning.nft_asset_name.as_deref(), Some("4e45574d"));
+
+        let minimal = earning_with_window(None, None);
+        assert!(minimal.claim_order_id.is_none());
+        assert!(minimal.nft_policy_id.is_none());
+        assert!(!minimal.claimed);
+    }
+
+    #[test]
+    fn test_earning_is_active_at() {
+        let now = parse_server_datetime("2025-06-01T12:00:00").unwrap();
+
+        assert!(earning_with_window(None, None).is_active_at(now));
+        assert!(earning_with_window(Some("2025-06-01T11:59:59"), None).is_active_at(now));
+        assert!(earning_with_window(None, Some("2025-06-01T12:00:00.5")).is_active_at(now));
+        // Bounds are exclusive, as on the server
+        assert!(!earning_with_window(Some("2025-06-01T12:00:00"), None).is_active_at(now));
+        assert!(!earning_with_window(None, Some("2025-06-01T12:00:00")).is_active_at(now));
+        assert!(
+            !earning_with_window(Some("2025-07-01T00:00:00"), Some("2025-08-01T00:00:00"))
+                .is_active_at(now)
+        );
+        assert!(
+            !earning_with_window(Some("2025-01-01T00:00:00"), Some("2025-02-01T00:00:00"))
+                .is_active_at(now)
+        );
     }
 
+    // CIP-19 test vector
+    const STAKE: &str = "stake1uyehkck0lajq8gr28t9uxnuvgcqrc6070x3k9r8048z8y5gh6ffgw";
+
     #[test]
-    fn test_usd_to_amount_too_many_decimals() {
-        assert!(usd_to_amount("10.1234567").is_err());
-        assert!(usd_to_amount("1.0000001").is_err());
+    fn test_reward_serializes_without_nulls() {
+        let start = parse_reward_date("2025-02-01").unwrap();
+        let reward = Earning::reward(STAKE, 5_000_000, " Quiz reward ", start, None);
+        assert!(reward.validate_new(Network::Mainnet).is_ok());
+
+        let json = serde_json::to_value(&reward).unwrap();
+        assert_eq!(json["stakeAddress"], STAKE);
+        assert_eq!(json["amount"], 5_000_000);
+        assert_eq!(json["memo"], "Quiz reward");
+        assert_eq!(json["startDate"], "2025-02-01T00:00:00");
+        assert!(json.get("endDate").is_none());
+        assert!(json.get("id").is_none());
+        assert!(json.get("songId").is_none());
+        assert!(json["createdAt"].is_string());
     }
 
     #[test]
-    fn test_usd_to_amount_negative() {
-        assert!(usd_to_amount("-10").is_err());
+    fn test_reward_validation() {
+        let day = |s| parse_reward_date(s).unwrap();
+        let validate = |earning: Earning| earning.validate_new(Network::Mainnet);
+        assert!(validate(Earning::reward(STAKE, 0, "memo", None, None)).is_err());
+        assert!(validate(Earning::reward(STAKE, 1, "  ", None, None)).is_err());
+        assert!(validate(Earning::reward("stake1bad", 1, "memo", None, None)).is_err());
+        assert!(validate(Earning::reward("", 1, "memo", None, None)).is_err());
+        assert!(
+            validate(Earning::reward(
+                STAKE,
+                1,
+                "memo",
+                day("2025-03-01"),
+                day("2025-02-01")
+            ))
+            .is_err()
+        );
+        // Mainnet address while connected to a testnet environment
+        assert!(
+            Earning::reward(STAKE, 1, "memo", None, None)
+                .validate_new(Network::Testnet)
+                .is_err()
+        );
     }
 
     #[test]
-    fn test_usd_to_amount_invalid() {
-        assert!(usd_to_amount("abc").is_err());
+    fn test_parse_reward_date() {
+        assert_eq!(parse_reward_date("").unwrap(), None);
+        assert_eq!(
+            parse_reward_date("2025-02-01T08:30").unwrap(),
+            parse_server_datetime("2025-02-01T08:30:00")
+        );
+        assert!(parse_reward_date("02/01/2025").is_err());
+    }
+
+    mod proptests {
+        use super::super::*;
+        use proptest::prelude::*;
+
+        /// Insert comma separators every 3 digits from the right
+        fn with_separators(digits: &str) -> String {
+            let mut out = String::new();
+            for (i, c) in digits.chars().enumerate() {
+                if i > 0 && (digits.len() - i).is_multiple_of(3) {
+                    out.push(',');
+                }
+                out.push(c);
+            }
+            out
+        }
+
+        proptest! {
+            #[test]
+            fn parses_exactly(whole in 0i64..=9_223_372_036_853, fraction in 0i64..1_000_000, decimals in 0usize..=6) {
+                // Truncate the fraction to the requested number of decimals
+                let scale = 10_i64.pow((6 - decimals) as u32);
+                let fraction = fraction / scale * scale;
+                let input = if decimals == 0 {
+                    whole.to_string()
+                } else {
+                    format!("{}.{:06}", whole, fraction)[..whole.to_string().len() + 1 + decimals].to_string()
+                };
+                prop_assert_eq!(parse_amount(&input).unwrap(), whole * 1_000_000 + fraction);
+            }
+
+            #[test]
+            fn separators_do_not_change_value(whole in 0i64..=9_223_372_036_853, fraction in 0i64..1_000_000) {
+                let plain = format!("{}.{:06}", whole, fraction);
+                let grouped = format!("{}.{:06}", with_separators(&whole.to_string()), fraction);
+                prop_assert_eq!(parse_amount(&plain).unwrap(), parse_amount(&grouped).unwrap());
+            }
+
+            #[test]
+            fn rejects_exponents(mantissa in 0u32..100_000, exponent in -20i32..20) {
+                let input = format!("{}e{}", mantissa, exponent);
+                prop_assert!(parse_amount(&input).is_err());
+            }
+
+            #[test]
+            fn never_panics_and_never_negative(input in "\\PC*") {
+                if let Ok(amount) = parse_amount(&input) {
+                    prop_assert!(amount >= 0);
+                }
+            }
+
+            #[test]
+            fn too_many_decimals_rejected(whole in 0u32..1_000_000, extra in "[0-9]{7,12}") {
+                let input = format!("{}.{}", whole, extra);
+                prop_assert!(parse_amount(&input).is_err());
+            }
+        }
+    }
+
+    #[test]
+    fn test_royalty_request_serializes_single_amount() {
+        let usd = AddSongRoyaltyRequest::new(RoyaltyCurrency::Usd, 10_500_000);
+        assert_eq!(
+            serde_json::to_string(&usd).unwrap(),
+            r#"{"usdAmount":10500000}"#
+        );
+
+        let newm = AddSongRoyaltyRequest::new(RoyaltyCurrency::Newm, 42_000_000);
+        assert_eq!(
+            serde_json::to_string(&newm).unwrap(),
+            r#"{"newmAmount":42000000}"#
+        );
+    }
+
+    #[test]
+    fn test_royalty_request_validate() {
+        assert!(
+            AddSongRoyaltyRequest::new(RoyaltyCurrency::Newm, 1)
+                .validate()
+                .is_ok()
+        );
+        let both = AddSongRoyaltyRequest {
+            newm_amount: Some(1),
+            usd_amount: Some(1),
+        };
+        assert!(both.validate().is_err());
+        let neither = AddSongRoyaltyRequest {
+            newm_amount: None,
+            usd_amount: None,
+        };
+        assert!(neither.validate().is_err());
+    }
+
+    #[test]
+    fn test_royalty_currency_parse() {
+        assert_eq!(RoyaltyCurrency::parse("usd"), Some(RoyaltyCurrency::Usd));
+        assert_eq!(
+            RoyaltyCurrency::parse(" NEWM "),
+            Some(RoyaltyCurrency::Newm)
+        );
+        assert_eq!(RoyaltyCurrency::parse("ADA"), None);
     }
 }
@@ -0,0 +1,381 @@
+//! Server Environments
+//!
+//! The admin tool ships with the hosted Garage and Studio environments. More
+//! can be added (or the built-ins overridden) in `environments.json` in the
+//! config directory, for example a staging cluster or a local newm-server:
+//!
+//! ```json
+//! [
+//!   { "name": "Staging", "baseUrl": "https://staging.newm.io", "network": "testnet", "color": "#eab308" }
+//! ]
+//! ```
+//!
+//! Debug builds also offer a "Localhost" entry pointing at a newm-server
+//! running on its default port.
+//!
+//! An entry may also list the OAuth client IDs the server is configured
+//! with, e.g. `"oauth": { "google": "1234.apps.googleusercontent.com" }`,
+//! to offer browser sign-in with those providers. The client must list
+//! `http://127.0.0.1:47320/callback` as an authorized redirect URI.
+//!
+//! Access tokens are trusted as issued unless the entry sets up verification.
+//! The issuer and audience are checked as given; public keys (PEM files and/or
+//! a JWKS file, relative to the config directory) add signature checks:
+//!
+//! ```json
+//! "tokenVerification": {
+//!   "issuer": "https://newm.io",
+//!   "audience": "newm-server-users",
+//!   "publicKeys": ["staging.pem"],
+//!   "jwks": "staging.jwks.json"
+//! }
+//! ```
+//!
+//! newm-server signs its tokens with HS256 and publishes no JWKS, so an entry
+//! pointing at it can only use the issuer and audience checks. Signature
+//! checks cannot be enabled against it until it signs with RS/ES keys and
+//! publishes them.
+
+use gpui::{Rgba, rgb};
+use reqwest::Url;
+use serde::Deserialize;
+
+use crate::cardano::Network;
+use crate::jwt::{TokenVerification, TokenVerificationEntry};
+use crate::oauth::OAuthProvider;
+use crate::storage;
+
+/// Name of the environments file in the config directory
+const CONFIG_FILE: &str = "environments.json";
+
+/// Color used when an entry does not set one
+const DEFAULT_COLOR: u32 = 0xa1a1aa;
+
+/// A NEWM server the admin tool can log in to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Environment {
+    name: String,
+    base_url: String,
+    network: Network,
+    color: u32,
+    oauth: OAuthClientIds,
+    token_verification: Option<TokenVerification>,
+}
+
+/// OAuth client IDs registered for an environment's server
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+pub struct OAuthClientIds {
+    #[serde(default)]
+    pub google: Option<String>,
+}
+
+/// An environment as written in the config file
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EnvironmentEntry {
+    pub name: String,
+    pub base_url: String,
+    pub network: Network,
+    #[serde(default)]
+    pub color: Option<String>,
+    #[serde(default)]
+    pub oauth: OAuthClientIds,
+    #[serde(default)]
+    pub token_verification: Option<TokenVerificationEntry>,
+}
+
+impl Environment {
+    /// Hosted testnet environment
+    pub fn garage() -> Self {
+        Self {
+            name: "Garage".to_string(),
+            base_url: "https://garage.newm.io".to_string(),
+            network: Network::Testnet,
+            color: 0x22c55e,
+            oauth: OAuthClientIds::default(),
+            token_verification: None,
+        }
+    }
+
+    /// Hosted production environment
+    pub fn studio() -> Self {
+        Self {
+            name: "Studio".to_string(),
+            base_url: "https://studio.newm.io".to_string(),
+            network: Network::Mainnet,
+            color: 0xF53C69,
+            oauth: OAuthClientIds::default(),
+            token_verification: None,
+        }
+    }
+
+    /// newm-server running locally on its default port
+    pub fn localhost() -> Self {
+        Self {
+            name: "Localhost".to_string(),
+            base_url: "http://localhost:3939".to_string(),
+            network: Network::Testnet,
+            color: 0x3b82f6,
+            oauth: OAuthClientIds::default(),
+            token_verification: None,
+        }
+    }
+
+    /// Validate a config file entry
+    pub fn from_entry(entry: EnvironmentEntry) -> Result<Self, String> {
+        let name = entry.name.trim();
+        if name.is_empty() {
+            return Err("Environment name must not be empty".to_string());
+        }
+
+        let url = Url::parse(entry.base_url.trim())
+            .map_err(|e| format!("{}: invalid base URL '{}': {}", name, entry.base_url, e))?;
+        if !matches!(url.scheme(), "http" | "https") || url.host_str().is_none() {
+            return Err(format!(
+                "{}: base URL must be an http(s) address, got '{}'",
+                name, entry.base_url
+            ));
+        }
+
+        let color = match entry.color.as_deref() {
+            Some(color) => parse_color(color).ok_or_else(|| {
+                format!("{}: color must look like #rrggbb, got '{}'", name, color)
+            })?,
+            None => DEFAULT_COLOR,
+        };
+
+        // Dropping the whole entry is safer than quietly trusting its tokens
+        let token_verification = match entry.token_verification {
+            Some(verification) => {
+                let dir = storage::config_dir()
+                    .ok_or_else(|| format!("{}: no config directory for key files", name))?;
+                Some(
+                    verification
+                        .load(&dir)
+                        .map_err(|e| format!("{}: {}", name, e))?,
+                )
+            }
+            None => None,
+        };
+
+        Ok(Self {
+            name: name.to_string(),
+            base_url: url.as_str().trim_end_matches('/').to_string(),
+            network: entry.network,
+            color,
+            oauth: entry.oauth,
+            token_verification,
+        })
+    }
+
+    /// Get the base URL for this environment
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    /// OAuth client ID for a provider, if browser sign-in is set up
+    pub fn oauth_client_id(&self, provider: OAuthProvider) -> Option<&str> {
+        let client_id = match provider {
+            OAuthProvider::Google => &self.oauth.google,
+        };
+        client_id.as_deref().filter(|id| !id.trim().is_empty())
+    }
+
+    /// Signature and claim checks for this environment's tokens, if configured
+    pub fn token_verification(&self) -> Option<&TokenVerification> {
+        self.token_verification.as_ref()
+    }
+
+    /// Display name for UI
+    pub fn display_name(&self) -> &str {
+        &self.name
+    }
+
+    /// Cardano network this environment runs against
+    pub fn network(&self) -> Network {
+        self.network
+    }
+
+    /// Accent color used to tell environments apart
+    pub fn color(&self) -> Rgba {
+        rgb(self.color)
+    }
+}
+
+/// Parse a `#rrggbb` color
+fn parse_color(color: &str) -> Option<u32> {
+    let hex = color.trim().strip_prefix('#')?;
+    if hex.len() != 6 {
+        return None;
+    }
+    u32::from_str_radix(hex, 16).ok()
+}
+
+/// Environments available without any configuration
+fn built_in() -> Vec<Environment> {
+    let mut environments = vec![Environment::garage(), Environment::studio()];
+    if cfg!(debug_assertions) {
+        environments.push(Environment::localhost());
+    }
+    environments
+}
+
+/// Add config file entries to a list of environments.
+///
+/// An entry named like an existing environment (ignoring case) replaces it in
+/// place; invalid entries are skipped with a warning so one typo does not
+/// lock the admin out of every other environment.
+fn merge(mut environments: Vec<Environment>, entries: Vec<EnvironmentEntry>) -> Vec<Environment> {
+    for entry in entries {
+        let environment = match Environment::from_entry(entry) {
+            Ok(environment) => environment,
+            Err(e) => {
+                tracing::warn!("Skipping environment in {}: {}", CONFIG_FILE, e);
+                continue;
+            }
+        };
+
+        match environments
+            .iter_mut()
+            .find(|existing| existing.name.eq_ignore_ascii_case(&environment.name))
+        {
+            Some(existing) => *existing = environment,
+            None => environments.push(environment),
+        }
+    }
+    environments
+}
+
+/// Load the built-in environments plus those in the user's config file
+pub fn load() -> Vec<Environment> {
+    let entries = match storage::config_file(CONFIG_FILE) {
+        Some(path) => match storage::read_json::<Vec<EnvironmentEntry>>(&path) {
+            Ok(entries) => entries.unwrap_or_default(),
+            Err(e) => {
+                tracing::warn!("Ignoring environments config: {}", e);
+                Vec::new()
+            }
+        },
+        None => Vec::new(),
+    };
+    merge(built_in(), entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Environment, EnvironmentEntry, built_in, merge, parse_color};
+    use crate::cardano::Network;
+    use crate::oauth::OAuthProvider;
+
+    fn entries(json: &str) -> Vec<EnvironmentEntry> {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn test_parse_config_entries() {
+        let environments = merge(
+            Vec::new(),
+            entries(
+                r##"[
+                    {"name": "Staging", "baseUrl": "https://staging.newm.io/", "network": "testnet", "color": "#eab308"},
+                    {"name": "Mainnet Mirror", "baseUrl": "http://10.0.0.5:3939", "network": "mainnet"}
+                ]"##,
+            ),
+        );
+
+        assert_eq!(environments.len(), 2);
+        assert_eq!(environments[0].display_name(), "Staging");
+        assert_eq!(environments[0].base_url(), "https://staging.newm.io");
+        assert_eq!(environments[0].network(), Network::Testnet);
+        assert_eq!(environments[1].base_url(), "http://10.0.0.5:3939");
+        assert_eq!(environments[1].network(), Network::Mainnet);
+    }
+
+    #[test]
+    fn test_oauth_client_ids() {
+        let environments = merge(
+            Vec::new(),
+            entries(
+                r#"[{"name": "Staging", "baseUrl": "https://staging.newm.io", "network": "testnet",
+                     "oauth": {"google": "1234.apps.googleusercontent.com", "apple": "ignored"}}]"#,
+            ),
+        );
+
+        let staging = &environments[0];
+        assert_eq!(
+            staging.oauth_client_id(OAuthProvider::Google),
+            Some("1234.apps.googleusercontent.com")
+        );
+        assert_eq!(
+            Environment::garage().oauth_client_id(OAuthProvider::Google),
+            None
+        );
+    }
+
+    #[test]
+    fn test_unknown_network_is_rejected() {
+        let result: Result<Vec<EnvironmentEntry>, _> = serde_json::from_str(
+            r#"[{"name": "Preview", "baseUrl": "https://x.io", "network": "preview"}]"#,
+        );
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_invalid_entries_are_skipped() {
+        let environments = merge(
+            built_in(),
+            entries(
+                r##"[
+                    {"name": " ", "baseUrl": "https://a.io", "network": "testnet"},
+                    {"name": "Ftp", "baseUrl": "ftp://a.io", "network": "testnet"},
+                    {"name": "Relative", "baseUrl": "/v1", "network": "testnet"},
+                    {"name": "Colorful", "baseUrl": "https://a.io", "network": "testnet", "color": "red"}
+                ]"##,
+            ),
+        );
+        assert_eq!(environments, built_in());
+    }
+
+    #[test]
+    fn test_missing_token_keys_skip_entry() {
+        let environments = merge(
+            Vec::new(),
+            entries(
+                r#"[{"name": "Staging", "baseUrl": "https://staging.newm.io", "network": "testnet",
+                     "tokenVerification": {"publicKeys": ["no-such-key-file.pem"]}}]"#,
+            ),
+        );
+        assert!(environments.is_empty());
+        assert!(Environment::garage().token_verification().is_none());
+    }
+
+    #[test]
+    fn test_entry_overrides_built_in_by_name() {
+        let environments = merge(
+            built_in(),
+            entries(
+                r#"[{"name": "garage", "baseUrl": "https://garage.internal", "network": "testnet"}]"#,
+            ),
+        );
+
+        assert_eq!(environments.len(), built_in().len());
+        assert_eq!(environments[0].display_name(), "garage");
+        assert_eq!(environments[0].base_url(), "https://garage.internal");
+        assert_eq!(environments[1], Environment::studio());
+    }
+
+    #[test]
+    fn test_localhost_only_in_debug_builds() {
+        let has_localhost = built_in().contains(&Environment::localhost());
+        assert_eq!(has_localhost, cfg!(debug_assertions));
+    }
+
+    #[test]
+    fn test_parse_color() {
+        assert_eq!(parse_color("#eab308"), Some(0xeab308));
+        assert_eq!(parse_color(" #FFFFFF "), Some(0xffffff));
+        assert_eq!(parse_color("eab308"), None);
+        assert_eq!(parse_color("#fff"), None);
+        assert_eq!(parse_color("#gggggg"), None);
+    }
+}
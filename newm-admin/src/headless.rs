@@ -0,0 +1,642 @@
+//! Headless Mode
+//!
+//! `newm-admin --headless <command>` runs admin tasks without opening a
+//! window, so royalty runs can be scripted and reviewed in CI. It goes
+//! through the same auth, session, earnings and CSV modules as the console.
+//!
+//! ```text
+//! newm-admin --headless --env garage login
+//! newm-admin --headless --env garage earnings list --song QZ1234567890 --format json
+//! newm-admin --headless --env garage earnings import royalties.csv --dry-run
+//! newm-admin --headless --env garage earnings delete --ids-from ids.txt --yes
+//! newm-admin --headless --env garage logout
+//! ```
+//!
+//! When `NEWM_ADMIN_EMAIL` and `NEWM_ADMIN_PASSWORD` are set, each command
+//! logs in with them (`NEWM_ADMIN_CODE` supplies a two-factor code).
+//! Otherwise the refresh token that `login` (or "Remember me" in the
+//! console) saved in the keyring is used. `NEWM_ADMIN_ENV` can stand in for
+//! `--env`.
+//!
+//! Results go to stdout and logs to stderr. The exit status is 0 on success,
+//! 1 if the command or any CSV row failed, and 2 for usage errors.
+
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+
+use async_compat::Compat;
+
+use crate::auth::{AuthClient, AuthError, LoginResponse};
+use crate::csv_import::{CsvResult, CsvRow, parse_csv, write_results};
+use crate::earnings::{
+    AddSongRoyaltyRequest, Earning, EarningsClient, EarningsError, format_amount, parse_amount,
+};
+use crate::environment::{self, Environment};
+use crate::preflight::{PreflightReport, lookup_song};
+use crate::remember::RememberedSessions;
+use crate::session::Session;
+use crate::songs::is_uuid;
+
+const ENV_EMAIL: &str = "NEWM_ADMIN_EMAIL";
+const ENV_PASSWORD: &str = "NEWM_ADMIN_PASSWORD";
+const ENV_CODE: &str = "NEWM_ADMIN_CODE";
+const ENV_ENVIRONMENT: &str = "NEWM_ADMIN_ENV";
+
+const USAGE: &str = "\
+Usage: newm-admin --headless [--env <name>] <command>
+
+Commands:
+  login                                  Log in and save the session in the keyring
+  logout                                 End the saved session
+  earnings list [--song <id|isrc>] [--format table|json]
+  earnings import <file.csv> [--dry-run]
+  earnings delete --ids-from <file> [--yes]
+
+Environment:
+  NEWM_ADMIN_ENV                         Environment name, instead of --env
+  NEWM_ADMIN_EMAIL, NEWM_ADMIN_PASSWORD  Log in with these instead of the keyring
+  NEWM_ADMIN_CODE                        Two-factor code, if the server asks for one";
+
+/// How `earnings list` prints its results
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum OutputFormat {
+    Table,
+    Json,
+}
+
+impl OutputFormat {
+    fn parse(value: &str) -> Result<Self, String> {
+        match value {
+            "table" => Ok(OutputFormat::Table),
+            "json" => Ok(OutputFormat::Json),
+            other => Err(format!(
+                "unknown format '{}', expected table or json",
+                other
+            )),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Command {
+    Help,
+    Login,
+    Logout,
+    ListEarnings {
+        song: Option<String>,
+        format: OutputFormat,
+    },
+    ImportEarnings {
+        file: PathBuf,
+        dry_run: bool,
+    },
+    DeleteEarnings {
+        ids_from: PathBuf,
+        yes: bool,
+    },
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct Invocation {
+    environment: Option<String>,
+    command: Command,
+}
+
+/// Parse the arguments that follow `--headless`
+fn parse_args(args: Vec<OsString>) -> Result<Invocation, String> {
+    let mut args = pico_args::Arguments::from_vec(args);
+    if args.contains(["-h", "--help"]) {
+        return Ok(Invocation {
+            environment: None,
+            command: Command::Help,
+        });
+    }
+    let environment = args
+        .opt_value_from_str("--env")
+        .map_err(|e| e.to_string())?;
+
+    let subcommand = |args: &mut pico_args::Arguments| args.subcommand().map_err(|e| e.to_string());
+    let command = match subcommand(&mut args)?.as_deref() {
+        Some("login") => Command::Login,
+        Some("logout") => Command::Logout,
+        Some("earnings") => match subcommand(&mut args)?.as_deref() {
+            Some("list") => Command::ListEarnings {
+                song: args
+                    .opt_value_from_str("--song")
+                    .map_err(|e| e.to_string())?,
+                format: args
+                    .opt_value_from_fn("--format", OutputFormat::parse)
+                    .map_err(|e| e.to_string())?
+                    .unwrap_or(OutputFormat::Table),
+            },
+            Some("import") => {
+                let dry_run = args.contains("--dry-run");
+                Command::ImportEarnings {
+                    file: args
+                        .free_from_str()
+                        .map_err(|_| "earnings import needs a CSV file".to_string())?,
+                    dry_run,
+                }
+            }
+            Some("delete") => Command::DeleteEarnings {
+                yes: args.contains("--yes"),
+                ids_from: args
+                    .value_from_str("--ids-from")
+                    .map_err(|_| "earnings delete needs --ids-from <file>".to_string())?,
+            },
+            Some(other) => return Err(format!("Unknown earnings command '{}'", other)),
+            None => return Err("earnings needs a command: list, import or delete".to_string()),
+        },
+        Some(other) => return Err(format!("Unknown command '{}'", other)),
+        None => return Err("No command given".to_string()),
+    };
+
+    let rest = args.finish();
+    if !rest.is_empty() {
+        return Err(format!("Unexpected arguments: {:?}", rest));
+    }
+    Ok(Invocation {
+        environment,
+        command,
+    })
+}
+
+/// Run a headless command and return the process exit status
+pub fn run(args: Vec<OsString>) -> i32 {
+    let invocation = match parse_args(args) {
+        Ok(invocation) => invocation,
+        Err(e) => {
+            eprintln!("{}\n\n{}", e, USAGE);
+            return 2;
+        }
+    };
+    if invocation.command == Command::Help {
+        println!("{}", USAGE);
+        return 0;
+    }
+
+    match futures::executor::block_on(Compat::new(execute(invocation))) {
+        Ok(()) => 0,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            1
+        }
+    }
+}
+
+async fn execute(invocation: Invocation) -> Result<(), String> {
+    let name = invocation
+        .environment
+        .or_else(|| std::env::var(ENV_ENVIRONMENT).ok())
+        .ok_or_else(|| format!("Choose an environment with --env or {}", ENV_ENVIRONMENT))?;
+    let environment = find_environment(&environment::load(), &name)?;
+
+    match invocation.command {
+        Command::Help => Ok(()),
+        Command::Login => login(&environment).await,
+        Command::Logout => logout(&environment).await,
+        Command::ListEarnings { song, format } => {
+            let session = open_session(&environment).await?;
+            list_earnings(&session, song.as_deref(), format).await
+        }
+        Command::ImportEarnings { file, dry_run } => {
+            let session = open_session(&environment).await?;
+            let report = preflight(&session, &file).await?;
+            if !report.is_ready() {
+                return Err(format!(
+                    "{} rows have problems, nothing was sent",
+                    report.problem_count()
+                ));
+            }
+            if dry_run {
+                println!("Dry run, nothing was sent");
+                return Ok(());
+            }
+            let rows = report.rows.into_iter().map(|checked| checked.row).collect();
+            import_earnings(&session, &file, rows).await
+        }
+        Command::DeleteEarnings { ids_from, yes } => {
+            let ids = read_id_list(&ids_from)?;
+            if !yes {
+                println!(
+                    "Would delete {} earnings from {}; pass --yes to delete them",
+                    ids.len(),
+                    environment.display_name()
+                );
+                return Ok(());
+            }
+            let session = open_session(&environment).await?;
+            delete_earnings(&session, ids).await
+        }
+    }
+}
+
+fn find_environment(environments: &[Environment], name: &str) -> Result<Environment, String> {
+    environments
+        .iter()
+        .find(|e| e.display_name().eq_ignore_ascii_case(name.trim()))
+        .cloned()
+        .ok_or_else(|| {
+            let names: Vec<&str> = environments.iter().map(|e| e.display_name()).collect();
+            format!(
+                "Unknown environment '{}', expected one of: {}",
+                name,
+                names.join(", ")
+            )
+        })
+}
+
+/// Email and password from the environment, if both are set
+fn credentials() -> Option<(String, String)> {
+    let email = std::env::var(ENV_EMAIL).ok().filter(|v| !v.is_empty())?;
+    let password = std::env::var(ENV_PASSWORD).ok().filter(|v| !v.is_empty())?;
+    Some((email, password))
+}
+
+async fn password_login(
+    environment: &Environment,
+    email: &str,
+    password: &str,
+) -> Result<LoginResponse, String> {
+    let client = AuthClient::new();
+    let code = std::env::var(ENV_CODE).ok().filter(|v| !v.is_empty());
+    match client
+        .login_with_code(email, password, code.as_deref(), environment)
+        .await
+    {
+        Ok(response) => Ok(response),
+        Err(AuthError::CodeRequired) => {
+            client
+                .request_code(email, environment)
+                .await
+                .map_err(|e| format!("Failed to send verification code: {}", e))?;
+            Err(format!(
+                "A verification code was emailed to {}; run again with {} set",
+                email, ENV_CODE
+            ))
+        }
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+/// Log in with credentials from the environment, or resume the keyring session
+async fn open_session(environment: &Environment) -> Result<Session, String> {
+    if let Some((email, password)) = credentials() {
+        let response = password_login(environment, &email, &password).await?;
+        return Ok(Session::new(response, environment.clone()));
+    }
+
+    let remembered = RememberedSessions::new();
+    let refresh_token = remembered.load(environment)?.ok_or_else(|| {
+        format!(
+            "Not logged in to {}: set {} and {}, or run `newm-admin --headless login`",
+            environment.display_name(),
+            ENV_EMAIL,
+            ENV_PASSWORD
+        )
+    })?;
+
+    let response = match AuthClient::new().refresh(&refresh_token, environment).await {
+        Ok(response) => response,
+        Err(AuthError::Network(e)) => return Err(format!("Network error: {}", e)),
+        Err(e) => {
+            // The token is no good anymore; don't offer it again
+            remembered.forget(environment)?;
+            return Err(format!("Saved session ended ({}); log in again", e));
+        }
+    };
+    // The old refresh token is spent, keep the new one
+    remembered.save(environment, &response.refresh_token)?;
+    Ok(Session::new(response, environment.clone()).with_remembered(remembered))
+}
+
+async fn login(environment: &Environment) -> Result<(), String> {
+    let (email, password) =
+        credentials().ok_or_else(|| format!("Set {} and {} to log in", ENV_EMAIL, ENV_PASSWORD))?;
+    let response = password_login(environment, &email, &password).await?;
+    RememberedSessions::new().save(environment, &response.refresh_token)?;
+    println!(
+        "Logged in to {} as {}; the session is saved in the keyring",
+        environment.display_name(),
+        email
+    );
+    Ok(())
+}
+
+async fn logout(environment: &Environment) -> Result<(), String> {
+    let remembered = RememberedSessions::new();
+    let Some(refresh_token) = remembered.load(environment)? else {
+        println!("No saved session for {}", environment.display_name());
+        return Ok(());
+    };
+
+    // Invalidate the tokens on the server too; the keyring entry goes either way
+    match AuthClient::new().refresh(&refresh_token, environment).await {
+        Ok(response) => {
+            if let Err(e) = Session::new(response, environment.clone()).logout().await {
+                tracing::warn!("Server logout failed: {}", e);
+            }
+        }
+        Err(e) => tracing::warn!("Saved session already ended: {}", e),
+    }
+    remembered.forget(environment)?;
+    println!("Logged out of {}", environment.display_name());
+    Ok(())
+}
+
+async fn list_earnings(
+    session: &Session,
+    song: Option<&str>,
+    format: OutputFormat,
+) -> Result<(), String> {
+    let client = EarningsClient::new();
+    let earnings = match song {
+        Some(song) => client.get_song_earnings(session, song).await,
+        None => client.get_earnings(session).await,
+    }
+    .map_err(|e| e.to_string())?;
+
+    match format {
+        OutputFormat::Json => println!(
+            "{}",
+            serde_json::to_string_pretty(&earnings).map_err(|e| e.to_string())?
+        ),
+        OutputFormat::Table => print!("{}", earnings_table(&earnings)),
+    }
+    Ok(())
+}
+
+fn earnings_table(earnings: &[Earning]) -> String {
+    let mut table = format!(
+        "{:<36}  {:<36}  {:>20}  {:<7}  {:<10}  {}\n",
+        "ID", "SONG", "AMOUNT (NEWM)", "CLAIMED", "CREATED", "STAKE ADDRESS"
+    );
+    for earning in earnings {
+        table.push_str(&format!(
+            "{:<36}  {:<36}  {:>20}  {:<7}  {:<10}  {}\n",
+            earning.id.as_deref().unwrap_or("-"),
+            earning.song_id.as_deref().unwrap_or("-"),
+            format_amount(earning.amount),
+            if earning.claimed { "yes" } else { "no" },
+            earning.created_at.get(..10).unwrap_or(&earning.created_at),
+            earning.stake_address
+        ));
+    }
+    table
+}
+
+/// Check a royalties CSV against the server and print what would be sent.
+///
+/// Every song is looked up once, so a file with problems is caught before
+/// the first royalty is booked.
+async fn preflight(session: &Session, file: &Path) -> Result<PreflightReport, String> {
+    let mut report = PreflightReport::check(parse_csv(file).map_err(|e| e.to_string())?);
+    for identifier in report.pending_lookups() {
+        let lookup = lookup_song(session, &identifier)
+            .await
+            .map_err(|e| format!("Failed to look up {}: {}", identifier, e))?;
+        report.record_lookup(&identifier, &lookup);
+    }
+
+    for (i, checked) in report.rows.iter().enumerate() {
+        let amount = checked
+            .amount
+            .map(format_amount)
+            .unwrap_or_else(|| checked.row.amount.clone());
+        let status = if checked.is_ready() {
+            checked.title.clone().unwrap_or_else(|| "ok".to_string())
+        } else {
+            checked.errors.join("; ")
+        };
+        println!(
+            "{:>5}  {:<36}  {:>20} {}  {}",
+            i + 1,
+            checked.row.song_id_or_isrc,
+            amount,
+            checked.row.currency,
+            status
+        );
+        for warning in &checked.warnings {
+            println!("{:>5}  warning: {}", "", warning);
+        }
+    }
+    println!("{}", report.summary());
+    Ok(report)
+}
+
+/// Book rows that passed the preflight, writing a results file next to it
+async fn import_earnings(session: &Session, file: &Path, rows: Vec<CsvRow>) -> Result<(), String> {
+    let total = rows.len();
+    let client = EarningsClient::new();
+    let mut results: Vec<CsvResult> = Vec::with_capacity(total);
+    let (mut succeeded, mut failed, mut unconfirmed) = (0usize, 0usize, 0usize);
+    let mut expired = None;
+
+    for (i, row) in rows.into_iter().enumerate() {
+        let result = match parse_amount(&row.amount) {
+            Ok(amount) => {
+                let royalty = AddSongRoyaltyRequest::new(row.currency, amount);
+                match client
+                    .add_earnings(session, &row.song_id_or_isrc, &royalty)
+                    .await
+                {
+                    Ok(()) => {
+                        succeeded += 1;
+                        "Success".to_string()
+                    }
+                    Err(EarningsError::SessionExpired(msg)) => {
+                        expired = Some(msg);
+                        break;
+                    }
+                    Err(EarningsError::Ambiguous(msg)) => {
+                        unconfirmed += 1;
+                        format!("Unconfirmed: {} - check the song's earnings", msg)
+                    }
+                    Err(e) => {
+                        failed += 1;
+                        format!("Error: {}", e)
+                    }
+                }
+            }
+            Err(e) => {
+                failed += 1;
+                format!("Error: Invalid amount - {}", e)
+            }
+        };
+        println!(
+            "[{}/{}] {} {} {}: {}",
+            i + 1,
+            total,
+            row.song_id_or_isrc,
+            row.amount,
+            row.currency,
+            result
+        );
+        results.push(CsvResult { row, result });
+    }
+
+    let output_path = write_results(file, &results).map_err(|e| e.to_string())?;
+    println!(
+        "Imported {}/{} earnings ({} failed, {} unconfirmed). Results saved to {}",
+        succeeded,
+        total,
+        failed,
+        unconfirmed,
+        output_path.display()
+    );
+
+    if let Some(msg) = expired {
+        return Err(format!(
+            "Session expired after {} rows, the rest were not sent: {}",
+            results.len(),
+            msg
+        ));
+    }
+    if unconfirmed > 0 {
+        return Err(format!(
+            "{} rows could not be confirmed; check them before importing again",
+            unconfirmed
+        ));
+    }
+    if failed > 0 {
+        return Err(format!("{} rows failed", failed));
+    }
+    Ok(())
+}
+
+/// Read earning IDs, one per line; blank lines and `#` comments are skipped
+fn read_id_list(path: &Path) -> Result<Vec<String>, String> {
+    let text = std::fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    parse_id_list(&text)
+}
+
+fn parse_id_list(text: &str) -> Result<Vec<String>, String> {
+    let mut ids: Vec<String> = Vec::new();
+    for (i, line) in text.lines().enumerate() {
+        let id = line.trim();
+        if id.is_empty() || id.starts_with('#') {
+            continue;
+        }
+        if !is_uuid(id) {
+            return Err(format!("Line {}: '{}' is not an earning ID", i + 1, id));
+        }
+        if !ids.iter().any(|existing| existing.eq_ignore_ascii_case(id)) {
+            ids.push(id.to_string());
+        }
+    }
+    if ids.is_empty() {
+        return Err("No earning IDs found".to_string());
+    }
+    Ok(ids)
+}
+
+async fn delete_earnings(session: &Session, ids: Vec<String>) -> Result<(), String> {
+    let count = ids.len();
+    EarningsClient::new()
+        .delete_earnings(session, ids)
+        .await
+        .map_err(|e| e.to_string())?;
+    println!(
+        "Deleted {} earnings from {}",
+        count,
+        session.environment().display_name()
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Command, Invocation, OutputFormat, find_environment, parse_args, parse_id_list};
+    use crate::environment::Environment;
+    use std::ffi::OsString;
+    use std::path::PathBuf;
+
+    fn parse(args: &str) -> Result<Invocation, String> {
+        parse_args(args.split_whitespace().map(OsString::from).collect())
+    }
+
+    #[test]
+    fn test_parse_commands() {
+        assert_eq!(
+            parse("--env garage earnings list --song QZ1234567890 --format json").unwrap(),
+            Invocation {
+                environment: Some("garage".to_string()),
+                command: Command::ListEarnings {
+                    song: Some("QZ1234567890".to_string()),
+                    format: OutputFormat::Json,
+                },
+            }
+        );
+        assert_eq!(
+            parse("earnings import royalties.csv --dry-run")
+                .unwrap()
+                .command,
+            Command::ImportEarnings {
+                file: PathBuf::from("royalties.csv"),
+                dry_run: true,
+            }
+        );
+        assert_eq!(
+            parse("earnings delete --ids-from ids.txt").unwrap().command,
+            Command::DeleteEarnings {
+                ids_from: PathBuf::from("ids.txt"),
+                yes: false,
+            }
+        );
+        assert_eq!(parse("login").unwrap().command, Command::Login);
+        assert_eq!(
+            parse("earnings list --help").unwrap().command,
+            Command::Help
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        for args in [
+            "",
+            "songs",
+            "earnings",
+            "earnings list --format xml",
+            "earnings import",
+            "earnings delete",
+            "login extra",
+        ] {
+            assert!(parse(args).is_err(), "accepted {:?}", args);
+        }
+    }
+
+    #[test]
+    fn test_parse_id_list() {
+        let ids = parse_id_list(
+            "# duplicate payout, see ticket\n\
+             6f2d1c9a-0000-4000-8000-000000000001\n\
+             \n  6f2d1c9a-0000-4000-8000-000000000002  \n\
+             6f2d1c9a-0000-4000-8000-000000000001\n",
+        )
+        .unwrap();
+        assert_eq!(
+            ids,
+            vec![
+                "6f2d1c9a-0000-4000-8000-000000000001",
+                "6f2d1c9a-0000-4000-8000-000000000002"
+            ]
+        );
+
+        let error =
+            parse_id_list("6f2d1c9a-0000-4000-8000-000000000001\nQZ1234567890").unwrap_err();
+        assert!(error.starts_with("Line 2"), "{}", error);
+        assert!(parse_id_list("# nothing here\n").is_err());
+    }
+
+    #[test]
+    fn test_find_environment() {
+        let environments = [Environment::garage(), Environment::studio()];
+        assert_eq!(
+            find_environment(&environments, "STUDIO").unwrap(),
+            Environment::studio()
+        );
+        let error = find_environment(&environments, "prod").unwrap_err();
+        assert!(error.contains("Garage"), "{}", error);
+    }
+}
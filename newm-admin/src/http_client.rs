@@ -3,21 +3,196 @@
 //! Studio traffic is fronted by an AWS load balancer that rejects requests without a non-empty
 //! `User-Agent` header (returns an HTML 403). To keep behavior consistent across environments,
 //! we configure a default `User-Agent` for all outbound reqwest requests.
+//!
+//! Timeouts, an explicit proxy and extra trusted CA certificates come from the `http` section of
+//! `settings.json` (see [`TransportSettings`]). Without a `proxy` entry the usual `HTTPS_PROXY` /
+//! `HTTP_PROXY` / `NO_PROXY` environment variables apply.
+//!
+//! The client is built once and shared: reqwest clients are cheap to clone and keep one
+//! connection pool between them.
+
+use std::path::Path;
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use reqwest::{Certificate, Client, NoProxy, Proxy, header};
 
-use reqwest::{Client, header};
+use crate::settings::{Settings, TransportSettings};
+use crate::storage;
 
 const USER_AGENT: &str = concat!("newm-admin/", env!("CARGO_PKG_VERSION"));
 
-pub fn new_client() -> Client {
-    Client::builder()
-        .default_headers({
-            let mut headers = header::HeaderMap::new();
-            headers.insert(
-                header::USER_AGENT,
-                header::HeaderValue::from_static(USER_AGENT),
-            );
-            headers
-        })
+/// The process-wide client, configured from the settings file
+pub fn shared() -> Client {
+    configured().0.clone()
+}
+
+/// Why the `http` settings were ignored in favour of the defaults, if they were
+pub fn settings_error() -> Option<&'static str> {
+    configured().1.as_deref()
+}
+
+fn configured() -> &'static (Client, Option<String>) {
+    static CLIENT: OnceLock<(Client, Option<String>)> = OnceLock::new();
+    CLIENT.get_or_init(|| {
+        let dir = storage::config_dir().unwrap_or_default();
+        match build_client(&Settings::shared().http, &dir) {
+            Ok(client) => (client, None),
+            Err(e) => {
+                tracing::error!("Ignoring http settings: {}", e);
+                let client = build_client(&TransportSettings::default(), &dir)
+                    .expect("Failed to build reqwest client");
+                (client, Some(e))
+            }
+        }
+    })
+}
+
+/// Build a client from transport settings; relative certificate paths are resolved against `dir`
+pub fn build_client(settings: &TransportSettings, dir: &Path) -> Result<Client, String> {
+    let mut builder = Client::builder().default_headers({
+        let mut headers = header::HeaderMap::new();
+        headers.insert(
+            header::USER_AGENT,
+            header::HeaderValue::from_static(USER_AGENT),
+        );
+        headers
+    });
+
+    // 0 means no limit
+    let seconds = |secs: u64| (secs > 0).then(|| Duration::from_secs(secs));
+    if let Some(timeout) = seconds(settings.connect_timeout_secs) {
+        builder = builder.connect_timeout(timeout);
+    }
+    if let Some(timeout) = seconds(settings.read_timeout_secs) {
+        builder = builder.read_timeout(timeout);
+    }
+    if let Some(timeout) = seconds(settings.timeout_secs) {
+        builder = builder.timeout(timeout);
+    }
+
+    if let Some(url) = settings
+        .proxy
+        .as_deref()
+        .filter(|url| !url.trim().is_empty())
+    {
+        // The URL may carry credentials, so keep it out of the message
+        let proxy = Proxy::all(url.trim())
+            .map_err(|e| format!("Invalid proxy setting: {}", e))?
+            .no_proxy(settings.no_proxy.as_deref().and_then(NoProxy::from_string));
+        builder = builder.proxy(proxy);
+    }
+
+    for file in &settings.ca_certificates {
+        let path = dir.join(file);
+        let pem = std::fs::read(&path)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        let certificates = Certificate::from_pem_bundle(&pem)
+            .map_err(|e| format!("Failed to parse {}: {}", file, e))?;
+        if certificates.is_empty() {
+            return Err(format!("{} contains no PEM certificates", file));
+        }
+        for certificate in certificates {
+            builder = builder.add_root_certificate(certificate);
+        }
+    }
+
+    builder
         .build()
-        .expect("Failed to build reqwest client")
+        .map_err(|e| format!("Failed to build HTTP client: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::build_client;
+    use crate::settings::TransportSettings;
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+
+    /// Self-signed test CA (P-256), not trusted anywhere
+    const TEST_CA: &str = "-----BEGIN CERTIFICATE-----
+MIIBhTCCASugAwIBAgIUOt84mHf43WYi6s517rl3qIVrPb0wCgYIKoZIzj0EAwIw
+FzEVMBMGA1UEAwwMTkVXTSBUZXN0IENBMCAXDTI2MTAxODEyMDExOVoYDzIxMjYw
+OTI0MTIwMTE5WjAXMRUwEwYDVQQDDAxORVdNIFRlc3QgQ0EwWTATBgcqhkjOPQIB
+BggqhkjOPQMBBwNCAASiFghpVAvcGIhTc5e3bkUzyZRpWbMFpbdm1e7/Mzaybm7n
+6lU5Q7irHz5M/uopl+vTsSBfKgnf2RM25QGuCHFfo1MwUTAdBgNVHQ4EFgQUoADJ
+5Yk2DXX5mI5s4ZkbSB1u27AwHwYDVR0jBBgwFoAUoADJ5Yk2DXX5mI5s4ZkbSB1u
+27AwDwYDVR0TAQH/BAUwAwEB/zAKBggqhkjOPQQDAgNIADBFAiEAlZfu9hHmcm6v
+OPkL4SzSgRqyy89yLNhKa7MP0GFGjBUCIGM2fM3EdZOp/Aen87qeXJO7CmoWj7gQ
+DFjo7WVwy0IG
+-----END CERTIFICATE-----
+";
+
+    #[test]
+    fn test_default_settings_build() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(build_client(&TransportSettings::default(), dir.path()).is_ok());
+    }
+
+    #[test]
+    fn test_ca_certificates() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("staging-ca.pem"), TEST_CA).unwrap();
+        std::fs::write(dir.path().join("empty.pem"), "not a certificate").unwrap();
+        let with_ca = |file: &str| TransportSettings {
+            ca_certificates: vec![file.to_string()],
+            ..Default::default()
+        };
+
+        assert!(build_client(&with_ca("staging-ca.pem"), dir.path()).is_ok());
+        let error = build_client(&with_ca("missing.pem"), dir.path()).unwrap_err();
+        assert!(error.contains("missing.pem"), "{}", error);
+        assert!(build_client(&with_ca("empty.pem"), dir.path()).is_err());
+    }
+
+    #[test]
+    fn test_invalid_proxy() {
+        let dir = tempfile::tempdir().unwrap();
+        let settings = TransportSettings {
+            proxy: Some("http://admin:hunter2@[::1".to_string()),
+            ..Default::default()
+        };
+        let error = build_client(&settings, dir.path()).unwrap_err();
+        assert!(!error.contains("hunter2"), "{}", error);
+    }
+
+    #[test]
+    fn test_requests_go_through_proxy() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let proxy = std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut buf = [0u8; 4096];
+            let n = stream.read(&mut buf).unwrap();
+            let _ = write!(
+                stream,
+                "HTTP/1.1 204 No Content\r\nConnection: close\r\n\r\n"
+            );
+            String::from_utf8_lossy(&buf[..n])
+                .lines()
+                .next()
+                .unwrap_or_default()
+                .to_string()
+        });
+
+        let dir = tempfile::tempdir().unwrap();
+        let settings = TransportSettings {
+            proxy: Some(format!("http://127.0.0.1:{}", port)),
+            ..Default::default()
+        };
+        let client = build_client(&settings, dir.path()).unwrap();
+        let response = futures::executor::block_on(async_compat::Compat::new(async {
+            client
+                .get("http://staging.newm.invalid/v1/ping")
+                .send()
+                .await
+        }))
+        .unwrap();
+
+        assert_eq!(response.status(), 204);
+        assert_eq!(
+            proxy.join().unwrap(),
+            "GET http://staging.newm.invalid/v1/ping HTTP/1.1"
+        );
+    }
 }
@@ -1,11 +1,24 @@
+mod api;
 mod app;
+mod audit;
 mod auth;
+mod cardano;
+mod cardano_admin;
+mod cli_key;
 mod colors;
 mod csv_import;
 mod earnings;
+mod environment;
+mod headless;
 mod http_client;
 mod jwt;
+mod oauth;
+mod preflight;
+mod remember;
 mod session;
+mod settings;
+mod songs;
+mod storage;
 mod toast;
 mod views;
 
@@ -16,6 +29,20 @@ use gpui_component_assets::Assets;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
 fn main() {
+    // `newm-admin --headless <command>` runs without a window
+    let args: Vec<std::ffi::OsString> = std::env::args_os().skip(1).collect();
+    if args.first().is_some_and(|arg| arg == "--headless") {
+        // Keep stdout for command output
+        tracing_subscriber::registry()
+            .with(tracing_subscriber::fmt::layer().with_writer(std::io::stderr))
+            .with(
+                tracing_subscriber::EnvFilter::try_from_default_env()
+                    .unwrap_or_else(|_| tracing_subscriber::EnvFilter::new("newm_admin=warn")),
+            )
+            .init();
+        std::process::exit(headless::run(args[1..].to_vec()));
+    }
+
     // ... tracing init ...
     tracing_subscriber::registry()
         .with(tracing_subscriber::fmt::layer())
@@ -55,6 +82,11 @@ fn main() {
                     cx.new(|cx| Root::new(view, window, cx))
                 },
             )?;
+            // Otherwise the admin only finds out from the log
+            if let Some(e) = http_client::settings_error() {
+                let message = format!("Ignoring http settings, using defaults: {}", e);
+                cx.update(|cx| toast::show_warning_async(cx, message))?;
+            }
             Ok::<_, anyhow::Error>(())
         })
         .detach();
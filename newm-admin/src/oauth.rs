@@ -0,0 +1,522 @@
+//! OAuth Browser Sign-In
+//!
+//! Opens the provider's consent page in the system browser and captures the
+//! authorization code on a one-shot HTTP listener bound to 127.0.0.1. The
+//! code is then handed to the server (`POST /v1/auth/login/{provider}`),
+//! which exchanges it using its own client secret.
+//!
+//! The request carries a PKCE challenge (RFC 7636) and the server passes the
+//! matching verifier along with the code, so a code intercepted on the way
+//! back to the loopback listener cannot be redeemed by anyone else.
+//!
+//! The listener always uses port 47320, because the server redeems the code
+//! with its own web client and Google only accepts redirect URIs registered
+//! for it exactly. `http://127.0.0.1:47320/callback` must therefore be added
+//! to the authorized redirect URIs of the environment's OAuth client. Sign in
+//! with Apple is not offered: Apple rejects loopback redirect URIs.
+
+use std::io::{ErrorKind, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use reqwest::Url;
+use sha2::{Digest, Sha256};
+
+use crate::auth::AuthError;
+
+/// How long to wait for the browser to come back
+const CALLBACK_TIMEOUT: Duration = Duration::from_secs(5 * 60);
+
+/// How often to check for a connection or cancellation
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Port the provider redirects back to; registered with the server's client
+const CALLBACK_PORT: u16 = 47320;
+
+/// Path the provider redirects back to
+const CALLBACK_PATH: &str = "/callback";
+
+/// Largest callback request we are willing to read
+const MAX_REQUEST_BYTES: usize = 16 * 1024;
+
+/// OAuth providers the server accepts
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OAuthProvider {
+    Google,
+}
+
+impl OAuthProvider {
+    pub const ALL: [OAuthProvider; 1] = [OAuthProvider::Google];
+
+    /// Path segment of the server's login endpoint
+    pub fn path(&self) -> &'static str {
+        match self {
+            OAuthProvider::Google => "google",
+        }
+    }
+
+    /// Display name for UI
+    pub fn label(&self) -> &'static str {
+        match self {
+            OAuthProvider::Google => "Google",
+        }
+    }
+
+    fn authorize_endpoint(&self) -> &'static str {
+        match self {
+            OAuthProvider::Google => "https://accounts.google.com/o/oauth2/v2/auth",
+        }
+    }
+
+    /// Consent page URL that redirects back to `redirect_uri` with a code
+    pub fn authorization_url(
+        &self,
+        client_id: &str,
+        redirect_uri: &str,
+        state: &str,
+        pkce: &Pkce,
+    ) -> String {
+        let mut url = Url::parse(self.authorize_endpoint()).expect("valid authorize endpoint");
+        {
+            let mut query = url.query_pairs_mut();
+            query
+                .append_pair("client_id", client_id)
+                .append_pair("redirect_uri", redirect_uri)
+                .append_pair("response_type", "code")
+                .append_pair("state", state)
+                .append_pair("code_challenge", &pkce.challenge())
+                .append_pair("code_challenge_method", "S256");
+            match self {
+                OAuthProvider::Google => {
+                    query
+                        .append_pair("scope", "openid email profile")
+                        .append_pair("prompt", "select_account");
+                }
+            }
+        }
+        url.into()
+    }
+}
+
+/// Random value tying the callback to the request we started
+pub fn new_state() -> String {
+    hex::encode(rand::random::<[u8; 16]>())
+}
+
+/// PKCE code verifier for one sign-in attempt
+#[derive(Clone)]
+pub struct Pkce {
+    verifier: String,
+}
+
+impl Pkce {
+    /// 32 random bytes, giving the 43-character verifier RFC 7636 recommends
+    pub fn new() -> Self {
+        Self {
+            verifier: URL_SAFE_NO_PAD.encode(rand::random::<[u8; 32]>()),
+        }
+    }
+
+    /// Secret sent with the code when it is exchanged
+    pub fn verifier(&self) -> &str {
+        &self.verifier
+    }
+
+    /// S256 challenge sent with the authorization request
+    pub fn challenge(&self) -> String {
+        URL_SAFE_NO_PAD.encode(Sha256::digest(self.verifier.as_bytes()))
+    }
+}
+
+impl std::fmt::Debug for Pkce {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Pkce").finish_non_exhaustive()
+    }
+}
+
+/// Extract the authorization code from a callback request target.
+///
+/// Returns `None` for requests to other paths (a browser may ask for
+/// `/favicon.ico` first).
+fn parse_callback(target: &str, expected_state: &str) -> Option<Result<String, AuthError>> {
+    let url = Url::parse(&format!("http://127.0.0.1{}", target)).ok()?;
+    if url.path() != CALLBACK_PATH {
+        return None;
+    }
+
+    let param = |name: &str| {
+        url.query_pairs()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.into_owned())
+    };
+
+    if let Some(error) = param("error") {
+        let message = match param("error_description") {
+            Some(description) => format!("{} ({})", error, description),
+            None => error,
+        };
+        return Some(Err(AuthError::OAuth(message)));
+    }
+    if param("state").as_deref() != Some(expected_state) {
+        return Some(Err(AuthError::OAuth(
+            "State mismatch in browser callback".to_string(),
+        )));
+    }
+    Some(
+        param("code")
+            .filter(|code| !code.is_empty())
+            .ok_or_else(|| AuthError::OAuth("No authorization code in callback".to_string())),
+    )
+}
+
+/// One-shot HTTP listener for the provider's redirect
+pub struct LoopbackListener {
+    listener: TcpListener,
+    port: u16,
+}
+
+impl LoopbackListener {
+    /// Listen on [`CALLBACK_PORT`] on 127.0.0.1
+    pub fn bind() -> Result<Self, AuthError> {
+        Self::bind_port(CALLBACK_PORT)
+    }
+
+    /// Listen on `port` on 127.0.0.1, or on a free port if it is 0
+    fn bind_port(port: u16) -> Result<Self, AuthError> {
+        let listener = TcpListener::bind(("127.0.0.1", port))
+            .and_then(|listener| {
+                listener.set_nonblocking(true)?;
+                Ok(listener)
+            })
+            .map_err(|e| {
+                AuthError::OAuth(format!(
+                    "Could not start callback listener on port {}: {}",
+                    port, e
+                ))
+            })?;
+        let port = listener
+            .local_addr()
+            .map_err(|e| AuthError::OAuth(e.to_string()))?
+            .port();
+        Ok(Self { listener, port })
+    }
+
+    /// Redirect URI to register with the provider
+    pub fn redirect_uri(&self) -> String {
+        format!("http://127.0.0.1:{}{}", self.port, CALLBACK_PATH)
+    }
+
+    /// Block until the browser delivers a code, the wait is cancelled, or it times out
+    pub fn wait_for_code(
+        self,
+        expected_state: &str,
+        cancelled: &AtomicBool,
+    ) -> Result<String, AuthError> {
+        let deadline = Instant::now() + CALLBACK_TIMEOUT;
+        loop {
+            if cancelled.load(Ordering::Relaxed) {
+                return Err(AuthError::OAuth("Cancelled".to_string()));
+            }
+            if Instant::now() >= deadline {
+                return Err(AuthError::OAuth(
+                    "Timed out waiting for the browser".to_string(),
+                ));
+            }
+
+            match self.listener.accept() {
+                Ok((stream, _)) => {
+                    if let Some(result) = Self::handle(stream, expected_state) {
+                        return result;
+                    }
+                }
+                Err(e) if e.kind() == ErrorKind::WouldBlock => std::thread::sleep(POLL_INTERVAL),
+                Err(e) => return Err(AuthError::OAuth(e.to_string())),
+            }
+        }
+    }
+
+    /// Answer one request, returning the outcome if it was the callback
+    fn handle(mut stream: TcpStream, expected_state: &str) -> Option<Result<String, AuthError>> {
+        let target = match Self::read_request_target(&mut stream) {
+            Ok(target) => target,
+            Err(e) => {
+                tracing::debug!("Ignoring malformed callback request: {}", e);
+                return None;
+            }
+        };
+
+        let result = parse_callback(&target, expected_state);
+        let (status, message) = match &result {
+            None => ("404 Not Found", "Not found".to_string()),
+            Some(Ok(_)) => (
+                "200 OK",
+                "Signed in. You can close this window and return to NEWM Admin.".to_string(),
+            ),
+            Some(Err(e)) => ("400 Bad Request", e.to_string()),
+        };
+        let body = format!(
+            "<!DOCTYPE html><html><head><title>NEWM Admin</title></head>\
+             <body style=\"font-family: sans-serif\"><p>{}</p></body></html>",
+            html_escape(&message)
+        );
+        let response = format!(
+            "HTTP/1.1 {}\r\nContent-Type: text/html; charset=utf-8\r\n\
+             Content-Length: {}\r\nConnection: close\r\n\r\n{}",
+            status,
+            body.len(),
+            body
+        );
+        if let Err(e) = stream.write_all(response.as_bytes()) {
+            tracing::debug!("Failed to answer browser callback: {}", e);
+        }
+        result
+    }
+
+    /// Read the request line and return its target, e.g. `/callback?code=...`
+    fn read_request_target(stream: &mut TcpStream) -> Result<String, String> {
+        stream
+            .set_nonblocking(false)
+            .and_then(|_| stream.set_read_timeout(Some(Duration::from_secs(5))))
+            .map_err(|e| e.to_string())?;
+
+        let mut request = Vec::new();
+        let mut buf = [0u8; 1024];
+        while !request.windows(2).any(|w| w == b"\r\n") {
+            let n = stream.read(&mut buf).map_err(|e| e.to_string())?;
+            if n == 0 || request.len() + n > MAX_REQUEST_BYTES {
+                return Err("Incomplete request".to_string());
+            }
+            request.extend_from_slice(&buf[..n]);
+        }
+
+        let request = String::from_utf8_lossy(&request);
+        let mut parts = request.lines().next().unwrap_or_default().split(' ');
+        match (parts.next(), parts.next()) {
+            (Some("GET"), Some(target)) => Ok(target.to_string()),
+            _ => Err("Expected a GET request".to_string()),
+        }
+    }
+}
+
+/// A browser sign-in in progress: the listener, state and PKCE verifier that
+/// belong to one authorization request
+pub struct OAuthAttempt {
+    provider: OAuthProvider,
+    listener: LoopbackListener,
+    state: String,
+    pkce: Pkce,
+}
+
+/// Authorization code and what the server needs to redeem it
+pub struct OAuthCode {
+    pub provider: OAuthProvider,
+    pub code: String,
+    pub redirect_uri: String,
+    pub code_verifier: String,
+}
+
+impl OAuthAttempt {
+    /// Listen for the callback of a new sign-in with `provider`
+    pub fn start(provider: OAuthProvider) -> Result<Self, AuthError> {
+        Ok(Self::with_listener(provider, LoopbackListener::bind()?))
+    }
+
+    fn with_listener(provider: OAuthProvider, listener: LoopbackListener) -> Self {
+        Self {
+            provider,
+            listener,
+            state: new_state(),
+            pkce: Pkce::new(),
+        }
+    }
+
+    /// Consent page to open in the browser
+    pub fn authorization_url(&self, client_id: &str) -> String {
+        self.provider.authorization_url(
+            client_id,
+            &self.listener.redirect_uri(),
+            &self.state,
+            &self.pkce,
+        )
+    }
+
+    /// Block until the browser comes back with a code, the wait is cancelled, or it times out
+    pub fn wait(self, cancelled: &AtomicBool) -> Result<OAuthCode, AuthError> {
+        let redirect_uri = self.listener.redirect_uri();
+        let code = self.listener.wait_for_code(&self.state, cancelled)?;
+        Ok(OAuthCode {
+            provider: self.provider,
+            code,
+            redirect_uri,
+            code_verifier: self.pkce.verifier().to_string(),
+        })
+    }
+}
+
+/// Escape text for an HTML body
+fn html_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{LoopbackListener, OAuthAttempt, OAuthProvider, Pkce, new_state, parse_callback};
+    use crate::auth::AuthError;
+    use reqwest::Url;
+    use std::io::{Read, Write};
+    use std::net::TcpStream;
+    use std::sync::atomic::AtomicBool;
+
+    #[test]
+    fn test_authorization_url() {
+        let pkce = Pkce::new();
+        let url = OAuthProvider::Google.authorization_url(
+            "client-1",
+            "http://127.0.0.1:4000/callback",
+            "abc",
+            &pkce,
+        );
+        assert!(url.starts_with("https://accounts.google.com/o/oauth2/v2/auth?"));
+        assert!(url.contains("client_id=client-1"));
+        assert!(url.contains("redirect_uri=http%3A%2F%2F127.0.0.1%3A4000%2Fcallback"));
+        assert!(url.contains("response_type=code"));
+        assert!(url.contains("state=abc"));
+        assert!(url.contains(&format!("code_challenge={}", pkce.challenge())));
+        assert!(url.contains("code_challenge_method=S256"));
+        assert!(!url.contains(pkce.verifier()));
+    }
+
+    #[test]
+    fn test_pkce() {
+        // RFC 7636 appendix B
+        let pkce = Pkce {
+            verifier: "dBjftJeZ4CVP-mB92K27uhbUJU1p1r_wW1gFWFOEjXk".to_string(),
+        };
+        assert_eq!(
+            pkce.challenge(),
+            "E9Melhoa2OwvFrEMTJguCHaoeK1t8URWbuGJSstw-cM"
+        );
+
+        let pkce = Pkce::new();
+        assert_eq!(pkce.verifier().len(), 43);
+        assert_ne!(pkce.verifier(), Pkce::new().verifier());
+        assert!(!format!("{:?}", pkce).contains(pkce.verifier()));
+    }
+
+    #[test]
+    fn test_new_state_is_random() {
+        let state = new_state();
+        assert_eq!(state.len(), 32);
+        assert_ne!(state, new_state());
+    }
+
+    #[test]
+    fn test_parse_callback() {
+        assert_eq!(
+            parse_callback("/callback?state=s1&code=4%2F0Ab", "s1")
+                .unwrap()
+                .unwrap(),
+            "4/0Ab"
+        );
+        assert!(parse_callback("/favicon.ico", "s1").is_none());
+        assert!(matches!(
+            parse_callback("/callback?state=other&code=c", "s1"),
+            Some(Err(AuthError::OAuth(_)))
+        ));
+        assert!(matches!(
+            parse_callback("/callback?state=s1", "s1"),
+            Some(Err(AuthError::OAuth(_)))
+        ));
+        match parse_callback("/callback?error=access_denied&state=s1", "s1") {
+            Some(Err(AuthError::OAuth(msg))) => assert!(msg.contains("access_denied")),
+            other => panic!("unexpected {:?}", other.map(|r| r.is_ok())),
+        }
+    }
+
+    #[test]
+    fn test_loopback_receives_code() {
+        let listener = LoopbackListener::bind_port(0).unwrap();
+        let redirect_uri = listener.redirect_uri();
+        let port = redirect_uri
+            .trim_start_matches("http://127.0.0.1:")
+            .trim_end_matches("/callback")
+            .to_string();
+
+        let browser = std::thread::spawn(move || {
+            let get = |target: &str| {
+                let mut stream = TcpStream::connect(format!("127.0.0.1:{}", port)).unwrap();
+                write!(stream, "GET {} HTTP/1.1\r\nHost: 127.0.0.1\r\n\r\n", target).unwrap();
+                let mut response = String::new();
+                stream.read_to_string(&mut response).unwrap();
+                response
+            };
+            let favicon = get("/favicon.ico");
+            let callback = get("/callback?code=the-code&state=xyz");
+            (favicon, callback)
+        });
+
+        let code = listener
+            .wait_for_code("xyz", &AtomicBool::new(false))
+            .unwrap();
+        assert_eq!(code, "the-code");
+
+        let (favicon, callback) = browser.join().unwrap();
+        assert!(favicon.starts_with("HTTP/1.1 404"));
+        assert!(callback.starts_with("HTTP/1.1 200"));
+    }
+
+    #[test]
+    fn test_attempt_returns_code_with_verifier() {
+        let attempt = OAuthAttempt::with_listener(
+            OAuthProvider::Google,
+            LoopbackListener::bind_port(0).unwrap(),
+        );
+        let url = Url::parse(&attempt.authorization_url("client-1")).unwrap();
+        let param = |name: &str| {
+            url.query_pairs()
+                .find(|(key, _)| key == name)
+                .map(|(_, value)| value.into_owned())
+                .unwrap()
+        };
+        let (redirect_uri, state, challenge) = (
+            param("redirect_uri"),
+            param("state"),
+            param("code_challenge"),
+        );
+
+        let callback = Url::parse(&redirect_uri).unwrap();
+        let browser = std::thread::spawn(move || {
+            let mut stream = TcpStream::connect(("127.0.0.1", callback.port().unwrap())).unwrap();
+            write!(
+                stream,
+                "GET /callback?code=the-code&state={} HTTP/1.1\r\nHost: 127.0.0.1\r\n\r\n",
+                state
+            )
+            .unwrap();
+            let mut response = String::new();
+            stream.read_to_string(&mut response).unwrap();
+        });
+
+        let code = attempt.wait(&AtomicBool::new(false)).unwrap();
+        browser.join().unwrap();
+        assert_eq!(code.provider, OAuthProvider::Google);
+        assert_eq!(code.code, "the-code");
+        assert_eq!(code.redirect_uri, redirect_uri);
+        let pkce = Pkce {
+            verifier: code.code_verifier,
+        };
+        assert_eq!(pkce.challenge(), challenge);
+    }
+
+    #[test]
+    fn test_cancelled_wait() {
+        let listener = LoopbackListener::bind_port(0).unwrap();
+        let result = listener.wait_for_code("xyz", &AtomicBool::new(true));
+        assert!(matches!(result, Err(AuthError::OAuth(_))));
+    }
+}
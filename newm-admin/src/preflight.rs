@@ -0,0 +1,301 @@
+//! CSV Import Preflight
+//!
+//! Checks a royalties CSV before anything is booked. Every amount must parse,
+//! every identifier must be a song UUID or an ISRC in the server's format,
+//! and every song must exist on the server. The report lists the problems of
+//! each row and the totals per currency, so the admin can fix the file or
+//! confirm the import knowing exactly what will be sent.
+
+use crate::csv_import::CsvRow;
+use crate::earnings::{RoyaltyCurrency, format_amount, parse_amount};
+use crate::session::Session;
+use crate::songs::{SongsClient, SongsError, is_isrc, is_uuid};
+
+/// A CSV row and what the preflight found out about it
+#[derive(Debug, Clone)]
+pub struct CheckedRow {
+    pub row: CsvRow,
+    /// Amount with 6 decimal places, if it parsed
+    pub amount: Option<i64>,
+    /// Set once the song was found on the server
+    pub found: bool,
+    /// Song title, when the server has one
+    pub title: Option<String>,
+    /// Reasons the row cannot be imported
+    pub errors: Vec<String>,
+    /// Worth a second look, but not blocking
+    pub warnings: Vec<String>,
+}
+
+impl CheckedRow {
+    pub fn is_ready(&self) -> bool {
+        self.errors.is_empty()
+    }
+}
+
+/// Result of looking a song up on the server
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SongLookup {
+    Found { title: Option<String> },
+    Missing(String),
+}
+
+/// Identifier as the server compares it: lowercase UUID or bare uppercase ISRC
+fn song_key(identifier: &str) -> Option<String> {
+    let identifier = identifier.trim();
+    if is_uuid(identifier) {
+        Some(identifier.to_lowercase())
+    } else if is_isrc(identifier) {
+        Some(identifier.replace('-', "").to_uppercase())
+    } else {
+        None
+    }
+}
+
+/// Preflight report for a whole CSV file
+#[derive(Debug, Clone)]
+pub struct PreflightReport {
+    pub rows: Vec<CheckedRow>,
+}
+
+impl PreflightReport {
+    /// Check amounts and identifiers without calling the server
+    pub fn check(rows: Vec<CsvRow>) -> Self {
+        let mut checked: Vec<CheckedRow> = Vec::with_capacity(rows.len());
+        for row in rows {
+            let mut errors = Vec::new();
+            let mut warnings = Vec::new();
+
+            let amount = match parse_amount(&row.amount) {
+                Ok(0) => {
+                    errors.push("Amount must be greater than zero".to_string());
+                    None
+                }
+                Ok(amount) => Some(amount),
+                Err(e) => {
+                    errors.push(format!("Invalid amount '{}': {}", row.amount, e));
+                    None
+                }
+            };
+
+            match song_key(&row.song_id_or_isrc) {
+                Some(key) => {
+                    // Paying the same song twice in one file is usually a mistake
+                    if let Some(first) = checked
+                        .iter()
+                        .position(|c| song_key(&c.row.song_id_or_isrc).as_ref() == Some(&key))
+                    {
+                        warnings.push(format!("Same song as row {}", first + 1));
+                    }
+                }
+                None => errors.push(format!(
+                    "'{}' is not a song UUID or ISRC",
+                    row.song_id_or_isrc
+                )),
+            }
+
+            checked.push(CheckedRow {
+                row,
+                amount,
+                found: false,
+                title: None,
+                errors,
+                warnings,
+            });
+        }
+        Self { rows: checked }
+    }
+
+    /// Identifiers to look up on the server, each song once
+    pub fn pending_lookups(&self) -> Vec<String> {
+        let mut keys: Vec<String> = Vec::new();
+        let mut lookups = Vec::new();
+        for checked in &self.rows {
+            if let Some(key) = song_key(&checked.row.song_id_or_isrc)
+                && !keys.contains(&key)
+            {
+                keys.push(key);
+                lookups.push(checked.row.song_id_or_isrc.trim().to_string());
+            }
+        }
+        lookups
+    }
+
+    /// Apply the result of looking up `identifier` to every row for that song
+    pub fn record_lookup(&mut self, identifier: &str, lookup: &SongLookup) {
+        let key = song_key(identifier);
+        for checked in self
+            .rows
+            .iter_mut()
+            .filter(|c| key.is_some() && song_key(&c.row.song_id_or_isrc) == key)
+        {
+            match lookup {
+                SongLookup::Found { title } => {
+                    checked.found = true;
+                    checked.title = title.clone();
+                }
+                SongLookup::Missing(reason) => checked.errors.push(reason.clone()),
+            }
+        }
+    }
+
+    pub fn ready_count(&self) -> usize {
+        self.rows.iter().filter(|c| c.is_ready()).count()
+    }
+
+    pub fn problem_count(&self) -> usize {
+        self.rows.len() - self.ready_count()
+    }
+
+    /// Whether the import can go ahead: rows to send and none with problems
+    pub fn is_ready(&self) -> bool {
+        !self.rows.is_empty() && self.problem_count() == 0
+    }
+
+    /// Sum of the importable amounts in `currency` (6 decimal places)
+    pub fn total(&self, currency: RoyaltyCurrency) -> i64 {
+        self.rows
+            .iter()
+            .filter(|c| c.is_ready() && c.row.currency == currency)
+            .filter_map(|c| c.amount)
+            .fold(0i64, |total, amount| total.saturating_add(amount))
+    }
+
+    /// One-line summary with counts and totals
+    pub fn summary(&self) -> String {
+        format!(
+            "{} rows: {} ready, {} with problems. Totals: {} USD, {} NEWM",
+            self.rows.len(),
+            self.ready_count(),
+            self.problem_count(),
+            format_amount(self.total(RoyaltyCurrency::Usd)),
+            format_amount(self.total(RoyaltyCurrency::Newm))
+        )
+    }
+}
+
+/// Look a song up the way the import endpoint will resolve it.
+///
+/// ISRCs go through `GET /v1/songs/isrc/{isrc}`, which matches them the same
+/// way the import does. UUIDs are checked with the song itself, as the
+/// earnings endpoint does not check them.
+///
+/// # Returns
+/// * `Ok(SongLookup)` whether or not the song exists
+/// * `Err(SongsError)` if the session expired or the server could not be reached
+pub async fn lookup_song(session: &Session, identifier: &str) -> Result<SongLookup, SongsError> {
+    let songs = SongsClient::new();
+    let song = if is_uuid(identifier) {
+        songs.get_song(session, identifier).await
+    } else {
+        songs.get_song_by_isrc(session, identifier).await
+    };
+    match song {
+        Ok(song) => Ok(SongLookup::Found { title: song.title }),
+        Err(e) => missing(identifier, e),
+    }
+}
+
+/// Classify a failed lookup: the song is missing unless the failure was ours
+fn missing(identifier: &str, error: SongsError) -> Result<SongLookup, SongsError> {
+    match error {
+        SongsError::SessionExpired(_) | SongsError::Network(_) => Err(error),
+        SongsError::NotFound(_) => Ok(SongLookup::Missing(format!(
+            "No song found for {}",
+            identifier
+        ))),
+        SongsError::Api { message, .. } => Ok(SongLookup::Missing(format!(
+            "Song lookup failed: {}",
+            message
+        ))),
+        e => Ok(SongLookup::Missing(format!("Song lookup failed: {}", e))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{PreflightReport, SongLookup};
+    use crate::csv_import::CsvRow;
+    use crate::earnings::RoyaltyCurrency;
+
+    const SONG: &str = "1b936e39-3a99-47f8-842e-f7a020c4e2bf";
+
+    fn row(identifier: &str, amount: &str, currency: RoyaltyCurrency) -> CsvRow {
+        CsvRow {
+            song_id_or_isrc: identifier.to_string(),
+            amount: amount.to_string(),
+            currency,
+        }
+    }
+
+    fn report() -> PreflightReport {
+        PreflightReport::check(vec![
+            row(SONG, "10.50", RoyaltyCurrency::Usd),
+            row("IE-LOI-23-01693", "25", RoyaltyCurrency::Usd),
+            row("IELOI2301693", "3", RoyaltyCurrency::Newm),
+            row("not-a-song", "1", RoyaltyCurrency::Usd),
+            row(SONG, "1e3", RoyaltyCurrency::Usd),
+            row("QZ1234567890", "0", RoyaltyCurrency::Usd),
+        ])
+    }
+
+    #[test]
+    fn test_offline_checks() {
+        let report = report();
+        assert!(report.rows[0].is_ready());
+        assert!(report.rows[1].is_ready());
+        // Same ISRC written without hyphens
+        assert_eq!(report.rows[2].warnings, vec!["Same song as row 2"]);
+        assert!(report.rows[3].errors[0].contains("not a song UUID or ISRC"));
+        assert!(report.rows[4].errors[0].starts_with("Invalid amount"));
+        assert_eq!(report.rows[4].warnings, vec!["Same song as row 1"]);
+        assert_eq!(
+            report.rows[5].errors,
+            vec!["Amount must be greater than zero"]
+        );
+
+        assert_eq!(report.ready_count(), 3);
+        assert_eq!(report.problem_count(), 3);
+        assert!(!report.is_ready());
+        assert_eq!(report.total(RoyaltyCurrency::Usd), 35_500_000);
+        assert_eq!(report.total(RoyaltyCurrency::Newm), 3_000_000);
+        assert_eq!(
+            report.summary(),
+            "6 rows: 3 ready, 3 with problems. Totals: 35.500000 USD, 3.000000 NEWM"
+        );
+    }
+
+    #[test]
+    fn test_each_song_looked_up_once() {
+        assert_eq!(
+            report().pending_lookups(),
+            vec![SONG, "IE-LOI-23-01693", "QZ1234567890"]
+        );
+    }
+
+    #[test]
+    fn test_record_lookup() {
+        let mut report = PreflightReport::check(vec![
+            row(SONG, "10", RoyaltyCurrency::Usd),
+            row("IE-LOI-23-01693", "25", RoyaltyCurrency::Usd),
+            row("ieloi2301693", "5", RoyaltyCurrency::Usd),
+        ]);
+        report.record_lookup(
+            &SONG.to_uppercase(),
+            &SongLookup::Found {
+                title: Some("Daisy".to_string()),
+            },
+        );
+        report.record_lookup(
+            "IE-LOI-23-01693",
+            &SongLookup::Missing("No song found for IE-LOI-23-01693".to_string()),
+        );
+
+        assert!(report.rows[0].found);
+        assert_eq!(report.rows[0].title.as_deref(), Some("Daisy"));
+        assert!(!report.rows[1].is_ready());
+        assert!(!report.rows[2].is_ready());
+        assert_eq!(report.total(RoyaltyCurrency::Usd), 10_000_000);
+        assert!(!report.is_ready());
+    }
+}
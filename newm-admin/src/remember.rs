@@ -0,0 +1,346 @@
+//! Remembered Sessions
+//!
+//! When "Remember me" is ticked at login, the refresh token is kept in the
+//! platform secret store (Keychain on macOS, Credential Manager on Windows,
+//! the Secret Service API on Linux), one entry per environment base URL.
+//! Only the name of the last remembered environment is written to the data
+//! directory, so the next launch knows which token to try.
+//!
+//! The server blacklists a refresh token once it has been used, so the
+//! session saves the replacement after every refresh.
+//!
+//! The marker also records whether the console was locked, so quitting while
+//! locked does not skip the lock screen on the next launch.
+
+#[cfg(test)]
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+
+use crate::environment::Environment;
+use crate::storage;
+
+/// Service name the tokens are filed under in the secret store
+const SERVICE: &str = "newm-admin";
+
+/// File in the data directory naming the last remembered environment
+const MARKER_FILE: &str = "remembered_session.json";
+
+/// Somewhere refresh tokens can be kept between launches
+pub trait TokenStore: Send + Sync {
+    fn load(&self, account: &str) -> Result<Option<String>, String>;
+    fn save(&self, account: &str, token: &str) -> Result<(), String>;
+    fn delete(&self, account: &str) -> Result<(), String>;
+}
+
+/// Platform secret store
+pub struct KeyringStore;
+
+impl KeyringStore {
+    fn entry(account: &str) -> Result<keyring::Entry, String> {
+        keyring::Entry::new(SERVICE, account).map_err(|e| e.to_string())
+    }
+}
+
+impl TokenStore for KeyringStore {
+    fn load(&self, account: &str) -> Result<Option<String>, String> {
+        match Self::entry(account)?.get_password() {
+            Ok(token) => Ok(Some(token)),
+            Err(keyring::Error::NoEntry) => Ok(None),
+            Err(e) => Err(e.to_string()),
+        }
+    }
+
+    fn save(&self, account: &str, token: &str) -> Result<(), String> {
+        Self::entry(account)?
+            .set_password(token)
+            .map_err(|e| e.to_string())
+    }
+
+    fn delete(&self, account: &str) -> Result<(), String> {
+        match Self::entry(account)?.delete_credential() {
+            Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+            Err(e) => Err(e.to_string()),
+        }
+    }
+}
+
+/// Plain JSON file standing in for the secret store in tests
+#[cfg(test)]
+pub struct FileStore {
+    path: PathBuf,
+}
+
+#[cfg(test)]
+impl FileStore {
+    pub fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    fn tokens(&self) -> Result<BTreeMap<String, String>, String> {
+        Ok(storage::read_json(&self.path)?.unwrap_or_default())
+    }
+}
+
+#[cfg(test)]
+impl TokenStore for FileStore {
+    fn load(&self, account: &str) -> Result<Option<String>, String> {
+        Ok(self.tokens()?.remove(account))
+    }
+
+    fn save(&self, account: &str, token: &str) -> Result<(), String> {
+        let mut tokens = self.tokens()?;
+        tokens.insert(account.to_string(), token.to_string());
+        storage::write_json(&self.path, &tokens)
+    }
+
+    fn delete(&self, account: &str) -> Result<(), String> {
+        let mut tokens = self.tokens()?;
+        if tokens.remove(account).is_some() {
+            storage::write_json(&self.path, &tokens)?;
+        }
+        Ok(())
+    }
+}
+
+/// Which environment the remembered token belongs to
+#[derive(Debug, Serialize, Deserialize)]
+struct Marker {
+    environment: String,
+    /// The console was locked when this was last written
+    #[serde(default)]
+    locked: bool,
+}
+
+/// A remembered session found at launch
+#[derive(Debug)]
+pub struct RestoredSession {
+    pub environment: Environment,
+    pub refresh_token: String,
+    /// The console was locked when the app last ran
+    pub locked: bool,
+}
+
+/// Remembered refresh tokens and the environment they were saved for
+#[derive(Clone)]
+pub struct RememberedSessions {
+    store: Arc<dyn TokenStore>,
+    marker: Option<PathBuf>,
+}
+
+impl Default for RememberedSessions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RememberedSessions {
+    /// Use the platform secret store
+    pub fn new() -> Self {
+        Self {
+            store: Arc::new(KeyringStore),
+            marker: storage::data_file(MARKER_FILE),
+        }
+    }
+
+    #[cfg(test)]
+    pub fn with_store(store: impl TokenStore + 'static, marker: PathBuf) -> Self {
+        Self {
+            store: Arc::new(store),
+            marker: Some(marker),
+        }
+    }
+
+    /// Keep an environment's refresh token for the next launch
+    pub fn save(&self, environment: &Environment, refresh_token: &str) -> Result<(), String> {
+        self.store.save(environment.base_url(), refresh_token)?;
+        let Some(marker) = &self.marker else {
+            return Ok(());
+        };
+        // Tokens are refreshed while locked too; that must not unlock
+        let locked = matches!(
+            storage::read_json::<Marker>(marker),
+            Ok(Some(m)) if m.locked && m.environment == environment.display_name()
+        );
+        storage::write_json(
+            marker,
+            &Marker {
+                environment: environment.display_name().to_string(),
+                locked,
+            },
+        )
+    }
+
+    /// Record whether the console is locked, if `environment` is the
+    /// remembered one
+    pub fn set_locked(&self, environment: &Environment, locked: bool) -> Result<(), String> {
+        let Some(marker) = &self.marker else {
+            return Ok(());
+        };
+        match storage::read_json::<Marker>(marker)? {
+            Some(m) if m.environment == environment.display_name() && m.locked != locked => {
+                storage::write_json(marker, &Marker { locked, ..m })
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// The refresh token kept for an environment, if any
+    pub fn load(&self, environment: &Environment) -> Result<Option<String>, String> {
+        self.store.load(environment.base_url())
+    }
+
+    /// Drop an environment's refresh token
+    pub fn forget(&self, environment: &Environment) -> Result<(), String> {
+        self.store.delete(environment.base_url())?;
+        let Some(marker) = &self.marker else {
+            return Ok(());
+        };
+        match storage::read_json::<Marker>(marker)? {
+            Some(m) if m.environment == environment.display_name() => std::fs::remove_file(marker)
+                .map_err(|e| format!("Failed to remove {}: {}", marker.display(), e)),
+            _ => Ok(()),
+        }
+    }
+
+    /// The last remembered environment (if it is still configured) and its token
+    pub fn restore(&self, environments: &[Environment]) -> Option<RestoredSession> {
+        let marker = match storage::read_json::<Marker>(self.marker.as_ref()?) {
+            Ok(marker) => marker?,
+            Err(e) => {
+                tracing::warn!("Ignoring remembered session: {}", e);
+                return None;
+            }
+        };
+        let environment = environments
+            .iter()
+            .find(|e| e.display_name() == marker.environment)?
+            .clone();
+
+        match self.store.load(environment.base_url()) {
+            Ok(token) => token.map(|refresh_token| RestoredSession {
+                environment,
+                refresh_token,
+                locked: marker.locked,
+            }),
+            Err(e) => {
+                tracing::warn!("Could not read remembered session: {}", e);
+                None
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{FileStore, RememberedSessions, TokenStore};
+    use crate::environment::Environment;
+
+    fn sessions(dir: &tempfile::TempDir) -> RememberedSessions {
+        RememberedSessions::with_store(
+            FileStore::new(dir.path().join("tokens.json")),
+            dir.path().join("marker.json"),
+        )
+    }
+
+    #[test]
+    fn test_nothing_remembered() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(sessions(&dir).restore(&[Environment::garage()]).is_none());
+    }
+
+    #[test]
+    fn test_save_then_restore() {
+        let dir = tempfile::tempdir().unwrap();
+        let remembered = sessions(&dir);
+        remembered.save(&Environment::garage(), "first").unwrap();
+        remembered.save(&Environment::studio(), "second").unwrap();
+
+        let environments = [Environment::garage(), Environment::studio()];
+        let restored = remembered.restore(&environments).unwrap();
+        assert_eq!(restored.environment, Environment::studio());
+        assert_eq!(restored.refresh_token, "second");
+        assert!(!restored.locked);
+        assert_eq!(
+            remembered.load(&Environment::garage()).unwrap().as_deref(),
+            Some("first")
+        );
+
+        // A fresh instance reads the same files
+        let restored = sessions(&dir).restore(&environments).unwrap();
+        assert_eq!(restored.environment, Environment::studio());
+        assert_eq!(restored.refresh_token, "second");
+    }
+
+    #[test]
+    fn test_saving_again_replaces_token() {
+        let dir = tempfile::tempdir().unwrap();
+        let remembered = sessions(&dir);
+        remembered.save(&Environment::garage(), "old").unwrap();
+        remembered.save(&Environment::garage(), "rotated").unwrap();
+
+        let restored = remembered.restore(&[Environment::garage()]).unwrap();
+        assert_eq!(restored.refresh_token, "rotated");
+    }
+
+    #[test]
+    fn test_locked_survives_refresh() {
+        let dir = tempfile::tempdir().unwrap();
+        let remembered = sessions(&dir);
+        let environments = [Environment::garage()];
+        remembered.save(&Environment::garage(), "old").unwrap();
+        remembered.set_locked(&Environment::garage(), true).unwrap();
+        remembered.save(&Environment::garage(), "rotated").unwrap();
+
+        let restored = sessions(&dir).restore(&environments).unwrap();
+        assert!(restored.locked);
+        assert_eq!(restored.refresh_token, "rotated");
+
+        // Another environment's lock state is not ours to change
+        remembered
+            .set_locked(&Environment::studio(), false)
+            .unwrap();
+        assert!(remembered.restore(&environments).unwrap().locked);
+
+        remembered
+            .set_locked(&Environment::garage(), false)
+            .unwrap();
+        assert!(!remembered.restore(&environments).unwrap().locked);
+    }
+
+    #[test]
+    fn test_forget() {
+        let dir = tempfile::tempdir().unwrap();
+        let remembered = sessions(&dir);
+        remembered.save(&Environment::garage(), "token").unwrap();
+        remembered.forget(&Environment::garage()).unwrap();
+
+        assert!(remembered.restore(&[Environment::garage()]).is_none());
+        let store = FileStore::new(dir.path().join("tokens.json"));
+        assert_eq!(store.load(Environment::garage().base_url()).unwrap(), None);
+
+        // Forgetting twice is fine
+        remembered.forget(&Environment::garage()).unwrap();
+    }
+
+    #[test]
+    fn test_forgetting_other_environment_keeps_marker() {
+        let dir = tempfile::tempdir().unwrap();
+        let remembered = sessions(&dir);
+        remembered.save(&Environment::garage(), "token").unwrap();
+        remembered.forget(&Environment::studio()).unwrap();
+
+        assert!(remembered.restore(&[Environment::garage()]).is_some());
+    }
+
+    #[test]
+    fn test_unconfigured_environment_is_not_restored() {
+        let dir = tempfile::tempdir().unwrap();
+        let remembered = sessions(&dir);
+        remembered.save(&Environment::studio(), "token").unwrap();
+
+        assert!(remembered.restore(&[Environment::garage()]).is_none());
+    }
+}
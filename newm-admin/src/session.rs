@@ -4,23 +4,47 @@
 //! This module provides a reusable authentication layer for all admin API calls.
 
 use async_compat::Compat;
+use futures::FutureExt;
+use futures::future::{BoxFuture, Shared};
 use gpui::*;
 use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
-use crate::auth::{Environment, LoginResponse};
-use crate::http_client;
+use crate::auth::{AuthClient, AuthError, LoginResponse};
+use crate::environment::Environment;
 use crate::jwt;
+use crate::remember::RememberedSessions;
 
 /// Session state containing authentication tokens
 #[derive(Clone)]
 pub struct Session {
     inner: Arc<Mutex<SessionInner>>,
     environment: Environment,
+    /// Set when the refresh token should survive a restart
+    remembered: Option<RememberedSessions>,
 }
 
 struct SessionInner {
     access_token: String,
     refresh_token: String,
+    /// When the access token was issued to us (seconds since epoch)
+    received_at: i64,
+    /// Refresh in flight, awaited by every caller that needs a new token
+    refreshing: Option<SharedRefresh>,
+}
+
+type SharedRefresh = Shared<BoxFuture<'static, Result<String, SessionError>>>;
+
+/// Proactive refreshes happen once this share of the access token lifetime has passed
+const REFRESH_AT_LIFETIME_PERCENT: i64 = 75;
+
+fn now_secs() -> i64 {
+    chrono::Utc::now().timestamp()
+}
+
+/// When to refresh a token received at `received_at` that expires at `expires_at`
+fn refresh_due_at(received_at: i64, expires_at: i64) -> i64 {
+    received_at + (expires_at - received_at).max(0) * REFRESH_AT_LIFETIME_PERCENT / 100
 }
 
 /// Error returned when session operations fail
@@ -50,87 +74,499 @@ impl Session {
             inner: Arc::new(Mutex::new(SessionInner {
                 access_token: login_response.access_token,
                 refresh_token: login_response.refresh_token,
+                received_at: now_secs(),
+                refreshing: None,
             })),
             environment,
+            remembered: None,
         }
     }
 
+    /// Keep the refresh token in the secret store, updating it on every refresh
+    pub fn with_remembered(mut self, remembered: RememberedSessions) -> Self {
+        self.remembered = Some(remembered);
+        self
+    }
+
     /// Get the environment for this session
-    pub fn environment(&self) -> Environment {
-        self.environment
+    pub fn environment(&self) -> &Environment {
+        &self.environment
     }
 
     /// Get a valid access token, refreshing if necessary.
     ///
     /// This method checks if the current token expires within 60 seconds
-    /// and automatically refreshes it if needed.
+    /// and automatically refreshes it if needed. Concurrent callers share a
+    /// single refresh: the server blacklists a refresh token once used, so a
+    /// second request with the same token would end the session.
     ///
     /// Returns `SessionError::Expired` if the token cannot be refreshed.
     pub async fn get_valid_token(&self) -> Result<String, SessionError> {
         const REFRESH_BUFFER_SECS: i64 = 60;
 
-        let (access_token, refresh_token) = {
-            let inner = self.inner.lock().unwrap();
-            (inner.access_token.clone(), inner.refresh_token.clone())
+        let refresh = {
+            let mut inner = self.inner.lock().unwrap();
+
+            // Check if token expires soon
+            if !jwt::expires_soon(&inner.access_token, REFRESH_BUFFER_SECS) {
+                return Ok(inner.access_token.clone());
+            }
+
+            tracing::info!("Access token expires soon, refreshing...");
+            self.shared_refresh(&mut inner)
         };
 
-        // Check if token expires soon
-        if !jwt::expires_soon(&access_token, REFRESH_BUFFER_SECS) {
-            return Ok(access_token);
+        refresh.await
+    }
+
+    /// Refresh now, whether or not the access token is about to expire.
+    ///
+    /// Joins a refresh that is already in flight rather than starting another.
+    pub async fn refresh_now(&self) -> Result<String, SessionError> {
+        let refresh = {
+            let mut inner = self.inner.lock().unwrap();
+            self.shared_refresh(&mut inner)
+        };
+        refresh.await
+    }
+
+    /// When the access token should be proactively refreshed (seconds since epoch)
+    pub fn next_refresh_at(&self) -> Option<i64> {
+        let inner = self.inner.lock().unwrap();
+        let expires_at = jwt::expires_at(&inner.access_token)?;
+        Some(refresh_due_at(inner.received_at, expires_at))
+    }
+
+    /// Seconds until the refresh token expires and the admin must log in again
+    pub fn refresh_expires_in(&self) -> Option<i64> {
+        jwt::expires_in_secs(&self.inner.lock().unwrap().refresh_token)
+    }
+
+    /// The refresh in flight, or a newly started one
+    fn shared_refresh(&self, inner: &mut SessionInner) -> SharedRefresh {
+        if let Some(refresh) = &inner.refreshing {
+            return refresh.clone();
         }
+        let refresh = self.refresh(inner.refresh_token.clone()).boxed().shared();
+        inner.refreshing = Some(refresh.clone());
+        refresh
+    }
 
-        tracing::info!("Access token expires soon, refreshing...");
+    /// Exchange the refresh token for new tokens and store them
+    fn refresh(
+        &self,
+        refresh_token: String,
+    ) -> impl Future<Output = Result<String, SessionError>> + Send + 'static {
+        let inner = self.inner.clone();
+        let environment = self.environment.clone();
+        let remembered = self.remembered.clone();
 
-        // Need to refresh - use the refresh token
-        let client = http_client::new_client();
-        let url = self.environment.refresh_url();
+        async move {
+            let result = Self::request_refresh(&environment, &refresh_token).await;
 
-        let response = Compat::new(async {
-            client
-                .get(&url)
-                .header("Authorization", format!("Bearer {}", refresh_token))
-                .send()
-                .await
-        })
-        .await
-        .map_err(|e| SessionError::Network(e.to_string()))?;
+            if let (Ok(login_response), Some(remembered)) = (&result, &remembered) {
+                // The old refresh token is blacklisted now, so keep the new one
+                if let Err(e) = remembered.save(&environment, &login_response.refresh_token) {
+                    tracing::warn!("Failed to update remembered session: {}", e);
+                }
+            }
 
-        if response.status().is_success() {
-            let login_response: LoginResponse = Compat::new(async { response.json().await })
-                .await
-                .map_err(|e| SessionError::Network(e.to_string()))?;
+            let mut inner = inner.lock().unwrap();
+            inner.refreshing = None;
+            let login_response = result?;
 
             // Update stored tokens
-            {
-                let mut inner = self.inner.lock().unwrap();
-                inner.access_token = login_response.access_token.clone();
-                inner.refresh_token = login_response.refresh_token;
-            }
+            inner.access_token = login_response.access_token.clone();
+            inner.refresh_token = login_response.refresh_token;
+            inner.received_at = now_secs();
 
             tracing::info!("Token refresh successful");
             Ok(login_response.access_token)
-        } else {
-            let status = response.status();
-            let error_text = Compat::new(async { response.text().await })
-                .await
-                .unwrap_or_else(|_| "Unknown error".to_string());
-
-            tracing::warn!("Token refresh failed: {} - {}", status, error_text);
-            Err(SessionError::Expired(format!(
-                "Refresh failed: {}",
-                error_text
-            )))
         }
     }
 
+    /// Call the refresh endpoint.
+    ///
+    /// Goes through [`AuthClient::refresh`], so rotated tokens get the same
+    /// admin and signature checks as a login.
+    async fn request_refresh(
+        environment: &Environment,
+        refresh_token: &str,
+    ) -> Result<LoginResponse, SessionError> {
+        let client = AuthClient::new();
+        Compat::new(client.refresh(refresh_token, environment))
+            .await
+            .map_err(|e| match e {
+                AuthError::Network(msg) => SessionError::Network(msg),
+                e => SessionError::Expired(format!("Refresh failed: {}", e)),
+            })
+    }
+
     /// Get current access token without refresh check (for internal use)
     #[allow(dead_code)]
     pub fn current_token(&self) -> String {
         self.inner.lock().unwrap().access_token.clone()
     }
+
+    /// User ID (JWT subject) the session belongs to
+    pub fn user_id(&self) -> Option<String> {
+        jwt::parse_claims(&self.inner.lock().unwrap().access_token)
+            .ok()?
+            .sub
+    }
+
+    /// Adopt tokens from a fresh login of the same user
+    pub fn replace_tokens(&self, login_response: LoginResponse) {
+        if let Some(remembered) = &self.remembered
+            && let Err(e) = remembered.save(&self.environment, &login_response.refresh_token)
+        {
+            tracing::warn!("Failed to update remembered session: {}", e);
+        }
+
+        let mut inner = self.inner.lock().unwrap();
+        inner.access_token = login_response.access_token;
+        inner.refresh_token = login_response.refresh_token;
+        inner.received_at = now_secs();
+    }
+
+    /// Invalidate the session's tokens on the server
+    pub async fn logout(&self) -> Result<(), AuthError> {
+        let (access_token, refresh_token) = {
+            let inner = self.inner.lock().unwrap();
+            (inner.access_token.clone(), inner.refresh_token.clone())
+        };
+        let client = AuthClient::new();
+        Compat::new(client.logout(&access_token, &refresh_token, &self.environment)).await
+    }
 }
 
 /// Event emitted when the session expires and user must re-login
 pub struct SessionExpiredEvent {
     pub message: String,
 }
+
+/// Event emitted once the refresh token comes within the warning window
+pub struct SessionExpiringEvent {
+    pub expires_in_secs: i64,
+}
+
+/// How often the watcher checks the session
+const WATCH_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Warn once the refresh token has less than this left
+const EXPIRY_WARNING_SECS: i64 = 15 * 60;
+
+/// Keeps a session alive in the background.
+///
+/// Refreshes the access token once most of its lifetime has passed, so an
+/// idle dashboard never wakes up to an expired refresh token, and reports
+/// when the refresh token itself is about to run out.
+pub struct SessionWatcher {
+    session: Session,
+    /// Seconds left on the refresh token, once inside the warning window
+    expires_in_secs: Option<i64>,
+    is_refreshing: bool,
+    _task: Task<()>,
+}
+
+impl SessionWatcher {
+    pub fn new(session: Session, cx: &mut Context<Self>) -> Self {
+        let task = cx.spawn(async move |this, cx| {
+            loop {
+                cx.background_executor().timer(WATCH_INTERVAL).await;
+
+                let Ok(session) = this.read_with(cx, |watcher, _| watcher.session.clone()) else {
+                    break;
+                };
+                if session
+                    .next_refresh_at()
+                    .is_some_and(|refresh_at| now_secs() >= refresh_at)
+                {
+                    tracing::info!("Refreshing access token in the background");
+                    let result = session.refresh_now().await;
+                    this.update(cx, |watcher, cx| watcher.refreshed(result, cx))
+                        .ok();
+                }
+
+                this.update(cx, |watcher, cx| watcher.check_expiry(cx)).ok();
+            }
+        });
+
+        let mut watcher = Self {
+            session,
+            expires_in_secs: None,
+            is_refreshing: false,
+            _task: task,
+        };
+        watcher.check_expiry(cx);
+        watcher
+    }
+
+    /// Seconds left before the admin has to log in again, if that is soon
+    pub fn expires_in_secs(&self) -> Option<i64> {
+        self.expires_in_secs
+    }
+
+    pub fn is_refreshing(&self) -> bool {
+        self.is_refreshing
+    }
+
+    /// Refresh right away, renewing the refresh token too
+    pub fn stay_signed_in(&mut self, cx: &mut Context<Self>) {
+        if self.is_refreshing {
+            return;
+        }
+        self.is_refreshing = true;
+        cx.notify();
+
+        let session = self.session.clone();
+        cx.spawn(async move |this, cx| {
+            let result = session.refresh_now().await;
+            this.update(cx, |watcher, cx| {
+                watcher.refreshed(result, cx);
+                watcher.check_expiry(cx);
+            })
+            .ok();
+        })
+        .detach();
+    }
+
+    fn refreshed(&mut self, result: Result<String, SessionError>, cx: &mut Context<Self>) {
+        self.is_refreshing = false;
+        match result {
+            Ok(_) => {}
+            Err(SessionError::Expired(message)) => cx.emit(SessionExpiredEvent { message }),
+            // Try again on the next tick
+            Err(e) => tracing::warn!("Background token refresh failed: {}", e),
+        }
+        cx.notify();
+    }
+
+    fn check_expiry(&mut self, cx: &mut Context<Self>) {
+        let expires_in = self
+            .session
+            .refresh_expires_in()
+            .filter(|secs| *secs <= EXPIRY_WARNING_SECS);
+        if let Some(expires_in_secs) = expires_in
+            && self.expires_in_secs.is_none()
+        {
+            cx.emit(SessionExpiringEvent { expires_in_secs });
+        }
+        self.expires_in_secs = expires_in;
+        cx.notify();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Session, SessionError, now_secs, refresh_due_at};
+    use crate::auth::LoginResponse;
+    use crate::cardano::Network;
+    use crate::environment::{Environment, EnvironmentEntry};
+    use crate::jwt::TokenVerificationEntry;
+    use base64::Engine;
+    use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    /// Unsigned admin token expiring `secs` from now
+    fn token(secs: i64) -> String {
+        let exp = chrono::Utc::now().timestamp() + secs;
+        format!(
+            "{}.{}.signature",
+            URL_SAFE_NO_PAD.encode(r#"{"alg":"HS256","typ":"JWT"}"#),
+            URL_SAFE_NO_PAD.encode(format!(r#"{{"admin":true,"exp":{}}}"#, exp))
+        )
+    }
+
+    /// Local server answering every request with `status` and `body`,
+    /// slowly enough for concurrent callers to overlap
+    fn mock_server(status: &'static str, body: String) -> (Environment, Arc<AtomicUsize>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let refresh_calls = Arc::new(AtomicUsize::new(0));
+
+        let calls = refresh_calls.clone();
+        std::thread::spawn(move || {
+            for mut stream in listener.incoming().flatten() {
+                let mut buf = [0u8; 4096];
+                let n = stream.read(&mut buf).unwrap_or(0);
+                if String::from_utf8_lossy(&buf[..n]).starts_with("GET /v1/auth/refresh ") {
+                    calls.fetch_add(1, Ordering::SeqCst);
+                }
+                std::thread::sleep(Duration::from_millis(200));
+                let _ = write!(
+                    stream,
+                    "HTTP/1.1 {}\r\nContent-Type: application/json\r\n\
+                     Content-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    status,
+                    body.len(),
+                    body
+                );
+            }
+        });
+
+        let environment = Environment::from_entry(EnvironmentEntry {
+            name: "Mock".to_string(),
+            base_url: format!("http://127.0.0.1:{}", port),
+            network: Network::Testnet,
+            color: None,
+            oauth: Default::default(),
+            token_verification: None,
+        })
+        .unwrap();
+        (environment, refresh_calls)
+    }
+
+    /// Unsigned admin access token from `issuer`, valid for an hour
+    fn issued_token(issuer: &str) -> String {
+        let exp = chrono::Utc::now().timestamp() + 3600;
+        format!(
+            "{}.{}.signature",
+            URL_SAFE_NO_PAD.encode(r#"{"alg":"HS256","typ":"JWT"}"#),
+            URL_SAFE_NO_PAD.encode(format!(
+                r#"{{"admin":true,"type":"Access","iss":"{}","exp":{}}}"#,
+                issuer, exp
+            ))
+        )
+    }
+
+    /// `environment` with tokens checked against the `https://newm.io` issuer
+    fn verifying(environment: &Environment) -> Environment {
+        Environment::from_entry(EnvironmentEntry {
+            name: "Mock".to_string(),
+            base_url: environment.base_url().to_string(),
+            network: Network::Testnet,
+            color: None,
+            oauth: Default::default(),
+            token_verification: Some(TokenVerificationEntry {
+                issuer: Some("https://newm.io".to_string()),
+                ..TokenVerificationEntry::default()
+            }),
+        })
+        .unwrap()
+    }
+
+    fn expiring_session(environment: Environment) -> Session {
+        Session::new(
+            LoginResponse {
+                access_token: token(10),
+                refresh_token: "refresh-1".to_string(),
+            },
+            environment,
+        )
+    }
+
+    #[test]
+    fn test_refresh_due_at() {
+        assert_eq!(refresh_due_at(1_000, 1_000 + 3600), 1_000 + 2700);
+        // Already expired when received: refresh straight away
+        assert_eq!(refresh_due_at(1_000, 900), 1_000);
+    }
+
+    #[test]
+    fn test_session_schedule() {
+        let session = Session::new(
+            LoginResponse {
+                access_token: token(3600),
+                refresh_token: token(600),
+            },
+            Environment::garage(),
+        );
+
+        let refresh_at = session.next_refresh_at().unwrap() - now_secs();
+        assert!((2695..=2700).contains(&refresh_at), "{}", refresh_at);
+        let expires_in = session.refresh_expires_in().unwrap();
+        assert!((595..=600).contains(&expires_in), "{}", expires_in);
+    }
+
+    #[test]
+    fn test_concurrent_callers_share_one_refresh() {
+        let fresh = token(3600);
+        let (environment, refresh_calls) = mock_server(
+            "200 OK",
+            format!(
+                r#"{{"accessToken":"{}","refreshToken":"refresh-2"}}"#,
+                fresh
+            ),
+        );
+        let session = expiring_session(environment);
+
+        let results = futures::executor::block_on(futures::future::join_all(
+            (0..5).map(|_| session.get_valid_token()),
+        ));
+
+        for result in results {
+            assert_eq!(result.unwrap(), fresh);
+        }
+        assert_eq!(refresh_calls.load(Ordering::SeqCst), 1);
+        assert_eq!(session.inner.lock().unwrap().refresh_token, "refresh-2");
+
+        // The new token is still valid, so no further refresh
+        let token = futures::executor::block_on(session.get_valid_token()).unwrap();
+        assert_eq!(token, fresh);
+        assert_eq!(refresh_calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_failed_refresh_is_shared_and_not_cached() {
+        let (environment, refresh_calls) =
+            mock_server("401 Unauthorized", "token blacklisted".to_string());
+        let session = expiring_session(environment);
+
+        let results = futures::executor::block_on(futures::future::join_all(
+            (0..3).map(|_| session.get_valid_token()),
+        ));
+
+        for result in results {
+            assert!(matches!(result, Err(SessionError::Expired(_))));
+        }
+        assert_eq!(refresh_calls.load(Ordering::SeqCst), 1);
+
+        // A later call tries again instead of reusing the failed result
+        let result = futures::executor::block_on(session.get_valid_token());
+        assert!(result.is_err());
+        assert_eq!(refresh_calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn test_refresh_verifies_new_token() {
+        let refreshed = |token: &str| {
+            format!(
+                r#"{{"accessToken":"{}","refreshToken":"refresh-2"}}"#,
+                token
+            )
+        };
+
+        let (environment, _) = mock_server("200 OK", refreshed(&issued_token("https://evil.io")));
+        let session = expiring_session(verifying(&environment));
+        let result = futures::executor::block_on(session.get_valid_token());
+        assert!(matches!(result, Err(SessionError::Expired(_))));
+        assert_eq!(session.inner.lock().unwrap().refresh_token, "refresh-1");
+
+        let fresh = issued_token("https://newm.io");
+        let (environment, _) = mock_server("200 OK", refreshed(&fresh));
+        let session = expiring_session(verifying(&environment));
+        let token = futures::executor::block_on(session.get_valid_token()).unwrap();
+        assert_eq!(token, fresh);
+    }
+
+    #[test]
+    fn test_logout() {
+        let (environment, _) = mock_server("204 No Content", String::new());
+        let result = futures::executor::block_on(expiring_session(environment).logout());
+        assert!(result.is_ok());
+
+        // Older servers without the endpoint: nothing to invalidate
+        let (environment, _) = mock_server("404 Not Found", String::new());
+        let result = futures::executor::block_on(expiring_session(environment).logout());
+        assert!(result.is_ok());
+
+        let (environment, _) = mock_server("500 Internal Server Error", "boom".to_string());
+        let result = futures::executor::block_on(expiring_session(environment).logout());
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,465 @@
+//! Songs API client for NEWM Admin
+//!
+//! Handles song lookups and admin song operations (minting refunds and
+//! minting status reprocessing) with automatic session management.
+
+use reqwest::Client;
+use serde::Deserialize;
+
+use crate::api::{ApiClient, ApiError};
+use crate::http_client;
+use crate::session::Session;
+
+/// Song record from the API (only the fields the admin tool needs)
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Song {
+    pub id: Option<String>,
+    pub owner_id: Option<String>,
+    pub title: Option<String>,
+    pub isrc: Option<String>,
+    pub minting_status: Option<String>,
+    pub minting_tx_id: Option<String>,
+    pub mint_payment_type: Option<String>,
+    pub mint_cost: Option<i64>,
+    pub error_message: Option<String>,
+}
+
+/// Song minting status, mirroring the server's `MintingStatus` enum.
+///
+/// Variants are listed in the server's ordinal order, which is stable because new
+/// statuses are only ever appended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MintingStatus {
+    Undistributed,
+    StreamTokenAgreementApproved,
+    MintingPaymentRequested,
+    MintingPaymentSubmitted,
+    MintingPaymentReceived,
+    AwaitingAudioEncoding,
+    AwaitingCollaboratorApproval,
+    ReadyToDistribute,
+    SubmittedForDistribution,
+    Distributed,
+    Declined,
+    Pending,
+    Minted,
+    MintingPaymentTimeout,
+    MintingPaymentException,
+    DistributionException,
+    SubmittedForDistributionException,
+    ArweaveUploadException,
+    MintingException,
+    ReleaseCheckException,
+    Released,
+    UpdateTokenMetadataRequested,
+}
+
+impl MintingStatus {
+    /// All statuses in server ordinal order
+    pub const ALL: [MintingStatus; 22] = [
+        MintingStatus::Undistributed,
+        MintingStatus::StreamTokenAgreementApproved,
+        MintingStatus::MintingPaymentRequested,
+        MintingStatus::MintingPaymentSubmitted,
+        MintingStatus::MintingPaymentReceived,
+        MintingStatus::AwaitingAudioEncoding,
+        MintingStatus::AwaitingCollaboratorApproval,
+        MintingStatus::ReadyToDistribute,
+        MintingStatus::SubmittedForDistribution,
+        MintingStatus::Distributed,
+        MintingStatus::Declined,
+        MintingStatus::Pending,
+        MintingStatus::Minted,
+        MintingStatus::MintingPaymentTimeout,
+        MintingStatus::MintingPaymentException,
+        MintingStatus::DistributionException,
+        MintingStatus::SubmittedForDistributionException,
+        MintingStatus::ArweaveUploadException,
+        MintingStatus::MintingException,
+        MintingStatus::ReleaseCheckException,
+        MintingStatus::Released,
+        MintingStatus::UpdateTokenMetadataRequested,
+    ];
+
+    /// Name used by the server in paths and JSON
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MintingStatus::Undistributed => "Undistributed",
+            MintingStatus::StreamTokenAgreementApproved => "StreamTokenAgreementApproved",
+            MintingStatus::MintingPaymentRequested => "MintingPaymentRequested",
+            MintingStatus::MintingPaymentSubmitted => "MintingPaymentSubmitted",
+            MintingStatus::MintingPaymentReceived => "MintingPaymentReceived",
+            MintingStatus::AwaitingAudioEncoding => "AwaitingAudioEncoding",
+            MintingStatus::AwaitingCollaboratorApproval => "AwaitingCollaboratorApproval",
+            MintingStatus::ReadyToDistribute => "ReadyToDistribute",
+            MintingStatus::SubmittedForDistribution => "SubmittedForDistribution",
+            MintingStatus::Distributed => "Distributed",
+            MintingStatus::Declined => "Declined",
+            MintingStatus::Pending => "Pending",
+            MintingStatus::Minted => "Minted",
+            MintingStatus::MintingPaymentTimeout => "MintingPaymentTimeout",
+            MintingStatus::MintingPaymentException => "MintingPaymentException",
+            MintingStatus::DistributionException => "DistributionException",
+            MintingStatus::SubmittedForDistributionException => "SubmittedForDistributionException",
+            MintingStatus::ArweaveUploadException => "ArweaveUploadException",
+            MintingStatus::MintingException => "MintingException",
+            MintingStatus::ReleaseCheckException => "ReleaseCheckException",
+            MintingStatus::Released => "Released",
+            MintingStatus::UpdateTokenMetadataRequested => "UpdateTokenMetadataRequested",
+        }
+    }
+
+    /// Whether this is one of the server's error statuses
+    pub fn is_error(&self) -> bool {
+        matches!(
+            self,
+            MintingStatus::MintingPaymentTimeout
+                | MintingStatus::MintingPaymentException
+                | MintingStatus::DistributionException
+                | MintingStatus::SubmittedForDistributionException
+                | MintingStatus::ArweaveUploadException
+                | MintingStatus::MintingException
+                | MintingStatus::ReleaseCheckException
+        )
+    }
+}
+
+impl std::fmt::Display for MintingStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Public profile of a song owner
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SongOwner {
+    pub id: Option<String>,
+    pub first_name: Option<String>,
+    pub last_name: Option<String>,
+    pub nickname: Option<String>,
+    pub email: Option<String>,
+}
+
+impl SongOwner {
+    /// Best available display name (nickname, then full name, then user ID)
+    pub fn display_name(&self) -> String {
+        if let Some(nickname) = self.nickname.as_deref().filter(|n| !n.is_empty()) {
+            return nickname.to_string();
+        }
+        let full_name = [self.first_name.as_deref(), self.last_name.as_deref()]
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>()
+            .join(" ");
+        if !full_name.is_empty() {
+            return full_name;
+        }
+        self.id.clone().unwrap_or_default()
+    }
+}
+
+/// Response from the minting refund endpoint
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RefundPaymentResponse {
+    pub transaction_id: String,
+    pub message: String,
+}
+
+/// Error from songs API operations
+#[derive(Debug)]
+pub enum SongsError {
+    /// Session expired, user must re-login
+    SessionExpired(String),
+    /// Song could not be found for the given identifier
+    NotFound(String),
+    /// API returned an error
+    Api { status: u16, message: String },
+    /// Network or other error
+    Network(String),
+    /// A write was sent but its outcome is unknown
+    Ambiguous(String),
+    /// Request was rejected before being sent
+    Invalid(String),
+}
+
+impl std::fmt::Display for SongsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SongsError::SessionExpired(msg) => write!(f, "Session expired: {}", msg),
+            SongsError::NotFound(msg) => write!(f, "Not found: {}", msg),
+            SongsError::Api { status, message } => {
+                write!(f, "API error {}: {}", status, message)
+            }
+            SongsError::Network(msg) => write!(f, "Network error: {}", msg),
+            SongsError::Ambiguous(msg) => {
+                write!(f, "Outcome unknown, check before retrying: {}", msg)
+            }
+            SongsError::Invalid(msg) => write!(f, "Invalid request: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for SongsError {}
+
+impl From<ApiError> for SongsError {
+    fn from(err: ApiError) -> Self {
+        match err {
+            ApiError::SessionExpired(msg) => SongsError::SessionExpired(msg),
+            ApiError::Api { status, message } => SongsError::Api { status, message },
+            ApiError::Network(msg) => SongsError::Network(msg),
+            ApiError::Ambiguous(msg) => SongsError::Ambiguous(msg),
+        }
+    }
+}
+
+/// Client for songs API operations
+#[derive(Clone)]
+pub struct SongsClient {
+    client: Client,
+}
+
+impl Default for SongsClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SongsClient {
+    /// Create a new songs client
+    pub fn new() -> Self {
+        Self {
+            client: http_client::shared(),
+        }
+    }
+
+    fn api(&self, session: &Session) -> ApiClient {
+        ApiClient::new(self.client.clone(), session)
+    }
+
+    /// Resolve a song UUID or ISRC to a song UUID.
+    ///
+    /// ISRCs are looked up through the admin song-by-ISRC endpoint.
+    pub async fn resolve_song_id(
+        &self,
+        session: &Session,
+        song_id_or_isrc: &str,
+    ) -> Result<String, SongsError> {
+        let identifier = song_id_or_isrc.trim();
+        if is_uuid(identifier) {
+            return Ok(identifier.to_lowercase());
+        }
+        if !is_isrc(identifier) {
+            return Err(SongsError::Invalid(format!(
+                "'{}' is not a valid song UUID or ISRC",
+                identifier
+            )));
+        }
+
+        let song = self.get_song_by_isrc(session, identifier).await?;
+        song.id
+            .ok_or_else(|| SongsError::NotFound(format!("No song found with ISRC {}", identifier)))
+    }
+
+    /// Get a song by its ISRC (admin only)
+    pub async fn get_song_by_isrc(
+        &self,
+        session: &Session,
+        isrc: &str,
+    ) -> Result<Song, SongsError> {
+        self.get_json(session, &format!("/v1/songs/isrc/{}", isrc), "song")
+            .await
+    }
+
+    /// Get a song by its UUID
+    pub async fn get_song(&self, session: &Session, song_id: &str) -> Result<Song, SongsError> {
+        self.get_json(session, &format!("/v1/songs/{}", song_id), "song")
+            .await
+    }
+
+    /// Get the public profile of a song owner
+    pub async fn get_owner(
+        &self,
+        session: &Session,
+        owner_id: &str,
+    ) -> Result<SongOwner, SongsError> {
+        self.get_json(session, &format!("/v1/users/{}", owner_id), "user")
+            .await
+    }
+
+    /// Refund the minting payment for a song back to its owner's wallet.
+    ///
+    /// # Arguments
+    /// * `session` - The authenticated session (will auto-refresh token if needed)
+    /// * `song_id` - UUID of the song
+    ///
+    /// # Returns
+    /// * `Ok(RefundPaymentResponse)` with the submitted refund transaction
+    /// * `Err(SongsError::SessionExpired)` if token refresh fails
+    /// * `Err(SongsError::Api)` for API errors (e.g. PayPal payments cannot be refunded)
+    pub async fn refund(
+        &self,
+        session: &Session,
+        song_id: &str,
+    ) -> Result<RefundPaymentResponse, SongsError> {
+        tracing::info!("Requesting minting refund for song {}", song_id);
+
+        let refund: RefundPaymentResponse = self
+            .api(session)
+            .post_empty(&format!("/v1/songs/{}/refund", song_id))
+            .await?;
+
+        tracing::info!(
+            "Refund submitted for song {}: {}",
+            song_id,
+            refund.transaction_id
+        );
+        Ok(refund)
+    }
+
+    /// Move a song back to the given minting status so the server reprocesses it.
+    ///
+    /// # Arguments
+    /// * `session` - The authenticated session (will auto-refresh token if needed)
+    /// * `song_id` - UUID of the song
+    /// * `minting_status` - Status to restart processing from
+    ///
+    /// # Returns
+    /// * `Ok(())` once the server has accepted the request
+    /// * `Err(SongsError::SessionExpired)` if token refresh fails
+    /// * `Err(SongsError::Api)` for API errors
+    pub async fn reprocess(
+        &self,
+        session: &Session,
+        song_id: &str,
+        minting_status: MintingStatus,
+    ) -> Result<(), SongsError> {
+        tracing::info!("Reprocessing song {} from {}", song_id, minting_status);
+
+        self.api(session)
+            .post_empty::<()>(&format!(
+                "/v1/songs/{}/reprocess/{}",
+                song_id,
+                minting_status.as_str()
+            ))
+            .await
+            .map_err(|e| match e {
+                ApiError::Api { status: 404, .. } => {
+                    SongsError::NotFound(format!("No song found with ID {}", song_id))
+                }
+                e => e.into(),
+            })?;
+
+        tracing::info!("Reprocess accepted for song {}", song_id);
+        Ok(())
+    }
+
+    /// Perform an authenticated GET and deserialize the JSON body
+    async fn get_json<T: serde::de::DeserializeOwned>(
+        &self,
+        session: &Session,
+        path: &str,
+        what: &str,
+    ) -> Result<T, SongsError> {
+        tracing::info!("Fetching {} from {}", what, path);
+
+        self.api(session).get(path).await.map_err(|e| match e {
+            ApiError::Api { status: 404, .. } => {
+                SongsError::NotFound(format!("No {} found at {}", what, path))
+            }
+            e => e.into(),
+        })
+    }
+}
+
+/// Check whether a string is a hyphenated UUID (8-4-4-4-12 hex digits)
+pub fn is_uuid(value: &str) -> bool {
+    let groups: Vec<&str> = value.split('-').collect();
+    groups.len() == 5
+        && groups
+            .iter()
+            .zip([8, 4, 4, 4, 12])
+            .all(|(group, len)| group.len() == len && group.chars().all(|c| c.is_ascii_hexdigit()))
+}
+
+/// Check whether a string matches the server's ISRC format
+/// (`CC-XXX-YY-NNNNN`, hyphens optional, case-insensitive)
+pub fn is_isrc(value: &str) -> bool {
+    let compact: Vec<char> = value.chars().filter(|c| *c != '-').collect();
+    if compact.len() != 12 || value.len() - compact.len() > 3 {
+        return false;
+    }
+    // Hyphens are only allowed at the group boundaries
+    let hyphens_ok = value
+        .char_indices()
+        .filter(|(_, c)| *c == '-')
+        .all(|(i, _)| {
+            let preceding = value[..i].chars().filter(|c| *c != '-').count();
+            matches!(preceding, 2 | 5 | 7) && !value[..i].ends_with('-')
+        });
+
+    hyphens_ok
+        && compact[..2].iter().all(|c| c.is_ascii_alphabetic())
+        && compact[2..5]
+            .iter()
+            .all(|c| c.is_ascii_alphanumeric() || *c == '_')
+        && compact[5..].iter().all(|c| c.is_ascii_digit())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_uuid() {
+        assert!(is_uuid("550e8400-e29b-41d4-a716-446655440000"));
+        assert!(is_uuid("550E8400-E29B-41D4-A716-446655440000"));
+        assert!(!is_uuid("550e8400e29b41d4a716446655440000"));
+        assert!(!is_uuid("550e8400-e29b-41d4-a716-44665544000g"));
+        assert!(!is_uuid("USRC12345678"));
+    }
+
+    #[test]
+    fn test_is_isrc() {
+        assert!(is_isrc("USRC17607839"));
+        assert!(is_isrc("US-RC1-76-07839"));
+        assert!(is_isrc("usrc17607839"));
+        assert!(!is_isrc("USRC1760783"));
+        assert!(!is_isrc("12RC17607839"));
+        assert!(!is_isrc("USRC1760783X"));
+        assert!(!is_isrc("U-SRC17607839"));
+        assert!(!is_isrc("550e8400-e29b-41d4-a716-446655440000"));
+    }
+
+    #[test]
+    fn test_minting_status_matches_server_ordinals() {
+        assert_eq!(MintingStatus::ALL[0], MintingStatus::Undistributed);
+        assert_eq!(
+            MintingStatus::ALL[17],
+            MintingStatus::ArweaveUploadException
+        );
+        assert_eq!(MintingStatus::ALL[18], MintingStatus::MintingException);
+        assert_eq!(
+            MintingStatus::ALL[21],
+            MintingStatus::UpdateTokenMetadataRequested
+        );
+        assert_eq!(
+            MintingStatus::ALL.iter().filter(|s| s.is_error()).count(),
+            7
+        );
+    }
+
+    #[test]
+    fn test_owner_display_name() {
+        let owner = SongOwner {
+            id: Some("abc".to_string()),
+            first_name: Some("Jane".to_string()),
+            last_name: Some("Doe".to_string()),
+            nickname: None,
+            email: None,
+        };
+        assert_eq!(owner.display_name(), "Jane Doe");
+    }
+}
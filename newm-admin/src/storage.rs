@@ -0,0 +1,118 @@
+//! Local Storage
+//!
+//! Small JSON files kept in the per-user data directory (for example
+//! `~/.local/share/newm-admin` on Linux) for state that should survive a
+//! restart but does not belong on the server, plus user-edited settings in
+//! the config directory (`~/.config/newm-admin`).
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+
+/// Directory name under the platform data directory
+const APP_DIR: &str = "newm-admin";
+
+/// Path of a named file in the app's data directory
+pub fn data_file(name: &str) -> Option<PathBuf> {
+    dirs::data_dir().map(|dir| dir.join(APP_DIR).join(name))
+}
+
+/// The app's config directory
+pub fn config_dir() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join(APP_DIR))
+}
+
+/// Path of a named file in the app's config directory
+pub fn config_file(name: &str) -> Option<PathBuf> {
+    config_dir().map(|dir| dir.join(name))
+}
+
+/// Read a JSON file, returning `None` if it does not exist yet
+pub fn read_json<T: DeserializeOwned>(path: &Path) -> Result<Option<T>, String> {
+    let contents = match fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(format!("Failed to read {}: {}", path.display(), e)),
+    };
+    serde_json::from_str(&contents)
+        .map(Some)
+        .map_err(|e| format!("Failed to parse {}: {}", path.display(), e))
+}
+
+/// Write a value as pretty-printed JSON, creating parent directories as needed.
+///
+/// The file is written to a temporary sibling first and then renamed into
+/// place, so a crash mid-write never leaves a truncated file behind.
+pub fn write_json<T: Serialize>(path: &Path, value: &T) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+    }
+    let json = serde_json::to_string_pretty(value)
+        .map_err(|e| format!("Failed to serialize {}: {}", path.display(), e))?;
+
+    let tmp_path = path.with_extension("tmp");
+    fs::write(&tmp_path, json)
+        .map_err(|e| format!("Failed to write {}: {}", tmp_path.display(), e))?;
+    fs::rename(&tmp_path, path).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+/// Append a value as one line of JSON, creating the file and its parent
+/// directories as needed
+pub fn append_json_line<T: Serialize>(path: &Path, value: &T) -> Result<(), String> {
+    use std::io::Write;
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+    }
+    let mut line = serde_json::to_string(value)
+        .map_err(|e| format!("Failed to serialize {}: {}", path.display(), e))?;
+    line.push('\n');
+
+    fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .and_then(|mut file| file.write_all(line.as_bytes()))
+        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn test_missing_file_reads_as_none() {
+        let dir = tempfile::tempdir().unwrap();
+        let value: Option<BTreeMap<String, u32>> =
+            read_json(&dir.path().join("missing.json")).unwrap();
+        assert!(value.is_none());
+    }
+
+    #[test]
+    fn test_write_then_read_round_trips() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("nested").join("state.json");
+        let value = BTreeMap::from([("garage".to_string(), 1u32), ("studio".to_string(), 2)]);
+
+        write_json(&path, &value).unwrap();
+
+        let read: BTreeMap<String, u32> = read_json(&path).unwrap().unwrap();
+        assert_eq!(read, value);
+        assert!(!path.with_extension("tmp").exists());
+    }
+
+    #[test]
+    fn test_corrupt_file_is_an_error() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("state.json");
+        fs::write(&path, "{not json").unwrap();
+
+        let result: Result<Option<BTreeMap<String, u32>>, String> = read_json(&path);
+        assert!(result.unwrap_err().starts_with("Failed to parse"));
+    }
+}
@@ -0,0 +1,172 @@
+//! Cardano Admin View
+//!
+//! Hosts the admin-only Cardano tools (script address whitelist, key pairs
+//! and encryption parameters) behind a tab bar.
+
+use gpui::*;
+use gpui_component::tab::{Tab, TabBar};
+use gpui_component::*;
+
+use crate::colors;
+use crate::session::{Session, SessionExpiredEvent};
+use crate::views::encryption::EncryptionView;
+use crate::views::keys::KeysView;
+use crate::views::whitelist::WhitelistView;
+
+/// Tabs of the Cardano admin view
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CardanoTab {
+    #[default]
+    Whitelist,
+    Keys,
+    Encryption,
+}
+
+impl CardanoTab {
+    const ALL: [CardanoTab; 3] = [
+        CardanoTab::Whitelist,
+        CardanoTab::Keys,
+        CardanoTab::Encryption,
+    ];
+
+    fn label(&self) -> &'static str {
+        match self {
+            CardanoTab::Whitelist => "Script Address Whitelist",
+            CardanoTab::Keys => "Key Pairs",
+            CardanoTab::Encryption => "Encryption",
+        }
+    }
+}
+
+/// Environment and network of a session, e.g. "Garage (Testnet)"
+pub(crate) fn environment_label(session: Option<&Session>) -> String {
+    session
+        .map(|s| {
+            format!(
+                "{} ({})",
+                s.environment().display_name(),
+                s.environment().network()
+            )
+        })
+        .unwrap_or_default()
+}
+
+/// Render a section heading
+pub(crate) fn section_title(title: &'static str) -> impl IntoElement {
+    div()
+        .text_lg()
+        .font_weight(FontWeight::SEMIBOLD)
+        .text_color(colors::text_primary())
+        .child(title)
+}
+
+pub struct CardanoView {
+    selected_tab: CardanoTab,
+    whitelist_view: Entity<WhitelistView>,
+    keys_view: Entity<KeysView>,
+    encryption_view: Entity<EncryptionView>,
+}
+
+impl CardanoView {
+    pub fn new(window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let whitelist_view = cx.new(|cx| WhitelistView::new(window, cx));
+        let keys_view = cx.new(|cx| KeysView::new(window, cx));
+        let encryption_view = cx.new(|cx| EncryptionView::new(window, cx));
+
+        // Forward session expiry from child views to DashboardView
+        cx.subscribe(
+            &whitelist_view,
+            |_this, _view, event: &SessionExpiredEvent, cx| {
+                cx.emit(SessionExpiredEvent {
+                    message: event.message.clone(),
+                });
+            },
+        )
+        .detach();
+        cx.subscribe(
+            &keys_view,
+            |_this, _view, event: &SessionExpiredEvent, cx| {
+                cx.emit(SessionExpiredEvent {
+                    message: event.message.clone(),
+                });
+            },
+        )
+        .detach();
+        cx.subscribe(
+            &encryption_view,
+            |_this, _view, event: &SessionExpiredEvent, cx| {
+                cx.emit(SessionExpiredEvent {
+                    message: event.message.clone(),
+                });
+            },
+        )
+        .detach();
+
+        Self {
+            selected_tab: CardanoTab::default(),
+            whitelist_view,
+            keys_view,
+            encryption_view,
+        }
+    }
+
+    /// Set the session (called from DashboardView after login)
+    pub fn set_session(&mut self, session: Option<Session>, cx: &mut Context<Self>) {
+        self.whitelist_view.update(cx, |view, cx| {
+            view.set_session(session.clone(), cx);
+        });
+        self.keys_view.update(cx, |view, cx| {
+            view.set_session(session.clone(), cx);
+        });
+        self.encryption_view.update(cx, |view, cx| {
+            view.set_session(session, cx);
+        });
+        cx.notify();
+    }
+}
+
+impl Render for CardanoView {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let selected_index = CardanoTab::ALL
+            .iter()
+            .position(|tab| *tab == self.selected_tab)
+            .unwrap_or_default();
+
+        div()
+            .v_flex()
+            .size_full()
+            .overflow_hidden()
+            .gap_6()
+            .child(
+                div()
+                    .text_2xl()
+                    .font_weight(FontWeight::BOLD)
+                    .text_color(colors::text_primary())
+                    .child("Cardano"),
+            )
+            .child(
+                TabBar::new("cardano-tabs")
+                    .underline()
+                    .selected_index(selected_index)
+                    .children(
+                        CardanoTab::ALL
+                            .iter()
+                            .map(|tab| Tab::new().label(tab.label())),
+                    )
+                    .on_click(cx.listener(|this, index: &usize, _window, cx| {
+                        this.selected_tab = CardanoTab::ALL[*index];
+                        cx.notify();
+                    })),
+            )
+            .child(
+                div()
+                    .flex_1()
+                    .overflow_hidden()
+                    .child(match self.selected_tab {
+                        CardanoTab::Whitelist => self.whitelist_view.clone().into_any_element(),
+                        CardanoTab::Keys => self.keys_view.clone().into_any_element(),
+                        CardanoTab::Encryption => self.encryption_view.clone().into_any_element(),
+                    }),
+            )
+    }
+}
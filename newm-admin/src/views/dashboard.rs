@@ -7,23 +7,40 @@ use gpui::prelude::FluentBuilder;
 use gpui::*;
 use gpui_component::button::{Button, ButtonVariants};
 use gpui_component::calendar::{Calendar, CalendarState, Date};
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
 use std::sync::Arc;
 
 use chrono::Datelike;
 use gpui::InteractiveElement;
 use gpui_component::input::*;
 use gpui_component::popover::Popover;
+use gpui_component::radio::{Radio, RadioGroup};
+use gpui_component::select::{Select, SelectEvent, SelectItem, SelectState};
 use gpui_component::table::{Column, ColumnSort, Table, TableDelegate, TableState};
 use gpui_component::tooltip::Tooltip;
 use gpui_component::*;
 
+use crate::cardano::{Network, as_stake_address, extract_stake_address};
 use crate::colors;
 const REFRESH_SVG: &[u8] = include_bytes!("../../assets/refresh.svg");
 const UPLOAD_SVG: &[u8] = include_bytes!("../../assets/upload.svg");
-use crate::csv_import::{CsvImportSummary, CsvResult, parse_csv, write_results};
-use crate::earnings::{Earning, EarningsClient, EarningsError, usd_to_amount};
-use crate::session::{Session, SessionExpiredEvent};
+use crate::app::LogoutEvent;
+use crate::csv_import::{
+    CsvImportSummary, CsvResult, CsvRow, RewardCsvResult, RewardCsvRow, parse_csv,
+    parse_rewards_csv, write_results, write_reward_results,
+};
+use crate::earnings::{
+    AddSongRoyaltyRequest, Earning, EarningsClient, EarningsError, RoyaltyCurrency, format_amount,
+    parse_amount, parse_reward_date,
+};
+use crate::preflight::{PreflightReport, lookup_song};
+use crate::session::{Session, SessionExpiredEvent, SessionExpiringEvent, SessionWatcher};
+use crate::songs::{Song, SongOwner, SongsClient, SongsError, is_isrc, is_uuid};
 use crate::toast;
+use crate::views::cardano::CardanoView;
+use crate::views::reprocess::ReprocessView;
+use crate::views::song_earnings::SongEarningsView;
 
 /// Currently selected menu item
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
@@ -31,6 +48,8 @@ pub enum MenuItem {
     #[default]
     Earnings,
     Refunds,
+    Reprocess,
+    Cardano,
 }
 
 impl MenuItem {
@@ -39,6 +58,8 @@ impl MenuItem {
         match self {
             MenuItem::Earnings => "Earnings",
             MenuItem::Refunds => "Refunds",
+            MenuItem::Reprocess => "Reprocess",
+            MenuItem::Cardano => "Cardano",
         }
     }
 
@@ -47,6 +68,8 @@ impl MenuItem {
         match self {
             MenuItem::Earnings => IconName::ChartPie,
             MenuItem::Refunds => IconName::Undo,
+            MenuItem::Reprocess => IconName::Redo,
+            MenuItem::Cardano => IconName::Globe,
         }
     }
 }
@@ -62,6 +85,195 @@ pub enum SortColumn {
     Amount,
     CreatedAt,
     Claimed,
+    Active,
+}
+
+/// Columns of the earnings table, in display order
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum EarningsColumn {
+    Select,
+    SongId,
+    StakeAddress,
+    Memo,
+    Amount,
+    Claimed,
+    Active,
+    StartDate,
+    EndDate,
+    ClaimOrderId,
+    NftPolicyId,
+    NftAssetName,
+    CreatedAt,
+}
+
+impl EarningsColumn {
+    const ALL: [EarningsColumn; 13] = [
+        EarningsColumn::Select,
+        EarningsColumn::SongId,
+        EarningsColumn::StakeAddress,
+        EarningsColumn::Memo,
+        EarningsColumn::Amount,
+        EarningsColumn::Claimed,
+        EarningsColumn::Active,
+        EarningsColumn::StartDate,
+        EarningsColumn::EndDate,
+        EarningsColumn::ClaimOrderId,
+        EarningsColumn::NftPolicyId,
+        EarningsColumn::NftAssetName,
+        EarningsColumn::CreatedAt,
+    ];
+
+    /// Columns shown before the user changes anything
+    const DEFAULT: [EarningsColumn; 8] = [
+        EarningsColumn::Select,
+        EarningsColumn::SongId,
+        EarningsColumn::StakeAddress,
+        EarningsColumn::Memo,
+        EarningsColumn::Amount,
+        EarningsColumn::Claimed,
+        EarningsColumn::Active,
+        EarningsColumn::CreatedAt,
+    ];
+
+    /// Columns the user can show or hide from the Columns menu
+    const OPTIONAL: [EarningsColumn; 6] = [
+        EarningsColumn::Active,
+        EarningsColumn::StartDate,
+        EarningsColumn::EndDate,
+        EarningsColumn::ClaimOrderId,
+        EarningsColumn::NftPolicyId,
+        EarningsColumn::NftAssetName,
+    ];
+
+    fn is_optional(&self) -> bool {
+        Self::OPTIONAL.contains(self)
+    }
+
+    fn label(&self) -> &'static str {
+        match self {
+            EarningsColumn::Select => "",
+            EarningsColumn::SongId => "Song ID",
+            EarningsColumn::StakeAddress => "Stake Address",
+            EarningsColumn::Memo => "Memo",
+            EarningsColumn::Amount => "Amount",
+            EarningsColumn::Claimed => "Claimed",
+            EarningsColumn::Active => "Active",
+            EarningsColumn::StartDate => "Start Date",
+            EarningsColumn::EndDate => "End Date",
+            EarningsColumn::ClaimOrderId => "Claim Order ID",
+            EarningsColumn::NftPolicyId => "NFT Policy ID",
+            EarningsColumn::NftAssetName => "NFT Asset Name",
+            EarningsColumn::CreatedAt => "Created At",
+        }
+    }
+
+    fn column(&self) -> Column {
+        let (key, width) = match self {
+            EarningsColumn::Select => ("select", 50.),
+            EarningsColumn::SongId => ("song_id", 250.),
+            EarningsColumn::StakeAddress => ("stake", 250.),
+            EarningsColumn::Memo => ("memo", 500.),
+            EarningsColumn::Amount => ("amount", 180.),
+            EarningsColumn::Claimed => ("claimed", 120.),
+            EarningsColumn::Active => ("active", 110.),
+            EarningsColumn::StartDate => ("start_date", 130.),
+            EarningsColumn::EndDate => ("end_date", 130.),
+            EarningsColumn::ClaimOrderId => ("claim_order_id", 300.),
+            EarningsColumn::NftPolicyId => ("nft_policy_id", 300.),
+            EarningsColumn::NftAssetName => ("nft_asset_name", 200.),
+            EarningsColumn::CreatedAt => ("created_at", 150.),
+        };
+        let column = Column::new(key, self.label()).width(px(width));
+        match self {
+            EarningsColumn::Amount
+            | EarningsColumn::Claimed
+            | EarningsColumn::Active
+            | EarningsColumn::CreatedAt => column.sortable(),
+            _ => column,
+        }
+    }
+}
+
+/// Status filter for the earnings table
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+enum EarningStatusFilter {
+    #[default]
+    All,
+    Active,
+    Inactive,
+    Unclaimed,
+    Claimed,
+    /// Attached to a claim order that has not completed yet
+    PendingClaim,
+}
+
+impl EarningStatusFilter {
+    const ALL: [EarningStatusFilter; 6] = [
+        EarningStatusFilter::All,
+        EarningStatusFilter::Active,
+        EarningStatusFilter::Inactive,
+        EarningStatusFilter::Unclaimed,
+        EarningStatusFilter::Claimed,
+        EarningStatusFilter::PendingClaim,
+    ];
+
+    fn label(&self) -> &'static str {
+        match self {
+            EarningStatusFilter::All => "All Earnings",
+            EarningStatusFilter::Active => "Active",
+            EarningStatusFilter::Inactive => "Inactive",
+            EarningStatusFilter::Unclaimed => "Unclaimed",
+            EarningStatusFilter::Claimed => "Claimed",
+            EarningStatusFilter::PendingClaim => "Pending Claim",
+        }
+    }
+
+    fn matches(&self, earning: &Earning) -> bool {
+        match self {
+            EarningStatusFilter::All => true,
+            EarningStatusFilter::Active => earning.is_active(),
+            EarningStatusFilter::Inactive => !earning.is_active(),
+            EarningStatusFilter::Unclaimed => !earning.claimed,
+            EarningStatusFilter::Claimed => earning.claimed,
+            EarningStatusFilter::PendingClaim => {
+                earning.claim_order_id.is_some() && !earning.claimed
+            }
+        }
+    }
+}
+
+impl SelectItem for EarningStatusFilter {
+    type Value = EarningStatusFilter;
+
+    fn title(&self) -> SharedString {
+        self.label().into()
+    }
+
+    fn value(&self) -> &Self::Value {
+        self
+    }
+}
+
+/// A royalties CSV that has been checked and waits for the admin to confirm
+struct CsvPreflight {
+    file_path: PathBuf,
+    report: PreflightReport,
+}
+
+/// A rewards CSV whose rows are all valid, waiting for the admin to confirm
+struct RewardsPreview {
+    file_path: PathBuf,
+    rows: Vec<RewardCsvRow>,
+    earnings: Vec<Earning>,
+}
+
+impl RewardsPreview {
+    /// NEWM total of all rewards in the file
+    fn total(&self) -> i64 {
+        self.earnings
+            .iter()
+            .fold(0i64, |total, earning| total.saturating_add(earning.amount))
+    }
 }
 
 /// Selection state for the table header checkbox
@@ -75,60 +287,115 @@ enum SelectionState {
     All,
 }
 
-/// Format 6-decimal integer amount with commas (e.g., 1000000 -> "1.000000", 1234567890 -> "1,234.567890")
-fn format_amount(amount: i64) -> String {
-    let sign = if amount < 0 { "-" } else { "" };
-    let abs_amount = amount.abs();
-    let integer_part = abs_amount / 1_000_000;
-    let decimal_part = abs_amount % 1_000_000;
-
-    let integer_str = integer_part.to_string();
-    let mut formatted_integer = String::new();
-    for (i, c) in integer_str.chars().rev().enumerate() {
-        if i > 0 && i % 3 == 0 {
-            formatted_integer.push(',');
+/// Input validator for amount fields: digits, a dot and thousands separators,
+/// with at most 6 decimal places (no negative values)
+fn is_amount_text(text: &str) -> bool {
+    // Allow empty input
+    if text.is_empty() {
+        return true;
+    }
+    // Check decimal places
+    if let Some(dot_pos) = text.find('.') {
+        let decimals = text.len() - dot_pos - 1;
+        if decimals > 6 {
+            return false; // Reject: too many decimal places
         }
-        formatted_integer.push(c);
     }
-    let formatted_integer: String = formatted_integer.chars().rev().collect();
+    text.chars()
+        .all(|c| c.is_ascii_digit() || c == '.' || c == ',')
+}
+
+/// Create a statistics card
+pub(crate) fn stat_card(
+    label: impl Into<SharedString>,
+    value: impl Into<SharedString>,
+    value_color: Rgba,
+) -> impl IntoElement {
+    div()
+        .v_flex()
+        .gap_2()
+        .p_4()
+        .rounded_lg()
+        .bg(colors::bg_surface())
+        .border_1()
+        .border_color(colors::border())
+        .min_w(px(180.0))
+        .child(
+            div()
+                .text_xs()
+                .text_color(colors::text_secondary())
+                .child(label.into()),
+        )
+        .child(
+            div()
+                .text_xl()
+                .font_weight(FontWeight::SEMIBOLD)
+                .text_color(value_color)
+                .child(value.into()),
+        )
+}
 
-    format!("{}{}.{:06}", sign, formatted_integer, decimal_part)
+/// Placeholder for the Add Earnings amount field in the given currency
+fn amount_placeholder(currency: RoyaltyCurrency) -> String {
+    format!("Amount in {} (e.g., 10.50)", currency)
 }
 
 // -----------------------------------------------------------------------------
 // Earnings Table Delegate
 // -----------------------------------------------------------------------------
 
+/// Callback invoked with a song ID from the earnings table
+type OpenSongHandler = Rc<dyn Fn(&str, &mut Window, &mut App)>;
+
 #[derive(Clone)]
 struct EarningsTableDelegate {
     earnings: Vec<Earning>,
     selected_ids: std::collections::HashSet<String>,
     sort_column: SortColumn,
     sort_direction: SortDirection,
+    visible_columns: Vec<EarningsColumn>,
     columns: Vec<Column>,
+    /// Called with the song ID when a Song ID cell is clicked
+    on_open_song: Option<OpenSongHandler>,
 }
 
 impl EarningsTableDelegate {
     fn new() -> Self {
+        let visible_columns = EarningsColumn::DEFAULT.to_vec();
         Self {
             earnings: Vec::new(),
             selected_ids: std::collections::HashSet::new(),
             sort_column: SortColumn::CreatedAt,
             sort_direction: SortDirection::Descending,
-            columns: vec![
-                Column::new("select", "").width(px(50.)),
-                Column::new("song_id", "Song ID").width(px(250.)),
-                Column::new("stake", "Stake Address").width(px(250.)),
-                Column::new("memo", "Memo").width(px(500.)),
-                Column::new("amount", "Amount").width(px(180.)).sortable(),
-                Column::new("claimed", "Claimed").width(px(120.)).sortable(),
-                Column::new("created_at", "Created At")
-                    .width(px(150.))
-                    .sortable(),
-            ],
+            columns: visible_columns.iter().map(|c| c.column()).collect(),
+            visible_columns,
+            on_open_song: None,
         }
     }
 
+    fn is_column_visible(&self, column: EarningsColumn) -> bool {
+        self.visible_columns.contains(&column)
+    }
+
+    /// Show or hide an optional column, keeping the canonical column order
+    fn set_column_visible(&mut self, column: EarningsColumn, visible: bool) {
+        if !column.is_optional() {
+            return;
+        }
+        self.visible_columns = EarningsColumn::ALL
+            .iter()
+            .copied()
+            .filter(|c| {
+                if *c == column {
+                    visible
+                } else {
+                    self.visible_columns.contains(c)
+                }
+            })
+            .collect();
+        self.columns = self.visible_columns.iter().map(|c| c.column()).collect();
+    }
+
     fn set_data(&mut self, data: Vec<Earning>) {
         self.earnings = data;
         // Clear selection when data changes
@@ -145,6 +412,7 @@ impl EarningsTableDelegate {
                 SortColumn::Amount => a.amount.cmp(&b.amount),
                 SortColumn::Claimed => a.claimed.cmp(&b.claimed),
                 SortColumn::CreatedAt => a.created_at.cmp(&b.created_at),
+                SortColumn::Active => a.is_active().cmp(&b.is_active()),
             };
             match dir {
                 SortDirection::Ascending => cmp,
@@ -155,8 +423,12 @@ impl EarningsTableDelegate {
 
     #[allow(dead_code)]
     fn set_memo_width(&mut self, width: f32) {
-        if let Some(col) = self.columns.get_mut(3) {
-            col.width = px(width);
+        if let Some(ix) = self
+            .visible_columns
+            .iter()
+            .position(|c| *c == EarningsColumn::Memo)
+        {
+            self.columns[ix].width = px(width);
         }
     }
 
@@ -223,7 +495,7 @@ impl EarningsTableDelegate {
 
 impl TableDelegate for EarningsTableDelegate {
     fn columns_count(&self, _cx: &App) -> usize {
-        7
+        self.columns.len()
     }
 
     fn rows_count(&self, _cx: &App) -> usize {
@@ -247,10 +519,11 @@ impl TableDelegate for EarningsTableDelegate {
             _ => SortDirection::Descending,
         };
 
-        self.sort_column = match col_ix {
-            4 => SortColumn::Amount,
-            5 => SortColumn::Claimed,
-            6 => SortColumn::CreatedAt,
+        self.sort_column = match self.visible_columns.get(col_ix) {
+            Some(EarningsColumn::Amount) => SortColumn::Amount,
+            Some(EarningsColumn::Claimed) => SortColumn::Claimed,
+            Some(EarningsColumn::Active) => SortColumn::Active,
+            Some(EarningsColumn::CreatedAt) => SortColumn::CreatedAt,
             _ => self.sort_column,
         };
 
@@ -264,8 +537,8 @@ impl TableDelegate for EarningsTableDelegate {
         _window: &mut Window,
         cx: &mut Context<TableState<Self>>,
     ) -> impl IntoElement {
-        // For the checkbox column (column 0), render a select-all checkbox
-        if col_ix == 0 {
+        // For the checkbox column, render a select-all checkbox
+        if self.visible_columns.get(col_ix) == Some(&EarningsColumn::Select) {
             let selection_state = self.selection_state();
             let (icon_name, is_checked) = match selection_state {
                 SelectionState::None => (None, false),
@@ -341,9 +614,11 @@ impl TableDelegate for EarningsTableDelegate {
         cx: &mut Context<TableState<Self>>,
     ) -> impl IntoElement {
         let earning = &self.earnings[row_ix];
-        match col_ix {
-            // Checkbox column
-            0 => {
+        let Some(column) = self.visible_columns.get(col_ix).copied() else {
+            return div();
+        };
+        match column {
+            EarningsColumn::Select => {
                 let id = earning.id.clone().unwrap_or_default();
                 let is_checked = self.selected_ids.contains(&id);
                 div()
@@ -366,73 +641,274 @@ impl TableDelegate for EarningsTableDelegate {
                         })),
                     )
             }
-            1 => div().size_full().overflow_hidden().child(
+            EarningsColumn::SongId => {
+                let song_id = earning.song_id.clone().unwrap_or_default();
                 div()
-                    .id(SharedString::from(format!("copy-song-{}", row_ix)))
                     .size_full()
+                    .overflow_hidden()
                     .flex()
                     .items_center()
-                    .justify_start()
-                    .px_2()
-                    .cursor_pointer()
-                    .hover(|s| s.bg(colors::bg_elevated()))
-                    .active(|s| s.bg(colors::border()))
                     .child(
                         div()
-                            .size_full()
-                            .overflow_hidden()
-                            .whitespace_nowrap()
-                            .text_ellipsis()
-                            .child(earning.song_id.clone().unwrap_or_default()),
+                            .id(SharedString::from(format!("open-song-{}", row_ix)))
+                            .flex_1()
+                            .h_full()
+                            .min_w_0()
+                            .flex()
+                            .items_center()
+                            .justify_start()
+                            .px_2()
+                            .cursor_pointer()
+                            .text_color(colors::secondary_text())
+                            .hover(|s| s.bg(colors::bg_elevated()))
+                            .active(|s| s.bg(colors::border()))
+                            .child(
+                                div()
+                                    .size_full()
+                                    .overflow_hidden()
+                                    .whitespace_nowrap()
+                                    .text_ellipsis()
+                                    .child(song_id.clone()),
+                            )
+                            .tooltip(move |window, cx| {
+                                Tooltip::new("View earnings for this song").build(window, cx)
+                            })
+                            .when_some(self.on_open_song.clone(), |this, on_open_song| {
+                                let song_id = song_id.clone();
+                                this.on_click(move |_, window, cx| {
+                                    if !song_id.is_empty() {
+                                        on_open_song(&song_id, window, cx);
+                                    }
+                                })
+                            }),
                     )
-                    .tooltip({
-                        let text = earning.song_id.clone().unwrap_or_default();
-                        move |window, cx| Tooltip::new(text.clone()).build(window, cx)
+                    .when(!song_id.is_empty(), |this| {
+                        this.child(
+                            Button::new(SharedString::from(format!("copy-song-{}", row_ix)))
+                                .ghost()
+                                .xsmall()
+                                .icon(IconName::Copy)
+                                .tooltip("Copy Song ID")
+                                .on_click(cx.listener(move |_, _, window, cx| {
+                                    cx.write_to_clipboard(ClipboardItem::new_string(
+                                        song_id.clone(),
+                                    ));
+                                    toast::show_info(window, cx, "Song ID copied to clipboard");
+                                    cx.notify();
+                                })),
+                        )
                     })
-                    .on_click(cx.listener({
-                        let text = earning.song_id.clone().unwrap_or_default();
-                        move |_, _, window, cx| {
-                            cx.write_to_clipboard(ClipboardItem::new_string(text.clone()));
-                            toast::show_info(window, cx, "Song ID copied to clipboard");
-                            cx.notify();
-                        }
-                    })),
+            }
+            EarningsColumn::StakeAddress => copyable_cell(
+                "copy-stake",
+                row_ix,
+                earning.stake_address.clone(),
+                "Stake Address copied to clipboard",
+                cx,
             ),
-            2 => div().size_full().overflow_hidden().child(
+            EarningsColumn::Memo => copyable_cell(
+                "copy-memo",
+                row_ix,
+                earning.memo.clone().unwrap_or_default(),
+                "Memo copied to clipboard",
+                cx,
+            ),
+            EarningsColumn::Amount => div().child(format!("Ɲ {}", format_amount(earning.amount))),
+            EarningsColumn::Claimed => div().child(if earning.claimed {
+                Icon::new(IconName::Check)
+                    .size(px(16.0))
+                    .text_color(colors::success())
+            } else {
+                Icon::new(IconName::Close)
+                    .size(px(16.0))
+                    .text_color(colors::text_muted())
+            }),
+            EarningsColumn::Active => div().child(active_badge(earning.is_active())),
+            EarningsColumn::StartDate => {
+                div().child(date_part(earning.start_date.as_deref().unwrap_or("")).to_string())
+            }
+            EarningsColumn::EndDate => {
+                div().child(date_part(earning.end_date.as_deref().unwrap_or("")).to_string())
+            }
+            EarningsColumn::ClaimOrderId => copyable_cell(
+                "copy-claim-order",
+                row_ix,
+                earning.claim_order_id.clone().unwrap_or_default(),
+                "Claim Order ID copied to clipboard",
+                cx,
+            ),
+            EarningsColumn::NftPolicyId => copyable_cell(
+                "copy-nft-policy",
+                row_ix,
+                earning.nft_policy_id.clone().unwrap_or_default(),
+                "NFT Policy ID copied to clipboard",
+                cx,
+            ),
+            EarningsColumn::NftAssetName => copyable_cell(
+                "copy-nft-asset",
+                row_ix,
+                earning.nft_asset_name.clone().unwrap_or_default(),
+                "NFT Asset Name copied to clipboard",
+                cx,
+            ),
+            EarningsColumn::CreatedAt => div().child(date_part(&earning.created_at).to_string()),
+        }
+    }
+}
+
+/// Table cell that copies its text to the clipboard when clicked
+fn copyable_cell(
+    id_prefix: &str,
+    row_ix: usize,
+    text: String,
+    copied_message: &'static str,
+    cx: &mut Context<TableState<EarningsTableDelegate>>,
+) -> Div {
+    div().size_full().overflow_hidden().child(
+        div()
+            .id(SharedString::from(format!("{}-{}", id_prefix, row_ix)))
+            .size_full()
+            .flex()
+            .items_center()
+            .justify_start()
+            .px_2()
+            .cursor_pointer()
+            .hover(|s| s.bg(colors::bg_elevated()))
+            .active(|s| s.bg(colors::border()))
+            .child(
                 div()
-                    .id(SharedString::from(format!("copy-stake-{}", row_ix)))
                     .size_full()
-                    .flex()
-                    .items_center()
-                    .justify_start()
-                    .px_2()
-                    .cursor_pointer()
-                    .hover(|s| s.bg(colors::bg_elevated()))
-                    .active(|s| s.bg(colors::border()))
-                    .child(
-                        div()
-                            .size_full()
-                            .overflow_hidden()
-                            .whitespace_nowrap()
-                            .text_ellipsis()
-                            .child(earning.stake_address.clone()),
-                    )
-                    .tooltip({
-                        let text = earning.stake_address.clone();
-                        move |window, cx| Tooltip::new(text.clone()).build(window, cx)
-                    })
-                    .on_click(cx.listener({
-                        let text = earning.stake_address.clone();
-                        move |_, _, window, cx| {
-                            cx.write_to_clipboard(ClipboardItem::new_string(text.clone()));
-                            toast::show_info(window, cx, "Stake Address copied to clipboard");
-                            cx.notify();
-                        }
-                    })),
-            ),
-            3 => div().size_full().overflow_hidden().child(
+                    .overflow_hidden()
+                    .whitespace_nowrap()
+                    .text_ellipsis()
+                    .child(text.clone()),
+            )
+            .tooltip({
+                let text = text.clone();
+                move |window, cx| Tooltip::new(text.clone()).build(window, cx)
+            })
+            .on_click(cx.listener(move |_, _, window, cx| {
+                cx.write_to_clipboard(ClipboardItem::new_string(text.clone()));
+                toast::show_info(window, cx, copied_message);
+                cx.notify();
+            })),
+    )
+}
+
+/// Write the rewards results file next to the CSV and return its name for the toast
+fn reward_results_file(file_path: &Path, results: &[RewardCsvResult]) -> String {
+    match write_reward_results(file_path, results) {
+        Ok(path) => path
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .to_string(),
+        Err(e) => {
+            tracing::warn!("Failed to write reward results: {}", e);
+            "(results file could not be written)".to_string()
+        }
+    }
+}
+
+/// Human-readable time until the session expires, e.g. "4 min"
+fn expiry_label(expires_in_secs: i64) -> String {
+    if expires_in_secs < 60 {
+        "less than a minute".to_string()
+    } else {
+        format!("{} min", expires_in_secs / 60)
+    }
+}
+
+/// Pill showing whether an earning is currently claimable
+fn active_badge(is_active: bool) -> Div {
+    div()
+        .px_2()
+        .rounded(px(4.0))
+        .text_xs()
+        .when(is_active, |this| {
+            this.bg(rgba(0x22c55e33))
+                .text_color(colors::success())
+                .child("Active")
+        })
+        .when(!is_active, |this| {
+            this.bg(colors::bg_elevated())
+                .text_color(colors::text_muted())
+                .child("Inactive")
+        })
+}
+
+/// Date portion of a server timestamp ("2025-01-31T12:00:00" -> "2025-01-31")
+pub(crate) fn date_part(timestamp: &str) -> &str {
+    timestamp.split('T').next().unwrap_or(timestamp)
+}
+
+// -----------------------------------------------------------------------------
+// Refunds Table Delegate
+// -----------------------------------------------------------------------------
+
+/// A refund issued during this session
+#[derive(Debug, Clone)]
+struct RefundRecord {
+    song_id: String,
+    title: String,
+    transaction_id: String,
+    message: String,
+    refunded_at: String,
+}
+
+#[derive(Clone)]
+struct RefundsTableDelegate {
+    refunds: Vec<RefundRecord>,
+    columns: Vec<Column>,
+}
+
+impl RefundsTableDelegate {
+    fn new() -> Self {
+        Self {
+            refunds: Vec::new(),
+            columns: vec![
+                Column::new("song_id", "Song ID").width(px(300.)),
+                Column::new("title", "Title").width(px(250.)),
+                Column::new("transaction_id", "Transaction ID").width(px(500.)),
+                Column::new("message", "Message").width(px(300.)),
+                Column::new("refunded_at", "Refunded At").width(px(180.)),
+            ],
+        }
+    }
+
+    /// Add a refund to the top of the history
+    fn push(&mut self, record: RefundRecord) {
+        self.refunds.insert(0, record);
+    }
+}
+
+impl TableDelegate for RefundsTableDelegate {
+    fn columns_count(&self, _cx: &App) -> usize {
+        self.columns.len()
+    }
+
+    fn rows_count(&self, _cx: &App) -> usize {
+        self.refunds.len()
+    }
+
+    fn column(&self, col_ix: usize, _cx: &App) -> &Column {
+        &self.columns[col_ix]
+    }
+
+    fn render_td(
+        &mut self,
+        row_ix: usize,
+        col_ix: usize,
+        _window: &mut Window,
+        cx: &mut Context<TableState<Self>>,
+    ) -> impl IntoElement {
+        let refund = &self.refunds[row_ix];
+        match col_ix {
+            0 => div().child(refund.song_id.clone()),
+            1 => div().child(refund.title.clone()),
+            2 => div().size_full().overflow_hidden().child(
                 div()
-                    .id(SharedString::from(format!("copy-memo-{}", row_ix)))
+                    .id(SharedString::from(format!("copy-refund-tx-{}", row_ix)))
                     .size_full()
                     .flex()
                     .items_center()
@@ -447,59 +923,66 @@ impl TableDelegate for EarningsTableDelegate {
                             .overflow_hidden()
                             .whitespace_nowrap()
                             .text_ellipsis()
-                            .child(earning.memo.clone().unwrap_or_default()),
+                            .child(refund.transaction_id.clone()),
                     )
                     .tooltip({
-                        let text = earning.memo.clone().unwrap_or_default();
+                        let text = refund.transaction_id.clone();
                         move |window, cx| Tooltip::new(text.clone()).build(window, cx)
                     })
                     .on_click(cx.listener({
-                        let text = earning.memo.clone().unwrap_or_default();
+                        let text = refund.transaction_id.clone();
                         move |_, _, window, cx| {
                             cx.write_to_clipboard(ClipboardItem::new_string(text.clone()));
-                            toast::show_info(window, cx, "Memo copied to clipboard");
+                            toast::show_info(window, cx, "Transaction ID copied to clipboard");
                             cx.notify();
                         }
                     })),
             ),
-            4 => div().child(format!("Ɲ {}", format_amount(earning.amount))),
-            5 => div().child(if earning.claimed {
-                Icon::new(IconName::Check)
-                    .size(px(16.0))
-                    .text_color(colors::success())
-            } else {
-                Icon::new(IconName::Close)
-                    .size(px(16.0))
-                    .text_color(colors::text_muted())
-            }),
-            6 => div().child(
-                earning
-                    .created_at
-                    .split('T')
-                    .next()
-                    .unwrap_or(&earning.created_at)
-                    .to_string(),
-            ),
+            3 => div().child(refund.message.clone()),
+            4 => div().child(refund.refunded_at.clone()),
             _ => div(),
         }
     }
 }
 
+/// Song and owner details loaded for a refund
+#[derive(Debug, Clone)]
+struct RefundLookup {
+    song: Song,
+    owner: Option<SongOwner>,
+}
+
 pub struct DashboardView {
     selected_menu: MenuItem,
     session: Option<Session>,
+    session_watcher: Option<Entity<SessionWatcher>>,
 
     // Add Earnings panel state
     show_add_earnings: bool,
     song_id_input: Entity<InputState>,
-    usd_amount_input: Entity<InputState>,
+    amount_input: Entity<InputState>,
+    royalty_currency: RoyaltyCurrency,
     is_submitting: bool,
     form_error: Option<String>,
     clear_form_on_open: bool,
 
+    // Add Reward form state
+    show_add_reward: bool,
+    reward_stake_input: Entity<InputState>,
+    reward_amount_input: Entity<InputState>,
+    reward_memo_input: Entity<InputState>,
+    reward_start_input: Entity<InputState>,
+    reward_end_input: Entity<InputState>,
+    is_submitting_reward: bool,
+    reward_form_error: Option<String>,
+    is_importing_rewards: bool,
+    rewards_preview: Option<RewardsPreview>,
+
     // CSV Import state
     is_importing_csv: bool,
+    csv_checking: bool,
     csv_import_progress: Option<(usize, usize)>, // (current, total)
+    csv_preflight: Option<CsvPreflight>,
 
     // Delete Earnings state
     is_deleting: bool,
@@ -511,16 +994,44 @@ pub struct DashboardView {
     is_loading_earnings: bool,
     // Filtering
     search_input: Entity<InputState>,
+    status_filter: Entity<SelectState<Vec<EarningStatusFilter>>>,
     calendar_state: Entity<CalendarState>,
     date_picker_open: bool,
     table: Entity<TableState<EarningsTableDelegate>>,
+
+    // Refunds state
+    refund_song_input: Entity<InputState>,
+    is_looking_up_song: bool,
+    refund_lookup: Option<RefundLookup>,
+    refund_error: Option<String>,
+    is_refunding: bool,
+    show_refund_confirmation: bool,
+    refunds_table: Entity<TableState<RefundsTableDelegate>>,
+
+    // Reprocess view
+    reprocess_view: Entity<ReprocessView>,
+
+    // Cardano admin view
+    cardano_view: Entity<CardanoView>,
+
+    // Per-song drill-down, shown in place of the earnings panel when open
+    song_detail: Option<Entity<SongEarningsView>>,
 }
 
 impl DashboardView {
     pub fn new(window: &mut Window, cx: &mut Context<Self>) -> Self {
         let song_id_input = cx.new(|cx| InputState::new(window, cx).placeholder("Song ID or ISRC"));
         let search_input = cx.new(|cx| {
-            InputState::new(window, cx).placeholder("Search by Song ID, Stake Address, or Memo")
+            InputState::new(window, cx)
+                .placeholder("Search earnings, or press Enter on a Song ID or ISRC")
+        });
+        let status_filter = cx.new(|cx| {
+            SelectState::new(
+                EarningStatusFilter::ALL.to_vec(),
+                Some(IndexPath::default()),
+                window,
+                cx,
+            )
         });
 
         // Initialize Calendar State
@@ -531,73 +1042,199 @@ impl DashboardView {
             state
         });
 
-        let usd_amount_input = cx.new(|cx| {
+        let amount_input = cx.new(|cx| {
             InputState::new(window, cx)
-                .placeholder("Amount in USD (e.g., 10.50)")
-                .validate(|text, _cx| {
-                    // Allow empty input
-                    if text.is_empty() {
-                        return true;
-                    }
-                    // Check decimal places
-                    if let Some(dot_pos) = text.find('.') {
-                        let decimals = text.len() - dot_pos - 1;
-                        if decimals > 6 {
-                            return false; // Reject: too many decimal places
-                        }
-                    }
-                    // Allow digits and single dot only (no negative values)
-                    text.chars().all(|c| c.is_ascii_digit() || c == '.')
-                })
+                .placeholder(amount_placeholder(RoyaltyCurrency::default()))
+                .validate(|text, _cx| is_amount_text(text))
         });
 
-        let delegate = EarningsTableDelegate::new();
+        let reward_stake_input =
+            cx.new(|cx| InputState::new(window, cx).placeholder("stake1... or wallet address"));
+        let reward_amount_input = cx.new(|cx| {
+            InputState::new(window, cx)
+                .placeholder("Amount in NEWM (e.g., 10.50)")
+                .validate(|text, _cx| is_amount_text(text))
+        });
+        let reward_memo_input = cx.new(|cx| {
+            InputState::new(window, cx).placeholder("Shown to the user, e.g. Learn to Earn reward")
+        });
+        let reward_start_input =
+            cx.new(|cx| InputState::new(window, cx).placeholder("YYYY-MM-DD (optional)"));
+        let reward_end_input =
+            cx.new(|cx| InputState::new(window, cx).placeholder("YYYY-MM-DD (optional)"));
+
+        let mut delegate = EarningsTableDelegate::new();
+        let dashboard = cx.entity().downgrade();
+        delegate.on_open_song = Some(Rc::new(move |song_id, window, cx| {
+            dashboard
+                .update(cx, |view, cx| view.open_song_detail(song_id, window, cx))
+                .ok();
+        }));
         // Create table state
         let table = cx.new(|cx| TableState::new(delegate, window, cx));
 
+        let refund_song_input =
+            cx.new(|cx| InputState::new(window, cx).placeholder("Song ID or ISRC"));
+        let refunds_table = cx.new(|cx| TableState::new(RefundsTableDelegate::new(), window, cx));
+
+        let reprocess_view = cx.new(|cx| ReprocessView::new(window, cx));
+        // Forward session expiry from child views to AdminApp
+        cx.subscribe(
+            &reprocess_view,
+            |_this, _view, event: &SessionExpiredEvent, cx| {
+                cx.emit(SessionExpiredEvent {
+                    message: event.message.clone(),
+                });
+            },
+        )
+        .detach();
+
+        let cardano_view = cx.new(|cx| CardanoView::new(window, cx));
+        cx.subscribe(
+            &cardano_view,
+            |_this, _view, event: &SessionExpiredEvent, cx| {
+                cx.emit(SessionExpiredEvent {
+                    message: event.message.clone(),
+                });
+            },
+        )
+        .detach();
+
         cx.observe(&search_input, |this: &mut Self, _, cx| {
             this.update_table(cx);
         })
         .detach();
 
+        // Enter on a full Song ID or ISRC opens that song's earnings
+        cx.subscribe_in(
+            &search_input,
+            window,
+            |this, input, event: &input::InputEvent, window, cx| {
+                if let input::InputEvent::PressEnter { .. } = event {
+                    let query = input.read(cx).value().trim().to_string();
+                    if is_uuid(&query) || is_isrc(&query) {
+                        this.open_song_detail(&query, window, cx);
+                    }
+                }
+            },
+        )
+        .detach();
+
         cx.observe(&calendar_state, |this: &mut Self, _, cx| {
             this.update_table(cx);
         })
         .detach();
 
+        cx.subscribe(
+            &status_filter,
+            |this, _, _: &SelectEvent<Vec<EarningStatusFilter>>, cx| {
+                this.update_table(cx);
+            },
+        )
+        .detach();
+
         Self {
             selected_menu: MenuItem::default(),
             session: None,
+            session_watcher: None,
             show_add_earnings: false,
             song_id_input,
-            usd_amount_input,
+            amount_input,
+            royalty_currency: RoyaltyCurrency::default(),
             is_submitting: false,
             form_error: None,
             clear_form_on_open: false,
+            show_add_reward: false,
+            reward_stake_input,
+            reward_amount_input,
+            reward_memo_input,
+            reward_start_input,
+            reward_end_input,
+            is_submitting_reward: false,
+            reward_form_error: None,
+            is_importing_rewards: false,
+            rewards_preview: None,
             is_importing_csv: false,
+            csv_checking: false,
             csv_import_progress: None,
+            csv_preflight: None,
             is_deleting: false,
             show_delete_confirmation: false,
             earnings: None,
             filtered_earnings: None,
             is_loading_earnings: false,
             search_input,
+            status_filter,
             calendar_state,
             date_picker_open: false,
             table,
+            refund_song_input,
+            is_looking_up_song: false,
+            refund_lookup: None,
+            refund_error: None,
+            is_refunding: false,
+            show_refund_confirmation: false,
+            refunds_table,
+            reprocess_view,
+            cardano_view,
+            song_detail: None,
         }
     }
 
+    /// Open the per-song earnings drill-down
+    fn open_song_detail(
+        &mut self,
+        song_id_or_isrc: &str,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let session = self.session.clone();
+        let song_id_or_isrc = song_id_or_isrc.to_string();
+        let detail = cx.new(|cx| SongEarningsView::new(song_id_or_isrc, session, window, cx));
+
+        cx.subscribe(&detail, |this, _, _: &DismissEvent, cx| {
+            this.song_detail = None;
+            cx.notify();
+        })
+        .detach();
+        cx.subscribe(&detail, |_this, _, event: &SessionExpiredEvent, cx| {
+            cx.emit(SessionExpiredEvent {
+                message: event.message.clone(),
+            });
+        })
+        .detach();
+
+        self.selected_menu = MenuItem::Earnings;
+        self.show_add_earnings = false;
+        self.show_add_reward = false;
+        self.song_detail = Some(detail);
+        cx.notify();
+    }
+
     fn update_table(&mut self, cx: &mut Context<Self>) {
         if let Some(earnings) = &self.earnings {
             let search_query = self.search_input.read(cx).text().to_string().to_lowercase();
+            // A pasted wallet (base) address matches earnings of its stake address
+            let search_stake_address = self
+                .session
+                .as_ref()
+                .and_then(|s| extract_stake_address(&search_query, s.environment().network()).ok())
+                .map(|address| address.to_string());
             let date_range = self.calendar_state.read(cx).date();
+            let status_filter = self
+                .status_filter
+                .read(cx)
+                .selected_value()
+                .copied()
+                .unwrap_or_default();
 
             let filtered_earnings: Vec<Earning> = earnings
                 .iter()
                 .filter(|earning| {
                     let matches_search = if search_query.is_empty() {
                         true
+                    } else if let Some(stake_address) = &search_stake_address {
+                        earning.stake_address == *stake_address
                     } else {
                         earning
                             .song_id
@@ -606,12 +1243,20 @@ impl DashboardView {
                             .to_lowercase()
                             .contains(&search_query)
                             || earning.stake_address.to_lowercase().contains(&search_query)
-                            || earning
-                                .memo
-                                .as_deref()
-                                .unwrap_or("")
-                                .to_lowercase()
-                                .contains(&search_query)
+                            || [
+                                &earning.memo,
+                                &earning.claim_order_id,
+                                &earning.nft_policy_id,
+                                &earning.nft_asset_name,
+                            ]
+                            .iter()
+                            .any(|field| {
+                                field
+                                    .as_deref()
+                                    .unwrap_or("")
+                                    .to_lowercase()
+                                    .contains(&search_query)
+                            })
                     };
 
                     let matches_date = match date_range {
@@ -641,7 +1286,7 @@ impl DashboardView {
                         _ => true,
                     };
 
-                    matches_search && matches_date
+                    matches_search && matches_date && status_filter.matches(earning)
                 })
                 .cloned()
                 .collect();
@@ -659,8 +1304,43 @@ impl DashboardView {
     /// Set the session (called from AdminApp after login)
     pub fn set_session(&mut self, session: Option<Session>, cx: &mut Context<Self>) {
         self.session = session;
-        if self.session.is_some() {
-            self.fetch_earnings(cx);
+        self.session_watcher = self.session.clone().map(|session| {
+            let watcher = cx.new(|cx| SessionWatcher::new(session, cx));
+            cx.subscribe(
+                &watcher,
+                |_this, _watcher, event: &SessionExpiredEvent, cx| {
+                    cx.emit(SessionExpiredEvent {
+                        message: event.message.clone(),
+                    });
+                },
+            )
+            .detach();
+            cx.subscribe(
+                &watcher,
+                |_this, _watcher, event: &SessionExpiringEvent, cx| {
+                    toast::show_warning_async(
+                        cx,
+                        format!(
+                            "Your session expires in {}. Choose \"Stay signed in\" to keep working.",
+                            expiry_label(event.expires_in_secs)
+                        ),
+                    );
+                },
+            )
+            .detach();
+            cx.observe(&watcher, |_this, _watcher, cx| cx.notify())
+                .detach();
+            watcher
+        });
+        self.song_detail = None;
+        self.reprocess_view.update(cx, |view, cx| {
+            view.set_session(self.session.clone(), cx);
+        });
+        self.cardano_view.update(cx, |view, cx| {
+            view.set_session(self.session.clone(), cx);
+        });
+        if self.session.is_some() {
+            self.fetch_earnings(cx);
         }
     }
 
@@ -671,6 +1351,315 @@ impl DashboardView {
         }
     }
 
+    /// Banner shown when the session is about to run out
+    fn session_expiry_banner(&self, cx: &mut Context<Self>) -> Option<impl IntoElement> {
+        let watcher = self.session_watcher.as_ref()?.read(cx);
+        let expires_in_secs = watcher.expires_in_secs()?;
+        let is_refreshing = watcher.is_refreshing();
+
+        Some(
+            div()
+                .h_flex()
+                .gap_3()
+                .items_center()
+                .justify_between()
+                .px_4()
+                .py_2()
+                .mb_4()
+                .rounded(px(4.0))
+                .border_1()
+                .border_color(colors::warning())
+                .child(
+                    div()
+                        .h_flex()
+                        .gap_2()
+                        .items_center()
+                        .text_sm()
+                        .text_color(colors::warning())
+                        .child(Icon::new(IconName::TriangleAlert).size(px(16.0)))
+                        .child(format!(
+                            "Session expires in {}",
+                            expiry_label(expires_in_secs)
+                        )),
+                )
+                .child(
+                    Button::new("stay-signed-in-btn")
+                        .label(if is_refreshing {
+                            "Refreshing..."
+                        } else {
+                            "Stay signed in"
+                        })
+                        .small()
+                        .outline()
+                        .disabled(is_refreshing)
+                        .on_click(cx.listener(|this, _, _window, cx| {
+                            if let Some(watcher) = &this.session_watcher {
+                                watcher.update(cx, |watcher, cx| watcher.stay_signed_in(cx));
+                            }
+                        })),
+                ),
+        )
+    }
+
+    /// Preflight report for a picked CSV, with the button that starts the import
+    fn csv_preflight_dialog(&self, cx: &mut Context<Self>) -> Option<impl IntoElement> {
+        let preflight = self.csv_preflight.as_ref()?;
+        let report = &preflight.report;
+        let ready = report.is_ready();
+        let file_name = preflight
+            .file_path
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .to_string();
+
+        let rows = report.rows.iter().enumerate().map(|(i, checked)| {
+            let (status, color) = if !checked.is_ready() {
+                (checked.errors.join("; "), colors::error())
+            } else if !checked.warnings.is_empty() {
+                (checked.warnings.join("; "), colors::warning())
+            } else {
+                (
+                    checked
+                        .title
+                        .clone()
+                        .unwrap_or_else(|| "Song found".to_string()),
+                    colors::success(),
+                )
+            };
+            div()
+                .h_flex()
+                .gap_3()
+                .py_1()
+                .text_sm()
+                .border_b_1()
+                .border_color(colors::border())
+                .child(
+                    div()
+                        .w(px(40.0))
+                        .text_color(colors::text_muted())
+                        .child(format!("{}", i + 1)),
+                )
+                .child(
+                    div()
+                        .w(px(300.0))
+                        .text_color(colors::text_primary())
+                        .child(checked.row.song_id_or_isrc.clone()),
+                )
+                .child(div().w(px(160.0)).text_color(colors::text_primary()).child(
+                    match checked.amount {
+                        Some(amount) => {
+                            format!("{} {}", format_amount(amount), checked.row.currency)
+                        }
+                        None => format!("{} {}", checked.row.amount, checked.row.currency),
+                    },
+                ))
+                .child(div().flex_1().text_color(color).child(status))
+        });
+
+        Some(
+            div()
+                .absolute()
+                .inset_0()
+                .flex()
+                .items_center()
+                .justify_center()
+                .bg(gpui::Rgba {
+                    r: 0.0,
+                    g: 0.0,
+                    b: 0.0,
+                    a: 0.5,
+                })
+                .child(
+                    div()
+                        .v_flex()
+                        .gap_4()
+                        .p_6()
+                        .rounded_lg()
+                        .bg(colors::bg_surface())
+                        .border_1()
+                        .border_color(colors::border())
+                        .shadow_lg()
+                        .w(px(900.0))
+                        .child(
+                            div()
+                                .text_xl()
+                                .font_weight(gpui::FontWeight::BOLD)
+                                .text_color(colors::text_primary())
+                                .child(format!("Import Preview: {}", file_name)),
+                        )
+                        .child(
+                            div()
+                                .text_color(if ready {
+                                    colors::text_secondary()
+                                } else {
+                                    colors::error()
+                                })
+                                .child(report.summary()),
+                        )
+                        .child(
+                            div()
+                                .id("csv-preflight-rows")
+                                .v_flex()
+                                .max_h(px(400.0))
+                                .overflow_y_scroll()
+                                .children(rows),
+                        )
+                        .when(!ready, |this| {
+                            this.child(div().text_sm().text_color(colors::text_secondary()).child(
+                                "Fix the rows with problems and upload the file again. \
+                                 Nothing has been imported.",
+                            ))
+                        })
+                        .child(
+                            div()
+                                .h_flex()
+                                .gap_3()
+                                .justify_end()
+                                .child(
+                                    Button::new("cancel-csv-import-btn")
+                                        .label("Cancel")
+                                        .ghost()
+                                        .on_click(cx.listener(|this, _, _window, cx| {
+                                            this.csv_preflight = None;
+                                            cx.notify();
+                                        })),
+                                )
+                                .child(
+                                    Button::new("confirm-csv-import-btn")
+                                        .label(format!("Import {} Rows", report.rows.len()))
+                                        .primary()
+                                        .disabled(!ready)
+                                        .on_click(cx.listener(|this, _, _window, cx| {
+                                            this.confirm_csv_import(cx);
+                                        })),
+                                ),
+                        ),
+                ),
+        )
+    }
+
+    /// Parsed rewards CSV with its NEWM total, with the button that sends it
+    fn rewards_preview_dialog(&self, cx: &mut Context<Self>) -> Option<impl IntoElement> {
+        let preview = self.rewards_preview.as_ref()?;
+        let file_name = preview
+            .file_path
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .to_string();
+
+        let rows = preview.earnings.iter().enumerate().map(|(i, earning)| {
+            div()
+                .h_flex()
+                .gap_3()
+                .py_1()
+                .text_sm()
+                .border_b_1()
+                .border_color(colors::border())
+                .child(
+                    div()
+                        .w(px(40.0))
+                        .text_color(colors::text_muted())
+                        .child(format!("{}", i + 1)),
+                )
+                .child(
+                    div()
+                        .w(px(360.0))
+                        .overflow_hidden()
+                        .whitespace_nowrap()
+                        .text_ellipsis()
+                        .text_color(colors::text_primary())
+                        .child(earning.stake_address.clone()),
+                )
+                .child(
+                    div()
+                        .w(px(160.0))
+                        .text_color(colors::text_primary())
+                        .child(format!("Ɲ {}", format_amount(earning.amount))),
+                )
+                .child(
+                    div()
+                        .flex_1()
+                        .overflow_hidden()
+                        .whitespace_nowrap()
+                        .text_ellipsis()
+                        .text_color(colors::text_secondary())
+                        .child(earning.memo.clone().unwrap_or_default()),
+                )
+        });
+
+        Some(
+            div()
+                .absolute()
+                .inset_0()
+                .flex()
+                .items_center()
+                .justify_center()
+                .bg(gpui::Rgba {
+                    r: 0.0,
+                    g: 0.0,
+                    b: 0.0,
+                    a: 0.5,
+                })
+                .child(
+                    div()
+                        .v_flex()
+                        .gap_4()
+                        .p_6()
+                        .rounded_lg()
+                        .bg(colors::bg_surface())
+                        .border_1()
+                        .border_color(colors::border())
+                        .shadow_lg()
+                        .w(px(900.0))
+                        .child(
+                            div()
+                                .text_xl()
+                                .font_weight(gpui::FontWeight::BOLD)
+                                .text_color(colors::text_primary())
+                                .child(format!("Rewards Preview: {}", file_name)),
+                        )
+                        .child(div().text_color(colors::text_secondary()).child(format!(
+                            "{} rewards. Total: {} NEWM",
+                            preview.earnings.len(),
+                            format_amount(preview.total())
+                        )))
+                        .child(
+                            div()
+                                .id("rewards-preview-rows")
+                                .v_flex()
+                                .max_h(px(400.0))
+                                .overflow_y_scroll()
+                                .children(rows),
+                        )
+                        .child(
+                            div()
+                                .h_flex()
+                                .gap_3()
+                                .justify_end()
+                                .child(
+                                    Button::new("cancel-rewards-upload-btn")
+                                        .label("Cancel")
+                                        .ghost()
+                                        .on_click(cx.listener(|this, _, _window, cx| {
+                                            this.rewards_preview = None;
+                                            cx.notify();
+                                        })),
+                                )
+                                .child(
+                                    Button::new("confirm-rewards-upload-btn")
+                                        .label(format!("Send {} Rewards", preview.earnings.len()))
+                                        .primary()
+                                        .on_click(cx.listener(|this, _, _window, cx| {
+                                            this.confirm_rewards_upload(cx);
+                                        })),
+                                ),
+                        ),
+                ),
+        )
+    }
+
     /// Render a sidebar menu button
     fn menu_button(&self, item: MenuItem, cx: &mut Context<Self>) -> impl IntoElement {
         let is_selected = self.selected_menu == item;
@@ -706,6 +1695,7 @@ impl DashboardView {
             )
             .on_click(cx.listener(move |this, _, _window, cx| {
                 this.selected_menu = item;
+                this.song_detail = None;
                 this.refresh_data(cx);
                 cx.notify();
             }))
@@ -714,11 +1704,54 @@ impl DashboardView {
     /// Render the work area content based on selected menu
     fn work_area_content(&self, cx: &mut Context<Self>) -> AnyElement {
         match self.selected_menu {
-            MenuItem::Earnings => self.earnings_panel(cx).into_any_element(),
-            MenuItem::Refunds => self.refunds_panel().into_any_element(),
+            MenuItem::Earnings => match &self.song_detail {
+                Some(detail) => detail.clone().into_any_element(),
+                None => self.earnings_panel(cx).into_any_element(),
+            },
+            MenuItem::Refunds => self.refunds_panel(cx).into_any_element(),
+            MenuItem::Reprocess => self.reprocess_view.clone().into_any_element(),
+            MenuItem::Cardano => self.cardano_view.clone().into_any_element(),
         }
     }
 
+    /// Popover for showing and hiding the optional earnings table columns
+    fn columns_picker(&self) -> impl IntoElement {
+        let table = self.table.clone();
+        Popover::new("earnings-columns-picker")
+            .trigger(
+                Button::new("earnings-columns-btn")
+                    .icon(Icon::new(IconName::Settings2).size(px(16.0)))
+                    .tooltip("Columns")
+                    .ghost(),
+            )
+            .content(move |_, _, cx| {
+                let popover = cx.entity();
+                div()
+                    .v_flex()
+                    .gap_2()
+                    .p_1()
+                    .children(EarningsColumn::OPTIONAL.iter().map(|column| {
+                        let column = *column;
+                        let table = table.clone();
+                        let popover = popover.clone();
+                        gpui_component::checkbox::Checkbox::new(SharedString::from(format!(
+                            "toggle-column-{}",
+                            column.label()
+                        )))
+                        .label(column.label())
+                        .checked(table.read(cx).delegate().is_column_visible(column))
+                        .on_click(move |checked, _window, cx| {
+                            table.update(cx, |table, cx| {
+                                table.delegate_mut().set_column_visible(column, *checked);
+                                table.refresh(cx);
+                                cx.notify();
+                            });
+                            popover.update(cx, |_, cx| cx.notify());
+                        })
+                    }))
+            })
+    }
+
     /// Earnings work area panel
     fn earnings_panel(&self, cx: &mut Context<Self>) -> impl IntoElement {
         let filtered_rows = self.filtered_earnings.clone().unwrap_or_default();
@@ -797,13 +1830,15 @@ impl DashboardView {
                                             }
                                         }),
                                 ),
-                            ),
+                            )
+                            .child(div().w(px(180.0)).child(Select::new(&self.status_filter))),
                     )
                     // Toolbar with Add Earnings button
                     .child(
                         div()
                             .h_flex()
                             .gap_2()
+                            .child(self.columns_picker())
                             .child(
                                 Button::new("refresh-earnings-btn")
                                     .child(
@@ -830,16 +1865,29 @@ impl DashboardView {
                                             this.song_id_input.update(cx, |state, cx| {
                                                 state.set_value("", window, cx);
                                             });
-                                            this.usd_amount_input.update(cx, |state, cx| {
+                                            this.amount_input.update(cx, |state, cx| {
                                                 state.set_value("", window, cx);
                                             });
                                             this.clear_form_on_open = false;
                                         }
                                         this.show_add_earnings = true;
+                                        this.show_add_reward = false;
                                         this.form_error = None;
                                         cx.notify();
                                     })),
                             )
+                            .child(
+                                Button::new("add-reward-btn")
+                                    .label("Add Reward")
+                                    .icon(Icon::new(IconName::Plus).size(px(16.0)))
+                                    .disabled(self.is_importing_csv || self.is_importing_rewards)
+                                    .on_click(cx.listener(|this, _, _window, cx| {
+                                        this.show_add_reward = true;
+                                        this.show_add_earnings = false;
+                                        this.reward_form_error = None;
+                                        cx.notify();
+                                    })),
+                            )
                             .child(
                                 Button::new("upload-csv-btn")
                                     .child(
@@ -850,10 +1898,15 @@ impl DashboardView {
                                         .size(px(16.0)),
                                     )
                                     .label(if self.is_importing_csv {
+                                        let action = if self.csv_checking {
+                                            "Checking"
+                                        } else {
+                                            "Importing"
+                                        };
                                         if let Some((current, total)) = self.csv_import_progress {
-                                            format!("Importing {}/{}...", current, total)
+                                            format!("{} {}/{}...", action, current, total)
                                         } else {
-                                            "Importing...".to_string()
+                                            format!("{}...", action)
                                         }
                                     } else {
                                         "Upload CSV".to_string()
@@ -898,17 +1951,17 @@ impl DashboardView {
                 div()
                     .h_flex()
                     .gap_4()
-                    .child(self.stat_card(
+                    .child(stat_card(
                         "Total Earnings",
                         format!("Ɲ {}", format_amount(total)),
                         colors::success(),
                     ))
-                    .child(self.stat_card(
+                    .child(stat_card(
                         "Claimed",
                         format!("Ɲ {}", format_amount(claimed)),
                         colors::text_primary(),
                     ))
-                    .child(self.stat_card(
+                    .child(stat_card(
                         "Unclaimed",
                         format!("Ɲ {}", format_amount(unclaimed)),
                         colors::text_secondary(),
@@ -940,9 +1993,20 @@ impl DashboardView {
     }
 
     /// Refunds work area panel
-    fn refunds_panel(&self) -> impl IntoElement {
+    fn refunds_panel(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let refund_count = self.refunds_table.read(cx).delegate().refunds.len();
+        let last_transaction = self
+            .refunds_table
+            .read(cx)
+            .delegate()
+            .refunds
+            .first()
+            .map(|r| r.transaction_id.clone());
+
         div()
             .v_flex()
+            .size_full()
+            .overflow_hidden()
             .gap_6()
             .child(
                 div()
@@ -954,43 +2018,138 @@ impl DashboardView {
             .child(
                 div()
                     .text_color(colors::text_secondary())
-                    .child("Process and track refund requests."),
+                    .child("Refund a song's minting payment back to the owner's wallet."),
             )
-            // Mock refunds summary cards
+            // Session summary cards
             .child(
                 div()
                     .h_flex()
                     .gap_4()
-                    .child(self.stat_card("Total Refunds", "$3,456.78", colors::error()))
-                    .child(self.stat_card("Pending Requests", "12", colors::text_primary()))
-                    .child(self.stat_card("Processed Today", "5", colors::success())),
+                    .child(stat_card(
+                        "Refunds This Session",
+                        refund_count.to_string(),
+                        colors::text_primary(),
+                    ))
+                    .child(stat_card(
+                        "Last Transaction",
+                        last_transaction
+                            .map(|tx| format!("{}...", &tx[..16.min(tx.len())]))
+                            .unwrap_or_else(|| "-".to_string()),
+                        colors::success(),
+                    )),
             )
-            // Mock table placeholder
+            // Song lookup
             .child(
                 div()
-                    .mt_4()
-                    .p_6()
+                    .h_flex()
+                    .gap_2()
+                    .items_center()
+                    .child(
+                        div().w(px(400.0)).child(
+                            Input::new(&self.refund_song_input).prefix(
+                                Icon::new(IconName::Search)
+                                    .size(px(16.0))
+                                    .text_color(colors::text_secondary()),
+                            ),
+                        ),
+                    )
+                    .child(
+                        Button::new("lookup-refund-song-btn")
+                            .label(if self.is_looking_up_song {
+                                "Looking up..."
+                            } else {
+                                "Look Up"
+                            })
+                            .disabled(self.is_looking_up_song || self.is_refunding)
+                            .on_click(cx.listener(|this, _, _window, cx| {
+                                this.lookup_refund_song(cx);
+                            })),
+                    ),
+            )
+            // Error message
+            .when_some(self.refund_error.clone(), |this, error_msg| {
+                this.child(
+                    div()
+                        .text_sm()
+                        .text_color(colors::error())
+                        .p_2()
+                        .rounded(px(4.0))
+                        .bg(rgba(0xff000020))
+                        .child(error_msg),
+                )
+            })
+            // Song details
+            .when_some(self.refund_lookup.clone(), |this, lookup| {
+                this.child(self.refund_song_card(&lookup, cx))
+            })
+            // Refund history
+            .child(
+                div()
+                    .text_sm()
+                    .font_weight(FontWeight::SEMIBOLD)
+                    .text_color(colors::text_secondary())
+                    .child("REFUNDS ISSUED THIS SESSION"),
+            )
+            .child(
+                div()
+                    .h_full()
+                    .w_full()
+                    .flex_1()
                     .rounded_lg()
                     .bg(colors::bg_surface())
                     .border_1()
                     .border_color(colors::border())
-                    .child(
-                        div()
-                            .text_sm()
-                            .text_color(colors::text_muted())
-                            .child("Refunds table will display here..."),
-                    ),
+                    .overflow_hidden()
+                    .child(Table::new(&self.refunds_table)),
             )
     }
 
-    /// Create a statistics card
-    /// Create a statistics card
-    fn stat_card(
-        &self,
-        label: impl Into<SharedString>,
-        value: impl Into<SharedString>,
-        value_color: Rgba,
-    ) -> impl IntoElement {
+    /// Card showing the looked-up song with its refund action
+    fn refund_song_card(&self, lookup: &RefundLookup, cx: &mut Context<Self>) -> impl IntoElement {
+        let song = &lookup.song;
+        let owner = lookup
+            .owner
+            .as_ref()
+            .map(|o| match &o.email {
+                Some(email) => format!("{} <{}>", o.display_name(), email),
+                None => o.display_name(),
+            })
+            .or_else(|| song.owner_id.clone())
+            .unwrap_or_else(|| "-".to_string());
+        let mint_cost = match (song.mint_cost, song.mint_payment_type.as_deref()) {
+            (Some(cost), Some(payment_type)) => format!("{} {}", format_amount(cost), payment_type),
+            (Some(cost), None) => format_amount(cost),
+            _ => "-".to_string(),
+        };
+        // The server pays again for every refund request, so only offer it once per session
+        let already_refunded = song.id.as_ref().is_some_and(|id| {
+            self.refunds_table
+                .read(cx)
+                .delegate()
+                .refunds
+                .iter()
+                .any(|refund| &refund.song_id == id)
+        });
+
+        let detail_row = |label: &'static str, value: String| {
+            div()
+                .h_flex()
+                .gap_4()
+                .child(
+                    div()
+                        .w(px(160.0))
+                        .text_sm()
+                        .text_color(colors::text_secondary())
+                        .child(label),
+                )
+                .child(
+                    div()
+                        .text_sm()
+                        .text_color(colors::text_primary())
+                        .child(value),
+                )
+        };
+
         div()
             .v_flex()
             .gap_2()
@@ -999,26 +2158,58 @@ impl DashboardView {
             .bg(colors::bg_surface())
             .border_1()
             .border_color(colors::border())
-            .min_w(px(180.0))
-            .child(
-                div()
-                    .text_xs()
-                    .text_color(colors::text_secondary())
-                    .child(label.into()),
-            )
             .child(
                 div()
                     .text_xl()
                     .font_weight(FontWeight::SEMIBOLD)
-                    .text_color(value_color)
-                    .child(value.into()),
+                    .text_color(colors::text_primary())
+                    .child(song.title.clone().unwrap_or_else(|| "Untitled".to_string())),
+            )
+            .child(detail_row("Song ID", song.id.clone().unwrap_or_default()))
+            .child(detail_row(
+                "ISRC",
+                song.isrc.clone().unwrap_or_else(|| "-".to_string()),
+            ))
+            .child(detail_row(
+                "Minting Status",
+                song.minting_status
+                    .clone()
+                    .unwrap_or_else(|| "-".to_string()),
+            ))
+            .child(detail_row("Owner", owner))
+            .child(detail_row("Mint Cost", mint_cost))
+            .when_some(song.minting_tx_id.clone(), |this, tx_id| {
+                this.child(detail_row("Minting Tx", tx_id))
+            })
+            .when_some(song.error_message.clone(), |this, error_message| {
+                this.child(detail_row("Error", error_message))
+            })
+            .child(
+                div().h_flex().justify_end().mt_2().child(
+                    Button::new("refund-song-btn")
+                        .label(if self.is_refunding {
+                            "Refunding..."
+                        } else if already_refunded {
+                            "Already Refunded"
+                        } else {
+                            "Refund Minting Payment"
+                        })
+                        .icon(Icon::new(IconName::Undo).size(px(16.0)))
+                        .danger()
+                        .disabled(self.is_refunding || already_refunded || song.id.is_none())
+                        .on_click(cx.listener(|this, _, _window, cx| {
+                            this.show_refund_confirmation = true;
+                            cx.notify();
+                        })),
+                ),
             )
     }
 
+    /// Create a statistics card
     /// Render the Add Earnings slide-out panel
     fn add_earnings_panel(&self, cx: &mut Context<Self>) -> impl IntoElement {
         let song_id_input = self.song_id_input.clone();
-        let usd_amount_input = self.usd_amount_input.clone();
+        let amount_input = self.amount_input.clone();
 
         // Slide-out panel from right
         div()
@@ -1062,9 +2253,19 @@ impl DashboardView {
                             })),
                     ),
             )
-            .child(div().text_sm().text_color(colors::text_secondary()).child(
-                "Create new earnings for a song. The USD amount will be converted to NEWM tokens.",
-            ))
+            .child(
+                div()
+                    .text_sm()
+                    .text_color(colors::text_secondary())
+                    .child(match self.royalty_currency {
+                        RoyaltyCurrency::Usd => {
+                            "Create new earnings for a song. The USD amount will be converted to NEWM tokens."
+                        }
+                        RoyaltyCurrency::Newm => {
+                            "Create new earnings for a song. The NEWM amount is split among stream token holders as-is."
+                        }
+                    }),
+            )
             // Error message
             .when_some(self.form_error.clone(), |this, error_msg| {
                 this.child(
@@ -1096,7 +2297,40 @@ impl DashboardView {
                             .text_color(colors::text_primary()),
                     ),
             )
-            // USD Amount field
+            // Currency selector
+            .child(
+                div()
+                    .v_flex()
+                    .gap_1()
+                    .child(
+                        div()
+                            .text_sm()
+                            .font_weight(FontWeight::MEDIUM)
+                            .text_color(colors::text_primary())
+                            .child("Paid Out In"),
+                    )
+                    .child(
+                        RadioGroup::horizontal("royalty-currency")
+                            .selected_index(Some(match self.royalty_currency {
+                                RoyaltyCurrency::Usd => 0,
+                                RoyaltyCurrency::Newm => 1,
+                            }))
+                            .disabled(self.is_submitting)
+                            .on_click(cx.listener(|this, index: &usize, window, cx| {
+                                this.royalty_currency = RoyaltyCurrency::from_index(*index);
+                                let placeholder = amount_placeholder(this.royalty_currency);
+                                this.amount_input.update(cx, |state, cx| {
+                                    state.set_placeholder(placeholder, window, cx);
+                                });
+                                cx.notify();
+                            }))
+                            .children(vec![
+                                Radio::new("currency-usd").label("USD"),
+                                Radio::new("currency-newm").label("NEWM"),
+                            ]),
+                    ),
+            )
+            // Amount field
             .child(
                 div()
                     .v_flex()
@@ -1106,10 +2340,10 @@ impl DashboardView {
                             .text_sm()
                             .font_weight(FontWeight::MEDIUM)
                             .text_color(colors::text_primary())
-                            .child("Amount (USD)"),
+                            .child(format!("Amount ({})", self.royalty_currency)),
                     )
                     .child(
-                        Input::new(&usd_amount_input)
+                        Input::new(&amount_input)
                             .bg(colors::bg_surface())
                             .border_color(colors::border())
                             .text_color(colors::text_primary()),
@@ -1151,7 +2385,8 @@ impl DashboardView {
     /// Submit the Add Earnings form
     fn submit_add_earnings(&mut self, cx: &mut Context<Self>) {
         let song_id = self.song_id_input.read(cx).value().to_string();
-        let usd_str = self.usd_amount_input.read(cx).value().to_string();
+        let amount_str = self.amount_input.read(cx).value().to_string();
+        let currency = self.royalty_currency;
 
         // Clear previous error
         self.form_error = None;
@@ -1163,15 +2398,15 @@ impl DashboardView {
             return;
         }
 
-        if usd_str.trim().is_empty() {
-            self.form_error = Some("Please enter a USD amount".to_string());
+        if amount_str.trim().is_empty() {
+            self.form_error = Some(format!("Please enter a {} amount", currency));
             cx.notify();
             return;
         }
 
-        // Convert USD to 6-decimal amount
-        let usd_amount = match usd_to_amount(&usd_str) {
-            Ok(amount) => amount,
+        // Convert to 6-decimal amount
+        let royalty = match parse_amount(&amount_str) {
+            Ok(amount) => AddSongRoyaltyRequest::new(currency, amount),
             Err(e) => {
                 self.form_error = Some(format!("Invalid amount: {}", e));
                 cx.notify();
@@ -1193,7 +2428,7 @@ impl DashboardView {
             let client = EarningsClient::new();
 
             let result =
-                Compat::new(async { client.add_earnings(&session, &song_id, usd_amount).await })
+                Compat::new(async { client.add_earnings(&session, &song_id, &royalty).await })
                     .await;
 
             cx.update(|cx| {
@@ -1227,36 +2462,487 @@ impl DashboardView {
         .detach();
     }
 
-    /// Handle CSV upload button click
-    fn upload_csv(&mut self, cx: &mut Context<Self>) {
-        let Some(session) = self.session.clone() else {
-            toast::show_error_async(cx, "No active session".to_string());
-            return;
-        };
+    /// Labelled form field for the slide-out panels
+    fn form_field(label: &str, input: &Entity<InputState>) -> impl IntoElement {
+        div()
+            .v_flex()
+            .gap_1()
+            .child(
+                div()
+                    .text_sm()
+                    .font_weight(FontWeight::MEDIUM)
+                    .text_color(colors::text_primary())
+                    .child(label.to_string()),
+            )
+            .child(
+                Input::new(input)
+                    .bg(colors::bg_surface())
+                    .border_color(colors::border())
+                    .text_color(colors::text_primary()),
+            )
+    }
 
-        self.is_importing_csv = true;
-        self.csv_import_progress = None;
-        cx.notify();
+    /// Add Reward slide-out panel
+    fn add_reward_panel(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let is_busy = self.is_submitting_reward || self.is_importing_rewards;
+
+        div()
+            .absolute()
+            .top_0()
+            .right_0()
+            .h_full()
+            .w(px(400.0))
+            .bg(colors::bg_surface())
+            .border_l_1()
+            .border_color(colors::border())
+            .shadow_lg()
+            .v_flex()
+            .p_6()
+            .gap_4()
+            // Header
+            .child(
+                div()
+                    .h_flex()
+                    .items_center()
+                    .justify_between()
+                    .child(
+                        div()
+                            .text_xl()
+                            .font_weight(FontWeight::BOLD)
+                            .text_color(colors::text_primary())
+                            .child("Add Reward"),
+                    )
+                    .child(
+                        div()
+                            .id("close-add-reward")
+                            .cursor_pointer()
+                            .child(
+                                Icon::new(IconName::Close)
+                                    .size(px(20.0))
+                                    .text_color(colors::text_secondary()),
+                            )
+                            .on_click(cx.listener(|this, _, _window, cx| {
+                                this.show_add_reward = false;
+                                cx.notify();
+                            })),
+                    ),
+            )
+            .child(div().text_sm().text_color(colors::text_secondary()).child(
+                "Create a NEWM earning for a wallet that is not tied to a song, \
+                         such as a learn-to-earn or marketing reward.",
+            ))
+            // Error message
+            .when_some(self.reward_form_error.clone(), |this, error_msg| {
+                this.child(
+                    div()
+                        .text_sm()
+                        .text_color(colors::error())
+                        .p_2()
+                        .rounded(px(4.0))
+                        .bg(rgba(0xff000020))
+                        .child(error_msg),
+                )
+            })
+            .child(Self::form_field("Stake Address", &self.reward_stake_input))
+            .child(Self::form_field("Amount (NEWM)", &self.reward_amount_input))
+            .child(Self::form_field("Memo", &self.reward_memo_input))
+            .child(
+                div()
+                    .h_flex()
+                    .gap_3()
+                    .child(
+                        div()
+                            .flex_1()
+                            .child(Self::form_field("Start Date", &self.reward_start_input)),
+                    )
+                    .child(
+                        div()
+                            .flex_1()
+                            .child(Self::form_field("End Date", &self.reward_end_input)),
+                    ),
+            )
+            // Spacer to push buttons to bottom
+            .child(div().flex_1())
+            // Batch import
+            .child(
+                div()
+                    .text_xs()
+                    .text_color(colors::text_muted())
+                    .child("CSV columns: stake_address, amount, memo, start, end"),
+            )
+            .child(
+                Button::new("upload-rewards-csv-btn")
+                    .child(
+                        img(Arc::new(Image::from_bytes(
+                            ImageFormat::Svg,
+                            UPLOAD_SVG.to_vec(),
+                        )))
+                        .size(px(16.0)),
+                    )
+                    .label(if self.is_importing_rewards {
+                        "Importing..."
+                    } else {
+                        "Upload Rewards CSV"
+                    })
+                    .disabled(is_busy)
+                    .on_click(cx.listener(|this, _, _window, cx| {
+                        this.upload_rewards_csv(cx);
+                    })),
+            )
+            // Buttons
+            .child(
+                div()
+                    .h_flex()
+                    .gap_3()
+                    .justify_end()
+                    .child(
+                        Button::new("cancel-add-reward")
+                            .label("Cancel")
+                            .disabled(is_busy)
+                            .on_click(cx.listener(|this, _, _window, cx| {
+                                this.show_add_reward = false;
+                                cx.notify();
+                            })),
+                    )
+                    .child(
+                        Button::new("submit-add-reward")
+                            .primary()
+                            .label(if self.is_submitting_reward {
+                                "Submitting..."
+                            } else {
+                                "Submit"
+                            })
+                            .disabled(is_busy)
+                            .on_click(cx.listener(|this, _, window, cx| {
+                                this.submit_add_reward(window, cx);
+                            })),
+                    ),
+            )
+    }
+
+    /// Build a reward earning from the Add Reward form
+    fn reward_from_form(&self, network: Network, cx: &App) -> Result<Earning, String> {
+        let value = |input: &Entity<InputState>| input.read(cx).value().to_string();
+
+        let address = value(&self.reward_stake_input);
+        let amount_str = value(&self.reward_amount_input);
+        let memo = value(&self.reward_memo_input);
+
+        if address.trim().is_empty() {
+            return Err("Please enter a stake address".to_string());
+        }
+        // Base addresses are accepted and reduced to their stake address
+        let stake_address = as_stake_address(&address, network)
+            .map_err(|e| format!("Invalid stake address: {}", e))?
+            .to_string();
+
+        if amount_str.trim().is_empty() {
+            return Err("Please enter a NEWM amount".to_string());
+        }
+        let amount = parse_amount(&amount_str).map_err(|e| format!("Invalid amount: {}", e))?;
+        let start = parse_reward_date(&value(&self.reward_start_input))?;
+        let end = parse_reward_date(&value(&self.reward_end_input))?;
+
+        let earning = Earning::reward(&stake_address, amount, &memo, start, end);
+        earning.validate_new(network)?;
+        Ok(earning)
+    }
+
+    /// Submit the Add Reward form
+    fn submit_add_reward(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.reward_form_error = None;
+
+        let Some(session) = self.session.clone() else {
+            self.reward_form_error = Some("No active session".to_string());
+            cx.notify();
+            return;
+        };
+
+        let earning = match self.reward_from_form(session.environment().network(), cx) {
+            Ok(earning) => earning,
+            Err(e) => {
+                self.reward_form_error = Some(e);
+                cx.notify();
+                return;
+            }
+        };
+
+        self.is_submitting_reward = true;
+        cx.notify();
+
+        let window_handle = window.window_handle();
+        cx.spawn(async move |this, cx| {
+            let client = EarningsClient::new();
+            let result = Compat::new(async {
+                client
+                    .add_rewards(&session, std::slice::from_ref(&earning))
+                    .await
+            })
+            .await;
+
+            cx.update(|cx| {
+                this.update(cx, |view, cx| {
+                    view.is_submitting_reward = false;
+                    match result {
+                        Ok(()) => {
+                            tracing::info!("Reward added for {}", earning.stake_address);
+                            view.show_add_reward = false;
+                            window_handle
+                                .update(cx, |_, window, cx| {
+                                    for input in [
+                                        &view.reward_stake_input,
+                                        &view.reward_amount_input,
+                                        &view.reward_memo_input,
+                                        &view.reward_start_input,
+                                        &view.reward_end_input,
+                                    ] {
+                                        input.update(cx, |state, cx| {
+                                            state.set_value("", window, cx);
+                                        });
+                                    }
+                                })
+                                .ok();
+                            view.fetch_earnings(cx);
+                        }
+                        Err(EarningsError::SessionExpired(msg)) => {
+                            cx.emit(SessionExpiredEvent { message: msg });
+                        }
+                        Err(e) => {
+                            tracing::warn!("Failed to add reward: {}", e);
+                            view.reward_form_error = Some(e.to_string());
+                        }
+                    }
+                    cx.notify();
+                })
+            })
+        })
+        .detach();
+    }
+
+    /// Parse and validate a rewards CSV, then preview it for confirmation.
+    ///
+    /// Nothing is uploaded unless every row is valid and the admin confirms
+    /// the preview; per-row problems are written to the results file.
+    fn upload_rewards_csv(&mut self, cx: &mut Context<Self>) {
+        let Some(session) = self.session.clone() else {
+            toast::show_error_async(cx, "No active session".to_string());
+            return;
+        };
+
+        let network = session.environment().network();
+        self.is_importing_rewards = true;
+        self.reward_form_error = None;
+        cx.notify();
+
+        cx.spawn(async move |this, cx| {
+            let finish = |cx: &mut AsyncApp| {
+                cx.update(|cx| {
+                    this.update(cx, |view, cx| {
+                        view.is_importing_rewards = false;
+                        cx.notify();
+                    })
+                })
+                .ok();
+            };
 
-        // Spawn async file dialog and processing
-        cx.spawn(async move |this, cx| {
-            // Open file dialog
             let file_handle = rfd::AsyncFileDialog::new()
                 .add_filter("CSV Files", &["csv"])
-                .set_title("Select Earnings CSV")
+                .set_title("Select Rewards CSV")
                 .pick_file()
                 .await;
 
             let Some(file_handle) = file_handle else {
-                // User cancelled
+                finish(cx);
+                return;
+            };
+
+            let file_path = file_handle.path().to_path_buf();
+            tracing::info!("Selected rewards CSV file: {:?}", file_path);
+
+            let rows = match parse_rewards_csv(&file_path) {
+                Ok(rows) => rows,
+                Err(e) => {
+                    finish(cx);
+                    cx.update(|cx| {
+                        toast::show_error_async(cx, format!("Failed to parse CSV: {}", e));
+                    })
+                    .ok();
+                    return;
+                }
+            };
+
+            let converted: Vec<Result<Earning, String>> =
+                rows.iter().map(|row| row.to_earning(network)).collect();
+            let invalid = converted.iter().filter(|r| r.is_err()).count();
+            finish(cx);
+
+            if invalid == 0 {
+                let earnings = converted.into_iter().flatten().collect();
+                cx.update(|cx| {
+                    this.update(cx, |view, cx| {
+                        view.rewards_preview = Some(RewardsPreview {
+                            file_path,
+                            rows,
+                            earnings,
+                        });
+                        cx.notify();
+                    })
+                })
+                .ok();
+                return;
+            }
+
+            let total = rows.len();
+            let results: Vec<RewardCsvResult> = rows
+                .into_iter()
+                .zip(&converted)
+                .map(|(row, converted)| {
+                    let result = match converted {
+                        Err(e) => format!("Invalid: {}", e),
+                        Ok(_) => "Skipped: other rows are invalid".to_string(),
+                    };
+                    RewardCsvResult { row, result }
+                })
+                .collect();
+            let output_name = reward_results_file(&file_path, &results);
+
+            cx.update(|cx| {
+                toast::show_warning_async(
+                    cx,
+                    format!(
+                        "{}/{} rows are invalid, nothing was uploaded. Results saved to {}",
+                        invalid, total, output_name
+                    ),
+                );
+            })
+            .ok();
+        })
+        .detach();
+    }
+
+    /// Create the previewed rewards in a single batch
+    fn confirm_rewards_upload(&mut self, cx: &mut Context<Self>) {
+        let Some(preview) = self.rewards_preview.take() else {
+            return;
+        };
+        let Some(session) = self.session.clone() else {
+            toast::show_error_async(cx, "No active session".to_string());
+            return;
+        };
+
+        self.is_importing_rewards = true;
+        cx.notify();
+
+        cx.spawn(async move |this, cx| {
+            let RewardsPreview {
+                file_path,
+                rows,
+                earnings,
+            } = preview;
+            let total = rows.len();
+
+            let client = EarningsClient::new();
+            let batch_result =
+                match Compat::new(async { client.add_rewards(&session, &earnings).await }).await {
+                    Err(EarningsError::SessionExpired(msg)) => {
+                        cx.update(|cx| {
+                            this.update(cx, |view, cx| {
+                                view.is_importing_rewards = false;
+                                cx.emit(SessionExpiredEvent { message: msg });
+                                cx.notify();
+                            })
+                        })
+                        .ok();
+                        return;
+                    }
+                    result => result,
+                };
+
+            let results: Vec<RewardCsvResult> = rows
+                .into_iter()
+                .map(|row| {
+                    let result = match &batch_result {
+                        Ok(()) => "Success".to_string(),
+                        Err(e) => format!("Error: {}", e),
+                    };
+                    RewardCsvResult { row, result }
+                })
+                .collect();
+            let output_name = reward_results_file(&file_path, &results);
+
+            cx.update(|cx| {
+                this.update(cx, |view, cx| {
+                    view.is_importing_rewards = false;
+                    if batch_result.is_ok() {
+                        view.show_add_reward = false;
+                        view.fetch_earnings(cx);
+                    }
+                    cx.notify();
+                })
+                .ok();
+
+                match batch_result {
+                    Ok(()) => toast::show_success_async(
+                        cx,
+                        format!(
+                            "Created {} rewards. Results saved to {}",
+                            total, output_name
+                        ),
+                    ),
+                    Err(e) => toast::show_error_async(
+                        cx,
+                        format!(
+                            "Reward upload failed: {}. Results saved to {}",
+                            e, output_name
+                        ),
+                    ),
+                }
+            })
+            .ok();
+        })
+        .detach();
+    }
+
+    /// Handle CSV upload button click.
+    ///
+    /// Nothing is sent until the file has passed the preflight: every row is
+    /// checked and every song looked up, then the admin confirms the import
+    /// from the report.
+    fn upload_csv(&mut self, cx: &mut Context<Self>) {
+        let Some(session) = self.session.clone() else {
+            toast::show_error_async(cx, "No active session".to_string());
+            return;
+        };
+
+        self.is_importing_csv = true;
+        self.csv_checking = true;
+        self.csv_import_progress = None;
+        cx.notify();
+
+        // Spawn async file dialog and preflight
+        cx.spawn(async move |this, cx| {
+            let finish = |cx: &mut AsyncApp| {
                 cx.update(|cx| {
                     this.update(cx, |view, cx| {
                         view.is_importing_csv = false;
+                        view.csv_checking = false;
                         view.csv_import_progress = None;
                         cx.notify();
                     })
                 })
                 .ok();
+            };
+
+            // Open file dialog
+            let file_handle = rfd::AsyncFileDialog::new()
+                .add_filter("CSV Files", &["csv"])
+                .set_title("Select Earnings CSV")
+                .pick_file()
+                .await;
+
+            let Some(file_handle) = file_handle else {
+                // User cancelled
+                finish(cx);
                 return;
             };
 
@@ -1267,27 +2953,96 @@ impl DashboardView {
             let rows = match parse_csv(&file_path) {
                 Ok(rows) => rows,
                 Err(e) => {
-                    let error_msg = e.to_string();
-                    cx.update(|cx| {
-                        this.update(cx, |view, cx| {
-                            view.is_importing_csv = false;
-                            view.csv_import_progress = None;
-                            cx.notify();
-                        })
-                    })
-                    .ok();
+                    finish(cx);
                     cx.update(|cx| {
-                        toast::show_error_async(cx, format!("Failed to parse CSV: {}", error_msg));
+                        toast::show_error_async(cx, format!("Failed to parse CSV: {}", e));
                     })
                     .ok();
                     return;
                 }
             };
 
+            // Check every row, then look each song up once
+            let mut report = PreflightReport::check(rows);
+            let lookups = report.pending_lookups();
+            for (i, identifier) in lookups.iter().enumerate() {
+                cx.update(|cx| {
+                    this.update(cx, |view, cx| {
+                        view.csv_import_progress = Some((i, lookups.len()));
+                        cx.notify();
+                    })
+                })
+                .ok();
+
+                match Compat::new(async { lookup_song(&session, identifier).await }).await {
+                    Ok(lookup) => report.record_lookup(identifier, &lookup),
+                    Err(SongsError::SessionExpired(msg)) => {
+                        finish(cx);
+                        cx.update(|cx| {
+                            this.update(cx, |_view, cx| {
+                                cx.emit(SessionExpiredEvent { message: msg });
+                            })
+                        })
+                        .ok();
+                        return;
+                    }
+                    Err(e) => {
+                        finish(cx);
+                        cx.update(|cx| {
+                            toast::show_error_async(
+                                cx,
+                                format!("Could not check the songs in the CSV: {}", e),
+                            );
+                        })
+                        .ok();
+                        return;
+                    }
+                }
+            }
+
+            tracing::info!("CSV preflight: {}", report.summary());
+            finish(cx);
+            cx.update(|cx| {
+                this.update(cx, |view, cx| {
+                    view.csv_preflight = Some(CsvPreflight { file_path, report });
+                    cx.notify();
+                })
+            })
+            .ok();
+        })
+        .detach();
+    }
+
+    /// Import the rows of a CSV that passed the preflight
+    fn confirm_csv_import(&mut self, cx: &mut Context<Self>) {
+        let Some(preflight) = self.csv_preflight.take() else {
+            return;
+        };
+        if !preflight.report.is_ready() {
+            self.csv_preflight = Some(preflight);
+            return;
+        }
+        let rows = preflight.report.rows.into_iter().map(|c| c.row).collect();
+        self.import_csv(preflight.file_path, rows, cx);
+    }
+
+    /// Book royalties row by row and write the results file next to the CSV
+    fn import_csv(&mut self, file_path: PathBuf, rows: Vec<CsvRow>, cx: &mut Context<Self>) {
+        let Some(session) = self.session.clone() else {
+            toast::show_error_async(cx, "No active session".to_string());
+            return;
+        };
+
+        self.is_importing_csv = true;
+        self.csv_import_progress = None;
+        cx.notify();
+
+        cx.spawn(async move |this, cx| {
             let total = rows.len();
             let mut results: Vec<CsvResult> = Vec::with_capacity(total);
             let mut succeeded = 0usize;
             let mut failed = 0usize;
+            let mut unconfirmed = 0usize;
 
             // Update progress
             cx.update(|cx| {
@@ -1302,13 +3057,14 @@ impl DashboardView {
 
             // Process each row sequentially
             for (i, row) in rows.into_iter().enumerate() {
-                // Convert USD to 6-decimal amount
-                let result_msg = match usd_to_amount(&row.amount_usd) {
+                // Convert to 6-decimal amount in the row's currency
+                let result_msg = match parse_amount(&row.amount) {
                     Ok(amount) => {
+                        let royalty = AddSongRoyaltyRequest::new(row.currency, amount);
                         // Call API
                         match Compat::new(async {
                             client
-                                .add_earnings(&session, &row.song_id_or_isrc, amount)
+                                .add_earnings(&session, &row.song_id_or_isrc, &royalty)
                                 .await
                         })
                         .await
@@ -1332,6 +3088,11 @@ impl DashboardView {
                                 .ok();
                                 return;
                             }
+                            Err(EarningsError::Ambiguous(msg)) => {
+                                // May have been booked; re-running the row could pay twice
+                                unconfirmed += 1;
+                                format!("Unconfirmed: {} - check the song's earnings", msg)
+                            }
                             Err(e) => {
                                 failed += 1;
                                 format!("Error: {}", e)
@@ -1388,6 +3149,7 @@ impl DashboardView {
                 total,
                 succeeded,
                 failed,
+                unconfirmed,
                 output_path: output_path.clone(),
             };
 
@@ -1403,7 +3165,26 @@ impl DashboardView {
             .ok();
 
             // Show toast
-            if failed > 0 {
+            if unconfirmed > 0 {
+                cx.update(|cx| {
+                    toast::show_error_async(
+                        cx,
+                        format!(
+                            "Imported {}/{} earnings; {} could not be confirmed and were not \
+                             retried. Check those songs before importing them again. \
+                             Results saved to {}",
+                            succeeded,
+                            total,
+                            unconfirmed,
+                            output_path
+                                .file_name()
+                                .unwrap_or_default()
+                                .to_string_lossy()
+                        ),
+                    );
+                })
+                .ok();
+            } else if failed > 0 {
                 cx.update(|cx| {
                     toast::show_warning_async(
                         cx,
@@ -1531,6 +3312,148 @@ impl DashboardView {
             .detach();
         }
     }
+
+    /// Look up the song entered in the Refunds panel along with its owner
+    fn lookup_refund_song(&mut self, cx: &mut Context<Self>) {
+        let song_id_or_isrc = self.refund_song_input.read(cx).value().trim().to_string();
+
+        self.refund_error = None;
+
+        if song_id_or_isrc.is_empty() {
+            self.refund_error = Some("Please enter a Song ID or ISRC".to_string());
+            cx.notify();
+            return;
+        }
+
+        let Some(session) = self.session.clone() else {
+            self.refund_error = Some("No active session".to_string());
+            cx.notify();
+            return;
+        };
+
+        self.is_looking_up_song = true;
+        self.refund_lookup = None;
+        cx.notify();
+
+        cx.spawn(async move |this, cx| {
+            let client = SongsClient::new();
+            let result = Compat::new(async {
+                let song_id = client.resolve_song_id(&session, &song_id_or_isrc).await?;
+                let song = client.get_song(&session, &song_id).await?;
+                // Owner details are informational only, so a failed lookup is not fatal
+                let owner = match song.owner_id.as_deref() {
+                    Some(owner_id) => match client.get_owner(&session, owner_id).await {
+                        Ok(owner) => Some(owner),
+                        Err(SongsError::SessionExpired(msg)) => {
+                            return Err(SongsError::SessionExpired(msg));
+                        }
+                        Err(e) => {
+                            tracing::warn!("Failed to fetch song owner {}: {}", owner_id, e);
+                            None
+                        }
+                    },
+                    None => None,
+                };
+                Ok(RefundLookup { song, owner })
+            })
+            .await;
+
+            cx.update(|cx| {
+                this.update(cx, |view, cx| {
+                    view.is_looking_up_song = false;
+                    match result {
+                        Ok(lookup) => {
+                            view.refund_lookup = Some(lookup);
+                        }
+                        Err(SongsError::SessionExpired(msg)) => {
+                            cx.emit(SessionExpiredEvent { message: msg });
+                        }
+                        Err(e) => {
+                            tracing::warn!("Failed to look up song: {}", e);
+                            view.refund_error = Some(e.to_string());
+                        }
+                    }
+                    cx.notify();
+                })
+            })
+        })
+        .detach();
+    }
+
+    /// Refund the minting payment of the looked-up song
+    fn refund_song(&mut self, cx: &mut Context<Self>) {
+        self.show_refund_confirmation = false;
+        if self.is_refunding {
+            return;
+        }
+
+        let Some(lookup) = self.refund_lookup.clone() else {
+            return;
+        };
+        let Some(song_id) = lookup.song.id.clone() else {
+            return;
+        };
+        let Some(session) = self.session.clone() else {
+            self.refund_error = Some("No active session".to_string());
+            cx.notify();
+            return;
+        };
+
+        self.is_refunding = true;
+        self.refund_error = None;
+        cx.notify();
+
+        cx.spawn(async move |this, cx| {
+            let client = SongsClient::new();
+            let result = Compat::new(async { client.refund(&session, &song_id).await }).await;
+
+            cx.update(|cx| {
+                this.update(cx, |view, cx| {
+                    view.is_refunding = false;
+                    match result {
+                        Ok(refund) => {
+                            // Clear the card so the same payment cannot be refunded twice
+                            view.refund_lookup = None;
+                            toast::show_success_async(
+                                cx,
+                                format!("Refund submitted: {}", refund.transaction_id),
+                            );
+                            let record = RefundRecord {
+                                song_id,
+                                title: lookup.song.title.clone().unwrap_or_default(),
+                                transaction_id: refund.transaction_id,
+                                message: refund.message,
+                                refunded_at: chrono::Local::now()
+                                    .format("%Y-%m-%d %H:%M:%S")
+                                    .to_string(),
+                            };
+                            view.refunds_table.update(cx, |table, cx| {
+                                table.delegate_mut().push(record);
+                                cx.notify();
+                            });
+                        }
+                        Err(SongsError::SessionExpired(msg)) => {
+                            cx.emit(SessionExpiredEvent { message: msg });
+                        }
+                        Err(SongsError::Ambiguous(msg)) => {
+                            tracing::error!("Refund of song {} unconfirmed: {}", song_id, msg);
+                            view.refund_lookup = None;
+                            view.refund_error = Some(format!(
+                                "Refund outcome unknown, check the payment before retrying: {}",
+                                msg
+                            ));
+                        }
+                        Err(e) => {
+                            tracing::error!("Failed to refund song {}: {}", song_id, e);
+                            view.refund_error = Some(format!("Refund failed: {}", e));
+                        }
+                    }
+                    cx.notify();
+                })
+            })
+        })
+        .detach();
+    }
 }
 
 impl Render for DashboardView {
@@ -1565,7 +3488,39 @@ impl Render for DashboardView {
                     )
                     // Menu items
                     .child(self.menu_button(MenuItem::Earnings, cx))
-                    .child(self.menu_button(MenuItem::Refunds, cx)),
+                    .child(self.menu_button(MenuItem::Refunds, cx))
+                    .child(self.menu_button(MenuItem::Reprocess, cx))
+                    .child(self.menu_button(MenuItem::Cardano, cx))
+                    // Current environment, pinned to the bottom
+                    .child(div().flex_1())
+                    .when_some(self.session.as_ref(), |this, session| {
+                        let environment = session.environment();
+                        this.child(
+                            div()
+                                .h_flex()
+                                .gap_2()
+                                .items_center()
+                                .text_sm()
+                                .text_color(colors::text_secondary())
+                                .child(
+                                    div()
+                                        .size(px(8.0))
+                                        .rounded_full()
+                                        .bg(environment.color()),
+                                )
+                                .child(environment.display_name().to_string()),
+                        )
+                    })
+                    .child(
+                        Button::new("logout-btn")
+                            .label("Log Out")
+                            .icon(Icon::new(IconName::ArrowLeft).size(px(16.0)))
+                            .ghost()
+                            .w_full()
+                            .on_click(cx.listener(|_this, _, _window, cx| {
+                                cx.emit(LogoutEvent);
+                            })),
+                    ),
             )
             // Work Area
             .child(
@@ -1573,12 +3528,28 @@ impl Render for DashboardView {
                     .flex_1()
                     .h_full()
                     .p_8()
-                    .child(self.work_area_content(cx)),
+                    .v_flex()
+                    .children(self.session_expiry_banner(cx))
+                    .child(
+                        div()
+                            .flex_1()
+                            .min_h_0()
+                            .w_full()
+                            .child(self.work_area_content(cx)),
+                    ),
             )
             // Add Earnings slide-out panel (conditional)
             .when(self.show_add_earnings, |this| {
                 this.child(self.add_earnings_panel(cx))
             })
+            // Add Reward slide-out panel (conditional)
+            .when(self.show_add_reward, |this| {
+                this.child(self.add_reward_panel(cx))
+            })
+            // CSV import preview modal
+            .children(self.csv_preflight_dialog(cx))
+            // Rewards CSV preview modal
+            .children(self.rewards_preview_dialog(cx))
             // Delete confirmation modal
             .when(self.show_delete_confirmation, |this| {
                 let selected_count = self.table.read(cx).delegate().selected_count();
@@ -1643,5 +3614,81 @@ impl Render for DashboardView {
                         )
                 )
             })
+            // Refund confirmation modal
+            .when_some(
+                self.refund_lookup
+                    .clone()
+                    .filter(|_| self.show_refund_confirmation),
+                |this, lookup| {
+                    this.child(
+                        div()
+                            .absolute()
+                            .inset_0()
+                            .flex()
+                            .items_center()
+                            .justify_center()
+                            .bg(gpui::Rgba { r: 0.0, g: 0.0, b: 0.0, a: 0.5 })
+                            .child(
+                                div()
+                                    .v_flex()
+                                    .gap_4()
+                                    .p_6()
+                                    .rounded_lg()
+                                    .bg(colors::bg_surface())
+                                    .border_1()
+                                    .border_color(colors::border())
+                                    .shadow_lg()
+                                    .min_w(px(400.0))
+                                    .max_w(px(600.0))
+                                    .child(
+                                        div()
+                                            .text_xl()
+                                            .font_weight(gpui::FontWeight::BOLD)
+                                            .text_color(colors::text_primary())
+                                            .child("Confirm Refund"),
+                                    )
+                                    .child(div().text_color(colors::text_secondary()).child(
+                                        format!(
+                                            "Refund the minting payment for \"{}\" ({}) to the owner's wallet on {}? \
+                                             This submits an on-chain transaction and cannot be undone.",
+                                            lookup.song.title.clone().unwrap_or_default(),
+                                            lookup.song.id.clone().unwrap_or_default(),
+                                            self.session
+                                                .as_ref()
+                                                .map(|s| s.environment().display_name())
+                                                .unwrap_or_default(),
+                                        ),
+                                    ))
+                                    .child(
+                                        div()
+                                            .h_flex()
+                                            .gap_3()
+                                            .justify_end()
+                                            .child(
+                                                Button::new("cancel-refund-btn")
+                                                    .label("Cancel")
+                                                    .ghost()
+                                                    .on_click(cx.listener(
+                                                        |this, _, _window, cx| {
+                                                            this.show_refund_confirmation = false;
+                                                            cx.notify();
+                                                        },
+                                                    )),
+                                            )
+                                            .child(
+                                                Button::new("confirm-refund-btn")
+                                                    .label("Refund")
+                                                    .danger()
+                                                    .on_click(cx.listener(
+                                                        |this, _, _window, cx| {
+                                                            this.refund_song(cx);
+                                                        },
+                                                    )),
+                                            ),
+                                    ),
+                            ),
+                    )
+                },
+            )
     }
 }
@@ -0,0 +1,374 @@
+//! Encryption Parameters View
+//!
+//! Guides an admin through the one-time upload of the salt and password the
+//! server uses to encrypt the keys it holds. The values are only kept in the
+//! (masked) input fields and are cleared once the server accepts them.
+
+use async_compat::Compat;
+use gpui::prelude::FluentBuilder;
+use gpui::*;
+use gpui_component::button::{Button, ButtonVariants};
+use gpui_component::input::*;
+use gpui_component::*;
+
+use crate::cardano_admin::{CardanoAdminClient, CardanoAdminError, EncryptionParams};
+use crate::colors;
+use crate::session::{Session, SessionExpiredEvent};
+use crate::toast;
+use crate::views::cardano::{environment_label, section_title};
+
+/// Steps of the wizard
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+enum EncryptionStep {
+    /// Enter and check the salt and password
+    #[default]
+    Enter,
+    /// Type the environment name to confirm
+    Confirm,
+    /// The server accepted the parameters
+    Done,
+}
+
+/// Whether the typed confirmation names the session's environment
+fn confirmation_matches(typed: &str, environment_name: &str) -> bool {
+    typed.trim().eq_ignore_ascii_case(environment_name)
+}
+
+pub struct EncryptionView {
+    session: Option<Session>,
+    step: EncryptionStep,
+    salt_input: Entity<InputState>,
+    password_input: Entity<InputState>,
+    confirm_input: Entity<InputState>,
+    is_submitting: bool,
+    form_error: Option<String>,
+    /// Clear the fields on the next render (needs a window)
+    clear_pending: bool,
+}
+
+impl EncryptionView {
+    pub fn new(window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let salt_input = cx.new(|cx| {
+            InputState::new(window, cx)
+                .masked(true)
+                .placeholder("Random hex, e.g. from `openssl rand -hex 16`")
+        });
+        let password_input = cx.new(|cx| {
+            InputState::new(window, cx)
+                .masked(true)
+                .placeholder("More than 30 characters")
+        });
+        let confirm_input =
+            cx.new(|cx| InputState::new(window, cx).placeholder("Environment name"));
+
+        for input in [&salt_input, &password_input, &confirm_input] {
+            cx.observe(input, |_this: &mut Self, _, cx| cx.notify())
+                .detach();
+        }
+
+        Self {
+            session: None,
+            step: EncryptionStep::default(),
+            salt_input,
+            password_input,
+            confirm_input,
+            is_submitting: false,
+            form_error: None,
+            clear_pending: false,
+        }
+    }
+
+    /// Set the session (called from CardanoView after login)
+    pub fn set_session(&mut self, session: Option<Session>, cx: &mut Context<Self>) {
+        self.session = session;
+        self.step = EncryptionStep::Enter;
+        self.form_error = None;
+        self.clear_pending = true;
+        cx.notify();
+    }
+
+    /// Clear every field
+    fn clear_inputs(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        for input in [&self.salt_input, &self.password_input, &self.confirm_input] {
+            input.update(cx, |state, cx| {
+                state.set_value("", window, cx);
+            });
+        }
+        self.clear_pending = false;
+    }
+
+    /// Validated parameters from the input fields
+    fn params(&self, cx: &App) -> Result<EncryptionParams, String> {
+        EncryptionParams::new(
+            &self.salt_input.read(cx).value(),
+            &self.password_input.read(cx).value(),
+        )
+    }
+
+    /// Move from entering values to the confirmation step
+    fn continue_to_confirm(&mut self, cx: &mut Context<Self>) {
+        match self.params(cx) {
+            Ok(_) => {
+                self.form_error = None;
+                self.step = EncryptionStep::Confirm;
+            }
+            Err(e) => self.form_error = Some(e),
+        }
+        cx.notify();
+    }
+
+    /// Submit the parameters once the environment name has been typed
+    fn submit(&mut self, cx: &mut Context<Self>) {
+        self.form_error = None;
+
+        let Some(session) = self.session.clone() else {
+            self.form_error = Some("No active session".to_string());
+            cx.notify();
+            return;
+        };
+
+        let environment_name = session.environment().display_name().to_string();
+        if !confirmation_matches(&self.confirm_input.read(cx).value(), &environment_name) {
+            self.form_error = Some(format!(
+                "Type '{}' to confirm the target environment",
+                environment_name
+            ));
+            cx.notify();
+            return;
+        }
+
+        let params = match self.params(cx) {
+            Ok(params) => params,
+            Err(e) => {
+                self.form_error = Some(e);
+                self.step = EncryptionStep::Enter;
+                cx.notify();
+                return;
+            }
+        };
+
+        self.is_submitting = true;
+        cx.notify();
+
+        cx.spawn(async move |this, cx| {
+            let client = CardanoAdminClient::new();
+            let result = Compat::new(client.save_encryption_params(&session, &params)).await;
+            drop(params);
+
+            cx.update(|cx| {
+                this.update(cx, |view, cx| {
+                    view.is_submitting = false;
+                    match result {
+                        Ok(()) => {
+                            view.step = EncryptionStep::Done;
+                            view.clear_pending = true;
+                            toast::show_success_async(
+                                cx,
+                                format!("Encryption parameters saved to {}", environment_name),
+                            );
+                        }
+                        Err(CardanoAdminError::SessionExpired(msg)) => {
+                            cx.emit(SessionExpiredEvent { message: msg });
+                        }
+                        Err(e) => {
+                            view.form_error = Some(e.to_string());
+                        }
+                    }
+                    cx.notify();
+                })
+            })
+            .ok();
+        })
+        .detach();
+    }
+
+    /// Render a labelled masked input
+    fn secret_field(label: &'static str, input: &Entity<InputState>) -> impl IntoElement {
+        div()
+            .v_flex()
+            .gap_1()
+            .child(
+                div()
+                    .text_sm()
+                    .font_weight(FontWeight::MEDIUM)
+                    .text_color(colors::text_primary())
+                    .child(label),
+            )
+            .child(
+                Input::new(input)
+                    .mask_toggle()
+                    .bg(colors::bg_surface())
+                    .border_color(colors::border())
+                    .text_color(colors::text_primary()),
+            )
+    }
+
+    /// First step: salt and password with live strength checks
+    fn enter_step(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let salt = self.salt_input.read(cx).value();
+        let password = self.password_input.read(cx).value();
+        let checks = EncryptionParams::checks(&salt, &password);
+        let can_continue = checks.iter().all(|check| check.passed || !check.required);
+
+        div()
+            .v_flex()
+            .gap_4()
+            .child(Self::secret_field("Encryption Salt", &self.salt_input))
+            .child(Self::secret_field(
+                "Encryption Password",
+                &self.password_input,
+            ))
+            .child(
+                div()
+                    .v_flex()
+                    .gap_1()
+                    .children(checks.into_iter().map(|check| {
+                        let (icon, color) = match (check.passed, check.required) {
+                            (true, _) => (IconName::CircleCheck, colors::success()),
+                            (false, true) => (IconName::CircleX, colors::error()),
+                            (false, false) => (IconName::TriangleAlert, colors::warning()),
+                        };
+                        div()
+                            .h_flex()
+                            .gap_2()
+                            .items_center()
+                            .text_sm()
+                            .child(Icon::new(icon).size(px(14.0)).text_color(color))
+                            .child(div().text_color(colors::text_secondary()).child(
+                                if check.required {
+                                    check.label.to_string()
+                                } else {
+                                    format!("{} (recommended)", check.label)
+                                },
+                            ))
+                    })),
+            )
+            .child(
+                div().child(
+                    Button::new("encryption-continue-btn")
+                        .primary()
+                        .label("Continue")
+                        .icon(Icon::new(IconName::ArrowRight).size(px(16.0)))
+                        .disabled(!can_continue)
+                        .on_click(cx.listener(|this, _, _window, cx| {
+                            this.continue_to_confirm(cx);
+                        })),
+                ),
+            )
+    }
+
+    /// Second step: type the environment name and submit
+    fn confirm_step(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let environment_name = self
+            .session
+            .as_ref()
+            .map(|s| s.environment().display_name())
+            .unwrap_or_default();
+        let confirmed =
+            confirmation_matches(&self.confirm_input.read(cx).value(), environment_name);
+
+        div()
+            .v_flex()
+            .gap_4()
+            .child(div().text_color(colors::text_secondary()).child(format!(
+                "The salt and password are set once per environment and cannot be changed \
+                 afterwards. Type {} to submit them to {}.",
+                environment_name,
+                environment_label(self.session.as_ref())
+            )))
+            .child(
+                Input::new(&self.confirm_input)
+                    .bg(colors::bg_surface())
+                    .border_color(colors::border())
+                    .text_color(colors::text_primary()),
+            )
+            .child(
+                div()
+                    .h_flex()
+                    .gap_3()
+                    .child(
+                        Button::new("encryption-back-btn")
+                            .label("Back")
+                            .ghost()
+                            .disabled(self.is_submitting)
+                            .on_click(cx.listener(|this, _, _window, cx| {
+                                this.step = EncryptionStep::Enter;
+                                this.form_error = None;
+                                cx.notify();
+                            })),
+                    )
+                    .child(
+                        Button::new("encryption-submit-btn")
+                            .label(if self.is_submitting {
+                                "Submitting..."
+                            } else {
+                                "Submit Encryption Parameters"
+                            })
+                            .danger()
+                            .disabled(!confirmed || self.is_submitting)
+                            .on_click(cx.listener(|this, _, _window, cx| {
+                                this.submit(cx);
+                            })),
+                    ),
+            )
+    }
+}
+
+impl Render for EncryptionView {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        if self.clear_pending {
+            self.clear_inputs(window, cx);
+        }
+
+        div()
+            .v_flex()
+            .size_full()
+            .gap_6()
+            .w(px(700.0))
+            .child(div().text_color(colors::text_secondary()).child(
+                "Bootstrap a new environment with the salt and password used to encrypt the \
+                 keys the server holds. These values are never logged or saved by this app.",
+            ))
+            .child(section_title(match self.step {
+                EncryptionStep::Enter => "Step 1 of 2: Enter Salt and Password",
+                EncryptionStep::Confirm => "Step 2 of 2: Confirm Environment",
+                EncryptionStep::Done => "Done",
+            }))
+            // Error message
+            .when_some(self.form_error.clone(), |this, error_msg| {
+                this.child(
+                    div()
+                        .text_sm()
+                        .text_color(colors::error())
+                        .p_2()
+                        .rounded(px(4.0))
+                        .bg(rgba(0xff000020))
+                        .child(error_msg),
+                )
+            })
+            .child(match self.step {
+                EncryptionStep::Enter => self.enter_step(cx).into_any_element(),
+                EncryptionStep::Confirm => self.confirm_step(cx).into_any_element(),
+                EncryptionStep::Done => div()
+                    .text_color(colors::success())
+                    .child(format!(
+                        "Encryption parameters were saved to {}. The fields have been cleared.",
+                        environment_label(self.session.as_ref())
+                    ))
+                    .into_any_element(),
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::confirmation_matches;
+
+    #[test]
+    fn test_confirmation_matches() {
+        assert!(confirmation_matches("Studio", "Studio"));
+        assert!(confirmation_matches("  studio ", "Studio"));
+        assert!(!confirmation_matches("Garage", "Studio"));
+        assert!(!confirmation_matches("", "Studio"));
+    }
+}
@@ -0,0 +1,627 @@
+//! Key Pair View
+//!
+//! Uploads cardano-cli payment key pairs for the server to hold, and
+//! downloads a held key pair by ID after explicit confirmation.
+
+use std::path::PathBuf;
+
+use async_compat::Compat;
+use gpui::prelude::FluentBuilder;
+use gpui::*;
+use gpui_component::button::{Button, ButtonVariants};
+use gpui_component::input::*;
+use gpui_component::table::{Column, Table, TableDelegate, TableState};
+use gpui_component::*;
+
+use crate::cardano_admin::{CardanoAdminClient, CardanoAdminError, KeyHistory, KeyRecord};
+use crate::cli_key::{self, CliKey, CliKeyPair, KeyError};
+use crate::colors;
+use crate::session::{Session, SessionExpiredEvent};
+use crate::toast;
+use crate::views::cardano::{environment_label, section_title};
+
+/// Which half of a key pair a file is for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum KeyFileKind {
+    Signing,
+    Verification,
+}
+
+impl KeyFileKind {
+    fn extension(&self) -> &'static str {
+        match self {
+            KeyFileKind::Signing => "skey",
+            KeyFileKind::Verification => "vkey",
+        }
+    }
+
+    fn label(&self) -> &'static str {
+        match self {
+            KeyFileKind::Signing => "Signing Key",
+            KeyFileKind::Verification => "Verification Key",
+        }
+    }
+}
+
+/// A picked key file and the result of reading it
+#[derive(Debug, Clone)]
+struct KeyFile {
+    path: PathBuf,
+    key: Result<CliKey, KeyError>,
+}
+
+impl KeyFile {
+    fn file_name(&self) -> String {
+        self.path
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .to_string()
+    }
+}
+
+// -----------------------------------------------------------------------------
+// History Table Delegate
+// -----------------------------------------------------------------------------
+
+#[derive(Clone)]
+struct KeyHistoryTableDelegate {
+    records: Vec<KeyRecord>,
+    columns: Vec<Column>,
+}
+
+impl KeyHistoryTableDelegate {
+    fn new() -> Self {
+        Self {
+            records: Vec::new(),
+            columns: vec![
+                Column::new("name", "Name").width(px(300.)),
+                Column::new("address", "Address").width(px(560.)),
+                Column::new("uploaded_at", "Uploaded At").width(px(200.)),
+            ],
+        }
+    }
+}
+
+impl TableDelegate for KeyHistoryTableDelegate {
+    fn columns_count(&self, _cx: &App) -> usize {
+        self.columns.len()
+    }
+
+    fn rows_count(&self, _cx: &App) -> usize {
+        self.records.len()
+    }
+
+    fn column(&self, col_ix: usize, _cx: &App) -> &Column {
+        &self.columns[col_ix]
+    }
+
+    fn render_td(
+        &mut self,
+        row_ix: usize,
+        col_ix: usize,
+        _window: &mut Window,
+        _cx: &mut Context<TableState<Self>>,
+    ) -> impl IntoElement {
+        let record = &self.records[row_ix];
+        match col_ix {
+            0 => div().child(record.name.clone()),
+            1 => div().child(record.address.clone()),
+            2 => div().child(record.uploaded_at.clone()),
+            _ => div(),
+        }
+    }
+}
+
+pub struct KeysView {
+    session: Option<Session>,
+    name_input: Entity<InputState>,
+    skey_file: Option<KeyFile>,
+    vkey_file: Option<KeyFile>,
+    is_uploading: bool,
+    form_error: Option<String>,
+    history: KeyHistory,
+    history_table: Entity<TableState<KeyHistoryTableDelegate>>,
+
+    // Download
+    key_id_input: Entity<InputState>,
+    show_download_confirmation: bool,
+    is_downloading: bool,
+}
+
+impl KeysView {
+    pub fn new(window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let name_input = cx.new(|cx| InputState::new(window, cx).placeholder("Key name"));
+        let key_id_input = cx.new(|cx| InputState::new(window, cx).placeholder("Key ID (UUID)"));
+        let history_table =
+            cx.new(|cx| TableState::new(KeyHistoryTableDelegate::new(), window, cx));
+
+        cx.observe(&name_input, |_this: &mut Self, _, cx| cx.notify())
+            .detach();
+
+        Self {
+            session: None,
+            name_input,
+            skey_file: None,
+            vkey_file: None,
+            is_uploading: false,
+            form_error: None,
+            history: KeyHistory::load(),
+            history_table,
+            key_id_input,
+            show_download_confirmation: false,
+            is_downloading: false,
+        }
+    }
+
+    /// Set the session (called from CardanoView after login)
+    pub fn set_session(&mut self, session: Option<Session>, cx: &mut Context<Self>) {
+        self.session = session;
+        self.update_history(cx);
+        cx.notify();
+    }
+
+    /// Show the history for the session's environment
+    fn update_history(&mut self, cx: &mut Context<Self>) {
+        let records = match &self.session {
+            Some(session) => self.history.records(session.environment()).to_vec(),
+            None => Vec::new(),
+        };
+        self.history_table.update(cx, |table, cx| {
+            table.delegate_mut().records = records;
+            cx.notify();
+        });
+    }
+
+    /// Pick a `.skey` or `.vkey` file and read it
+    fn pick_key_file(&mut self, kind: KeyFileKind, cx: &mut Context<Self>) {
+        cx.spawn(async move |this, cx| {
+            let file_handle = rfd::AsyncFileDialog::new()
+                .add_filter(kind.label(), &[kind.extension()])
+                .set_title(format!("Select {} File", kind.label()))
+                .pick_file()
+                .await;
+
+            let Some(file_handle) = file_handle else {
+                return;
+            };
+
+            let path = file_handle.path().to_path_buf();
+            tracing::info!("Selected {} file: {:?}", kind.extension(), path);
+            let key = CliKey::read_file(&path);
+
+            cx.update(|cx| {
+                this.update(cx, |view, cx| {
+                    let file = Some(KeyFile { path, key });
+                    match kind {
+                        KeyFileKind::Signing => view.skey_file = file,
+                        KeyFileKind::Verification => view.vkey_file = file,
+                    }
+                    view.form_error = None;
+                    cx.notify();
+                })
+            })
+            .ok();
+        })
+        .detach();
+    }
+
+    /// Check the picked files and name, returning the pair ready for upload
+    fn validated_key_pair(&self, cx: &App) -> Result<CliKeyPair, String> {
+        let session = self.session.as_ref().ok_or("No active session")?;
+        let skey = match &self.skey_file {
+            Some(file) => file.key.clone().map_err(|e| e.to_string())?,
+            None => return Err("Choose a signing key (.skey) file".to_string()),
+        };
+        let vkey = match &self.vkey_file {
+            Some(file) => file.key.clone().map_err(|e| e.to_string())?,
+            None => return Err("Choose a verification key (.vkey) file".to_string()),
+        };
+
+        let name = self.name_input.read(cx).value();
+        let key_pair = CliKeyPair::new(&name, skey, vkey).map_err(|e| e.to_string())?;
+
+        let environment = session.environment();
+        if self
+            .history
+            .records(environment)
+            .iter()
+            .any(|r| r.name == key_pair.name)
+        {
+            return Err(format!(
+                "A key named '{}' was already uploaded to {}",
+                key_pair.name,
+                environment.display_name()
+            ));
+        }
+        Ok(key_pair)
+    }
+
+    /// Upload the picked key pair
+    fn upload(&mut self, cx: &mut Context<Self>) {
+        self.form_error = None;
+
+        let key_pair = match self.validated_key_pair(cx) {
+            Ok(key_pair) => key_pair,
+            Err(e) => {
+                self.form_error = Some(e);
+                cx.notify();
+                return;
+            }
+        };
+        let Some(session) = self.session.clone() else {
+            return;
+        };
+
+        self.is_uploading = true;
+        cx.notify();
+
+        cx.spawn(async move |this, cx| {
+            let client = CardanoAdminClient::new();
+            let result = Compat::new(client.upload_key(&session, &key_pair)).await;
+
+            cx.update(|cx| {
+                this.update(cx, |view, cx| {
+                    view.is_uploading = false;
+                    match result {
+                        Ok(()) => {
+                            let environment = session.environment();
+                            let address =
+                                key_pair.address(environment.network()).unwrap_or_default();
+                            view.history.add(
+                                environment,
+                                KeyRecord {
+                                    name: key_pair.name.clone(),
+                                    address,
+                                    uploaded_at: chrono::Local::now()
+                                        .format("%Y-%m-%d %H:%M:%S")
+                                        .to_string(),
+                                },
+                            );
+                            if let Err(e) = view.history.save() {
+                                tracing::warn!("Failed to save key history: {}", e);
+                            }
+                            view.update_history(cx);
+                            view.skey_file = None;
+                            view.vkey_file = None;
+                            toast::show_success_async(
+                                cx,
+                                format!("Uploaded key pair '{}'", key_pair.name),
+                            );
+                        }
+                        Err(CardanoAdminError::SessionExpired(msg)) => {
+                            cx.emit(SessionExpiredEvent { message: msg });
+                        }
+                        Err(e) => {
+                            view.form_error = Some(e.to_string());
+                        }
+                    }
+                    cx.notify();
+                })
+            })
+            .ok();
+        })
+        .detach();
+    }
+
+    /// Download a key pair after the admin confirmed, saving it to a chosen folder
+    fn download(&mut self, cx: &mut Context<Self>) {
+        self.show_download_confirmation = false;
+
+        let Some(session) = self.session.clone() else {
+            toast::show_error_async(cx, "No active session".to_string());
+            return;
+        };
+        let key_id = self.key_id_input.read(cx).value().trim().to_string();
+
+        self.is_downloading = true;
+        cx.notify();
+
+        cx.spawn(async move |this, cx| {
+            let finish = |cx: &mut AsyncApp| {
+                cx.update(|cx| {
+                    this.update(cx, |view, cx| {
+                        view.is_downloading = false;
+                        cx.notify();
+                    })
+                })
+                .ok();
+            };
+
+            let folder = rfd::AsyncFileDialog::new()
+                .set_title("Save Key Pair To")
+                .pick_folder()
+                .await;
+            let Some(folder) = folder else {
+                finish(cx);
+                return;
+            };
+
+            let client = CardanoAdminClient::new();
+            let result = Compat::new(client.get_key(&session, &key_id)).await;
+            let saved = match result {
+                Ok(key_pair) => cli_key::write_key_files(folder.path(), &key_pair),
+                Err(CardanoAdminError::SessionExpired(msg)) => {
+                    cx.update(|cx| {
+                        this.update(cx, |view, cx| {
+                            view.is_downloading = false;
+                            cx.emit(SessionExpiredEvent { message: msg });
+                            cx.notify();
+                        })
+                    })
+                    .ok();
+                    return;
+                }
+                Err(e) => Err(e.to_string()),
+            };
+
+            finish(cx);
+            cx.update(|cx| match saved {
+                Ok(paths) => {
+                    tracing::info!("Saved key {} to {:?}", key_id, folder.path());
+                    toast::show_success_async(
+                        cx,
+                        format!(
+                            "Saved {} key file(s) to {}",
+                            paths.len(),
+                            folder.path().display()
+                        ),
+                    );
+                }
+                Err(e) => toast::show_error_async(cx, format!("Download failed: {}", e)),
+            })
+            .ok();
+        })
+        .detach();
+    }
+
+    /// Render a file picker row for one half of the key pair
+    fn key_file_row(&self, kind: KeyFileKind, cx: &mut Context<Self>) -> impl IntoElement {
+        let file = match kind {
+            KeyFileKind::Signing => &self.skey_file,
+            KeyFileKind::Verification => &self.vkey_file,
+        };
+        let (status, color) = match file {
+            None => ("No file chosen".to_string(), colors::text_secondary()),
+            Some(file) => match &file.key {
+                Ok(_) => (file.file_name(), colors::text_primary()),
+                Err(e) => (format!("{}: {}", file.file_name(), e), colors::error()),
+            },
+        };
+
+        div()
+            .h_flex()
+            .gap_3()
+            .items_center()
+            .child(
+                div()
+                    .w(px(140.0))
+                    .text_sm()
+                    .font_weight(FontWeight::MEDIUM)
+                    .text_color(colors::text_primary())
+                    .child(kind.label()),
+            )
+            .child(
+                Button::new(SharedString::from(format!("pick-{}-btn", kind.extension())))
+                    .label(format!("Choose .{}", kind.extension()))
+                    .icon(Icon::new(IconName::FolderOpen).size(px(16.0)))
+                    .ghost()
+                    .disabled(self.is_uploading)
+                    .on_click(cx.listener(move |this, _, _window, cx| {
+                        this.pick_key_file(kind, cx);
+                    })),
+            )
+            .child(div().text_sm().text_color(color).child(status))
+    }
+
+    /// Download confirmation modal
+    fn download_confirmation(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let key_id = self.key_id_input.read(cx).value().trim().to_string();
+
+        div()
+            .absolute()
+            .inset_0()
+            .flex()
+            .items_center()
+            .justify_center()
+            .bg(gpui::Rgba {
+                r: 0.0,
+                g: 0.0,
+                b: 0.0,
+                a: 0.5,
+            })
+            .child(
+                div()
+                    .v_flex()
+                    .gap_4()
+                    .p_6()
+                    .rounded_lg()
+                    .bg(colors::bg_surface())
+                    .border_1()
+                    .border_color(colors::border())
+                    .shadow_lg()
+                    .min_w(px(400.0))
+                    .max_w(px(600.0))
+                    .child(
+                        div()
+                            .text_xl()
+                            .font_weight(FontWeight::BOLD)
+                            .text_color(colors::text_primary())
+                            .child("Confirm Key Download"),
+                    )
+                    .child(div().text_color(colors::text_secondary()).child(format!(
+                        "Download key {} from {}? The signing key is decrypted and written \
+                         to disk in plain text. Anyone with the file can spend from this key.",
+                        key_id,
+                        environment_label(self.session.as_ref())
+                    )))
+                    .child(
+                        div()
+                            .h_flex()
+                            .gap_3()
+                            .justify_end()
+                            .child(
+                                Button::new("cancel-download-key-btn")
+                                    .label("Cancel")
+                                    .ghost()
+                                    .on_click(cx.listener(|this, _, _window, cx| {
+                                        this.show_download_confirmation = false;
+                                        cx.notify();
+                                    })),
+                            )
+                            .child(
+                                Button::new("confirm-download-key-btn")
+                                    .label("Download")
+                                    .danger()
+                                    .on_click(cx.listener(|this, _, _window, cx| {
+                                        this.download(cx);
+                                    })),
+                            ),
+                    ),
+            )
+    }
+}
+
+impl Render for KeysView {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let validation = if self.skey_file.is_some() && self.vkey_file.is_some() {
+            Some(self.validated_key_pair(cx).and_then(|key_pair| {
+                let network = self
+                    .session
+                    .as_ref()
+                    .map(|s| s.environment().network())
+                    .ok_or("No active session")?;
+                key_pair.address(network).map_err(|e| e.to_string())
+            }))
+        } else {
+            None
+        };
+        let can_upload = matches!(validation, Some(Ok(_))) && !self.is_uploading;
+
+        div()
+            .relative()
+            .v_flex()
+            .size_full()
+            .overflow_hidden()
+            .gap_6()
+            .child(div().text_color(colors::text_secondary()).child(
+                "Upload a payment key pair created with cardano-cli. The server stores the \
+                 signing key encrypted; names must be unique.",
+            ))
+            // Error message
+            .when_some(self.form_error.clone(), |this, error_msg| {
+                this.child(
+                    div()
+                        .text_sm()
+                        .text_color(colors::error())
+                        .p_2()
+                        .rounded(px(4.0))
+                        .bg(rgba(0xff000020))
+                        .child(error_msg),
+                )
+            })
+            .child(
+                div()
+                    .v_flex()
+                    .gap_3()
+                    .w(px(700.0))
+                    .child(
+                        div()
+                            .v_flex()
+                            .gap_1()
+                            .child(
+                                div()
+                                    .text_sm()
+                                    .font_weight(FontWeight::MEDIUM)
+                                    .text_color(colors::text_primary())
+                                    .child(format!(
+                                        "Name - {}",
+                                        environment_label(self.session.as_ref())
+                                    )),
+                            )
+                            .child(
+                                Input::new(&self.name_input)
+                                    .bg(colors::bg_surface())
+                                    .border_color(colors::border())
+                                    .text_color(colors::text_primary()),
+                            ),
+                    )
+                    .child(self.key_file_row(KeyFileKind::Signing, cx))
+                    .child(self.key_file_row(KeyFileKind::Verification, cx))
+                    .when_some(validation, |this, validation| {
+                        this.child(match validation {
+                            Ok(address) => div()
+                                .text_sm()
+                                .text_color(colors::success())
+                                .child(format!("Keys match - address {}", address)),
+                            Err(e) => div().text_sm().text_color(colors::error()).child(e),
+                        })
+                    })
+                    .child(
+                        div().child(
+                            Button::new("upload-key-btn")
+                                .primary()
+                                .label(if self.is_uploading {
+                                    "Uploading..."
+                                } else {
+                                    "Upload Key Pair"
+                                })
+                                .icon(Icon::new(IconName::ArrowUp).size(px(16.0)))
+                                .disabled(!can_upload)
+                                .on_click(cx.listener(|this, _, _window, cx| {
+                                    this.upload(cx);
+                                })),
+                        ),
+                    ),
+            )
+            .child(section_title("Download Key Pair"))
+            .child(
+                div()
+                    .h_flex()
+                    .gap_3()
+                    .items_center()
+                    .child(
+                        div().w(px(420.0)).child(
+                            Input::new(&self.key_id_input)
+                                .bg(colors::bg_surface())
+                                .border_color(colors::border())
+                                .text_color(colors::text_primary()),
+                        ),
+                    )
+                    .child(
+                        Button::new("download-key-btn")
+                            .label(if self.is_downloading {
+                                "Downloading..."
+                            } else {
+                                "Download"
+                            })
+                            .icon(Icon::new(IconName::ArrowDown).size(px(16.0)))
+                            .danger()
+                            .disabled(
+                                self.is_downloading
+                                    || self.key_id_input.read(cx).value().trim().is_empty(),
+                            )
+                            .on_click(cx.listener(|this, _, _window, cx| {
+                                this.show_download_confirmation = true;
+                                cx.notify();
+                            })),
+                    ),
+            )
+            .child(section_title("Uploaded From This Machine"))
+            .child(
+                div()
+                    .h_full()
+                    .w_full()
+                    .flex_1()
+                    .rounded_lg()
+                    .bg(colors::bg_surface())
+                    .border_1()
+                    .border_color(colors::border())
+                    .overflow_hidden()
+                    .child(Table::new(&self.history_table)),
+            )
+            .when(self.show_download_confirmation, |this| {
+                this.child(self.download_confirmation(cx))
+            })
+    }
+}
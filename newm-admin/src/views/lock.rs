@@ -0,0 +1,624 @@
+//! Lock Screen
+//!
+//! Covers the console after a period of inactivity. The views underneath keep
+//! their state; unlocking needs a fresh login as the same user, the same way
+//! they signed in: password, or the OAuth provider's page in the browser. A
+//! remembered session could have been either, so both are offered. Servers
+//! that require two-factor codes for admins get the same code step as the
+//! login screen.
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+use async_compat::Compat;
+use gpui::prelude::FluentBuilder;
+use gpui::*;
+use gpui_component::button::{Button, ButtonVariants};
+use gpui_component::input::*;
+use gpui_component::*;
+
+use crate::app::{LogoutEvent, SignInMethod};
+use crate::audit::{self, AuditAction, AuditEntry};
+use crate::auth::{AuthClient, AuthError, LoginResponse};
+use crate::colors;
+use crate::oauth::{OAuthAttempt, OAuthProvider};
+use crate::session::Session;
+use crate::views::login::{CodeChallenge, LoginStatus};
+
+/// Event emitted once the admin has re-authenticated
+pub struct UnlockEvent {
+    pub login_response: LoginResponse,
+}
+
+/// Only the admin who locked the console may unlock it
+fn same_user(session: &Session, response: LoginResponse) -> Result<LoginResponse, AuthError> {
+    let user_id = crate::jwt::parse_claims(&response.access_token)
+        .map_err(AuthError::Parse)?
+        .sub;
+    if user_id.is_some() && user_id == session.user_id() {
+        Ok(response)
+    } else {
+        Err(AuthError::Http {
+            status: 403,
+            message: "Signed in as a different user than the one who locked the console"
+                .to_string(),
+        })
+    }
+}
+
+pub struct LockView {
+    session: Session,
+    sign_in: Option<SignInMethod>,
+    email_input: Entity<InputState>,
+    password_input: Entity<InputState>,
+    code_input: Entity<InputState>,
+    status: LoginStatus,
+    /// Set while waiting for the browser to finish an OAuth sign-in
+    oauth_cancel: Option<Arc<AtomicBool>>,
+    /// Re-renders the resend countdown once a second
+    _cooldown_task: Option<Task<()>>,
+}
+
+impl LockView {
+    pub fn new(
+        session: Session,
+        sign_in: Option<SignInMethod>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let email = match &sign_in {
+            Some(SignInMethod::Password { email }) => Some(email.clone()),
+            _ => None,
+        };
+        let email_input = cx.new(|cx| {
+            InputState::new(window, cx)
+                .placeholder("Email")
+                .default_value(email.clone().unwrap_or_default())
+        });
+        let password_input = cx.new(|cx| {
+            InputState::new(window, cx)
+                .placeholder("Password")
+                .masked(true)
+        });
+
+        // Start typing straight away
+        let focused = if email.is_some() {
+            &password_input
+        } else {
+            &email_input
+        };
+        focused.update(cx, |state, cx| state.focus(window, cx));
+
+        cx.subscribe_in(
+            &password_input,
+            window,
+            |this, _input, event: &input::InputEvent, _window, cx| {
+                if let input::InputEvent::PressEnter { .. } = event {
+                    this.unlock(cx);
+                }
+            },
+        )
+        .detach();
+
+        let code_input = cx.new(|cx| InputState::new(window, cx).placeholder("Verification code"));
+        cx.subscribe_in(
+            &code_input,
+            window,
+            |this, _input, event: &input::InputEvent, _window, cx| {
+                if let input::InputEvent::PressEnter { .. } = event {
+                    this.verify_code(cx);
+                }
+            },
+        )
+        .detach();
+
+        Self {
+            session,
+            sign_in,
+            email_input,
+            password_input,
+            code_input,
+            status: LoginStatus::Idle,
+            oauth_cancel: None,
+            _cooldown_task: None,
+        }
+    }
+
+    fn is_unlocking(&self) -> bool {
+        matches!(self.status, LoginStatus::Loading)
+    }
+
+    /// Whether to show the email and password form
+    fn offers_password(&self) -> bool {
+        !matches!(self.sign_in, Some(SignInMethod::OAuth(_)))
+    }
+
+    /// OAuth providers to offer for unlocking
+    fn oauth_providers(&self) -> Vec<OAuthProvider> {
+        let environment = self.session.environment();
+        let providers = match self.sign_in {
+            Some(SignInMethod::OAuth(provider)) => vec![provider],
+            Some(SignInMethod::Password { .. }) => Vec::new(),
+            None => OAuthProvider::ALL.to_vec(),
+        };
+        providers
+            .into_iter()
+            .filter(|provider| environment.oauth_client_id(*provider).is_some())
+            .collect()
+    }
+
+    fn unlock(&mut self, cx: &mut Context<Self>) {
+        if self.is_unlocking() || matches!(self.status, LoginStatus::CodeChallenge(_)) {
+            return;
+        }
+        let email = self.email_input.read(cx).value().trim().to_string();
+        let password = self.password_input.read(cx).value().to_string();
+        if email.is_empty() || password.is_empty() {
+            self.status = LoginStatus::Error("Please enter email and password".to_string());
+            cx.notify();
+            return;
+        }
+
+        self.status = LoginStatus::Loading;
+        cx.notify();
+
+        let session = self.session.clone();
+        cx.spawn(async move |this, cx| {
+            let client = AuthClient::new();
+            let result = Compat::new(client.login(&email, &password, session.environment()))
+                .await
+                .and_then(|response| same_user(&session, response));
+
+            cx.update(|cx| {
+                this.update(cx, |view, cx| match result {
+                    // The password was right; the server wants a second factor
+                    Err(AuthError::CodeRequired) => {
+                        view.status = LoginStatus::CodeChallenge(CodeChallenge::default());
+                        view.send_code(cx);
+                    }
+                    result => view.finish_unlock(result, email, cx),
+                })
+            })
+            .ok();
+        })
+        .detach();
+    }
+
+    fn challenge_mut(&mut self) -> Option<&mut CodeChallenge> {
+        match &mut self.status {
+            LoginStatus::CodeChallenge(challenge) => Some(challenge),
+            _ => None,
+        }
+    }
+
+    /// Have the server email a (new) verification code
+    fn send_code(&mut self, cx: &mut Context<Self>) {
+        let email = self.email_input.read(cx).value().trim().to_string();
+        let environment = self.session.environment().clone();
+        let Some(challenge) = self.challenge_mut() else {
+            return;
+        };
+        if !challenge.can_resend(Instant::now()) {
+            return;
+        }
+        challenge.sending = true;
+        challenge.error = None;
+        cx.notify();
+
+        cx.spawn(async move |this, cx| {
+            let client = AuthClient::new();
+            let result = Compat::new(client.request_code(&email, &environment)).await;
+
+            cx.update(|cx| {
+                this.update(cx, |view, cx| {
+                    let Some(challenge) = view.challenge_mut() else {
+                        return;
+                    };
+                    challenge.sending = false;
+                    match result {
+                        Ok(()) => {
+                            challenge.sent_at = Some(Instant::now());
+                            view.start_cooldown(cx);
+                        }
+                        Err(e) => challenge.error = Some(e.to_string()),
+                    }
+                    cx.notify();
+                })
+            })
+            .ok();
+        })
+        .detach();
+    }
+
+    /// Tick the resend countdown until it runs out
+    fn start_cooldown(&mut self, cx: &mut Context<Self>) {
+        self._cooldown_task = Some(cx.spawn(async move |this, cx| {
+            loop {
+                cx.background_executor().timer(Duration::from_secs(1)).await;
+                let counting = this.update(cx, |view, cx| {
+                    cx.notify();
+                    view.challenge_mut()
+                        .is_some_and(|challenge| challenge.resend_in(Instant::now()).is_some())
+                });
+                if !matches!(counting, Ok(true)) {
+                    break;
+                }
+            }
+        }));
+    }
+
+    /// Log in again with the password and the emailed code
+    fn verify_code(&mut self, cx: &mut Context<Self>) {
+        let email = self.email_input.read(cx).value().trim().to_string();
+        let password = self.password_input.read(cx).value().to_string();
+        let code = self.code_input.read(cx).value().trim().to_string();
+        let Some(challenge) = self.challenge_mut() else {
+            return;
+        };
+        if challenge.verifying {
+            return;
+        }
+        if code.is_empty() {
+            challenge.error = Some("Please enter the code from the email".to_string());
+            cx.notify();
+            return;
+        }
+        challenge.verifying = true;
+        challenge.error = None;
+        cx.notify();
+
+        let session = self.session.clone();
+        cx.spawn(async move |this, cx| {
+            let client = AuthClient::new();
+            let result = Compat::new(client.login_with_code(
+                &email,
+                &password,
+                Some(&code),
+                session.environment(),
+            ))
+            .await
+            .and_then(|response| same_user(&session, response));
+
+            cx.update(|cx| this.update(cx, |view, cx| view.finish_unlock(result, email, cx)))
+                .ok();
+        })
+        .detach();
+    }
+
+    /// Leave the code step and go back to email and password
+    fn cancel_code_challenge(&mut self, cx: &mut Context<Self>) {
+        self.status = LoginStatus::Idle;
+        self._cooldown_task = None;
+        cx.notify();
+    }
+
+    /// Sign in again through the provider's page in the system browser
+    fn unlock_with_oauth(&mut self, provider: OAuthProvider, cx: &mut Context<Self>) {
+        if self.is_unlocking() {
+            return;
+        }
+        let Some(client_id) = self.session.environment().oauth_client_id(provider) else {
+            return;
+        };
+        let attempt = match OAuthAttempt::start(provider) {
+            Ok(attempt) => attempt,
+            Err(e) => {
+                self.status = LoginStatus::Error(e.to_string());
+                cx.notify();
+                return;
+            }
+        };
+        cx.open_url(&attempt.authorization_url(client_id));
+
+        let cancelled = Arc::new(AtomicBool::new(false));
+        self.oauth_cancel = Some(cancelled.clone());
+        self.status = LoginStatus::Loading;
+        cx.notify();
+
+        let session = self.session.clone();
+        cx.spawn(async move |this, cx| {
+            let wait_cancelled = cancelled.clone();
+            let code = cx
+                .background_spawn(async move { attempt.wait(&wait_cancelled) })
+                .await;
+
+            let result = match code {
+                Ok(code) => {
+                    let client = AuthClient::new();
+                    Compat::new(client.login_oauth(&code, session.environment()))
+                        .await
+                        .and_then(|response| same_user(&session, response))
+                }
+                Err(e) => Err(e),
+            };
+
+            cx.update(|cx| {
+                this.update(cx, |view, cx| {
+                    view.oauth_cancel = None;
+                    if cancelled.load(Ordering::Relaxed) {
+                        view.status = LoginStatus::Idle;
+                        cx.notify();
+                        return;
+                    }
+                    view.finish_unlock(result, format!("{} account", provider.label()), cx);
+                })
+            })
+            .ok();
+        })
+        .detach();
+    }
+
+    /// Stop waiting for the browser
+    fn cancel_oauth(&mut self, cx: &mut Context<Self>) {
+        if let Some(cancelled) = self.oauth_cancel.take() {
+            cancelled.store(true, Ordering::Relaxed);
+        }
+        cx.notify();
+    }
+
+    /// Unlock, or record the failed attempt by `user`
+    fn finish_unlock(
+        &mut self,
+        result: Result<LoginResponse, AuthError>,
+        user: String,
+        cx: &mut Context<Self>,
+    ) {
+        match result {
+            Ok(login_response) => {
+                self.status = LoginStatus::Success;
+                self._cooldown_task = None;
+                cx.emit(UnlockEvent { login_response });
+            }
+            Err(e) => {
+                audit::record(AuditEntry::new(
+                    AuditAction::UnlockFailed,
+                    self.session.environment().display_name(),
+                    Some(format!("{} ({})", user, e)),
+                ));
+                match (self.challenge_mut(), e) {
+                    // Let the admin try again or ask for a new code
+                    (Some(challenge), e @ AuthError::InvalidCode) => {
+                        challenge.verifying = false;
+                        challenge.error = Some(e.to_string());
+                    }
+                    (_, e) => {
+                        self._cooldown_task = None;
+                        self.status = LoginStatus::Error(match e {
+                            AuthError::Http { message, .. } => message,
+                            e => e.to_string(),
+                        });
+                    }
+                }
+            }
+        }
+        cx.notify();
+    }
+
+    /// Verification code entry with resend and back buttons
+    fn render_code_challenge(
+        &self,
+        challenge: &CodeChallenge,
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        let email = self.email_input.read(cx).value().trim().to_string();
+        let resend_label = match challenge.resend_in(Instant::now()) {
+            _ if challenge.sending => "Sending...".to_string(),
+            Some(left) => format!("Resend code in {}s", left.as_secs() + 1),
+            None => "Resend code".to_string(),
+        };
+
+        div()
+            .v_flex()
+            .gap_4()
+            .child(
+                div()
+                    .text_sm()
+                    .text_color(colors::text_secondary())
+                    .child(format!("Enter the verification code sent to {}.", email)),
+            )
+            .child(
+                Input::new(&self.code_input)
+                    .bg(colors::bg_surface())
+                    .border_color(colors::border())
+                    .text_color(colors::text_primary()),
+            )
+            .when_some(challenge.error.clone(), |this, error| {
+                this.child(div().text_sm().text_color(colors::error()).child(error))
+            })
+            .child(
+                div()
+                    .h_flex()
+                    .justify_between()
+                    .child(
+                        Button::new("lock-code-back")
+                            .ghost()
+                            .icon(IconName::ArrowLeft)
+                            .label("Back")
+                            .disabled(challenge.verifying)
+                            .on_click(cx.listener(|this, _, _window, cx| {
+                                this.cancel_code_challenge(cx);
+                            })),
+                    )
+                    .child(
+                        Button::new("lock-code-resend")
+                            .ghost()
+                            .label(resend_label)
+                            .disabled(!challenge.can_resend(Instant::now()))
+                            .on_click(cx.listener(|this, _, _window, cx| {
+                                this.send_code(cx);
+                            })),
+                    ),
+            )
+    }
+}
+
+impl Render for LockView {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let environment = self.session.environment();
+        let is_unlocking = self.is_unlocking();
+        let challenge = match &self.status {
+            LoginStatus::CodeChallenge(challenge) => Some(challenge.clone()),
+            _ => None,
+        };
+        let error = match &self.status {
+            LoginStatus::Error(message) => Some(message.clone()),
+            _ => None,
+        };
+        let offers_password = challenge.is_none() && self.offers_password();
+        let oauth_providers = if challenge.is_none() {
+            self.oauth_providers()
+        } else {
+            Vec::new()
+        };
+        let waiting_for_browser = self.oauth_cancel.is_some();
+        let prompt = match &self.sign_in {
+            Some(SignInMethod::OAuth(provider)) => {
+                format!(
+                    "Sign in with {} to continue where you left off.",
+                    provider.label()
+                )
+            }
+            _ => "Enter your password to continue where you left off.".to_string(),
+        };
+
+        div()
+            .absolute()
+            .inset_0()
+            .occlude()
+            .v_flex()
+            .items_center()
+            .justify_center()
+            .bg(colors::bg_primary())
+            .child(
+                div()
+                    .v_flex()
+                    .gap_4()
+                    .p_8()
+                    .rounded_lg()
+                    .bg(colors::bg_surface())
+                    .border_1()
+                    .border_color(colors::border())
+                    .w(px(400.0))
+                    .child(
+                        div()
+                            .h_flex()
+                            .gap_2()
+                            .items_center()
+                            .child(div().size(px(8.0)).rounded_full().bg(environment.color()))
+                            .child(
+                                div()
+                                    .text_xl()
+                                    .font_weight(FontWeight::BOLD)
+                                    .text_color(colors::text_primary())
+                                    .child("Console Locked"),
+                            ),
+                    )
+                    .child(
+                        div()
+                            .text_sm()
+                            .text_color(colors::text_secondary())
+                            .child(format!(
+                                "The {} session was locked after a period of inactivity. {}",
+                                environment.display_name(),
+                                prompt
+                            )),
+                    )
+                    .when(offers_password, |this| {
+                        this.child(
+                            Input::new(&self.email_input)
+                                .bg(colors::bg_surface())
+                                .border_color(colors::border())
+                                .text_color(colors::text_primary()),
+                        )
+                        .child(
+                            Input::new(&self.password_input)
+                                .mask_toggle()
+                                .bg(colors::bg_surface())
+                                .border_color(colors::border())
+                                .text_color(colors::text_primary()),
+                        )
+                    })
+                    .when_some(challenge.clone(), |this, challenge| {
+                        this.child(self.render_code_challenge(&challenge, cx))
+                    })
+                    .children(oauth_providers.into_iter().map(|provider| {
+                        Button::new(SharedString::from(format!(
+                            "unlock-oauth-{}",
+                            provider.path()
+                        )))
+                        .label(if waiting_for_browser {
+                            "Waiting for browser...".to_string()
+                        } else {
+                            format!("Continue with {}", provider.label())
+                        })
+                        .outline()
+                        .w_full()
+                        .disabled(is_unlocking)
+                        .on_click(cx.listener(
+                            move |this, _, _window, cx| {
+                                this.unlock_with_oauth(provider, cx);
+                            },
+                        ))
+                    }))
+                    .when_some(error, |this, error| {
+                        this.child(div().text_sm().text_color(colors::error()).child(error))
+                    })
+                    .child(
+                        div()
+                            .h_flex()
+                            .gap_3()
+                            .justify_end()
+                            .child(
+                                Button::new("lock-logout-btn")
+                                    .label("Log Out")
+                                    .ghost()
+                                    .disabled(is_unlocking)
+                                    .on_click(cx.listener(|_this, _, _window, cx| {
+                                        cx.emit(LogoutEvent);
+                                    })),
+                            )
+                            .when(waiting_for_browser, |this| {
+                                this.child(
+                                    Button::new("lock-cancel-oauth-btn")
+                                        .label("Cancel")
+                                        .ghost()
+                                        .on_click(cx.listener(|this, _, _window, cx| {
+                                            this.cancel_oauth(cx);
+                                        })),
+                                )
+                            })
+                            .when(offers_password, |this| {
+                                this.child(
+                                    Button::new("unlock-btn")
+                                        .label(if is_unlocking && !waiting_for_browser {
+                                            "Unlocking..."
+                                        } else {
+                                            "Unlock"
+                                        })
+                                        .primary()
+                                        .disabled(is_unlocking)
+                                        .on_click(cx.listener(|this, _, _window, cx| {
+                                            this.unlock(cx);
+                                        })),
+                                )
+                            })
+                            .when_some(challenge, |this, challenge| {
+                                this.child(
+                                    Button::new("lock-verify-code-btn")
+                                        .label(if challenge.verifying {
+                                            "Verifying..."
+                                        } else {
+                                            "Verify"
+                                        })
+                                        .primary()
+                                        .disabled(challenge.verifying)
+                                        .on_click(cx.listener(|this, _, _window, cx| {
+                                            this.verify_code(cx);
+                                        })),
+                                )
+                            }),
+                    ),
+            )
+    }
+}
@@ -1,17 +1,25 @@
 use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
 
 use async_compat::Compat;
 use gpui::prelude::FluentBuilder;
 use gpui::*;
-use gpui_component::{input::*, radio::*, *};
+use gpui_component::button::{Button, ButtonVariants};
+use gpui_component::{checkbox::Checkbox, input::*, radio::*, *};
 
-use crate::app::LoginSuccessEvent;
-use crate::auth::{AuthClient, AuthError, Environment};
+use crate::app::{LoginSuccessEvent, SignInMethod};
+use crate::auth::{AuthClient, AuthError, LoginResponse};
 use crate::colors;
+use crate::environment::{self, Environment};
+use crate::oauth::{OAuthAttempt, OAuthProvider};
 
 // Embed the NEWM logo at compile time
 const LOGO_BYTES: &[u8] = include_bytes!("../../assets/NEWM_Logo.png");
 
+/// Wait this long before another verification code can be requested
+const RESEND_COOLDOWN: Duration = Duration::from_secs(60);
+
 // Custom gradient button component using GPUI's native linear_gradient
 fn gradient_button(
     id: impl Into<ElementId>,
@@ -74,15 +82,49 @@ pub enum LoginStatus {
     Idle,
     Loading,
     Error(String),
+    /// Password accepted; waiting for the code the server emailed
+    CodeChallenge(CodeChallenge),
     Success,
 }
 
+/// Two-factor step of a password login
+#[derive(Debug, Clone, Default)]
+pub struct CodeChallenge {
+    /// A code request is in flight
+    pub(crate) sending: bool,
+    /// When the last code was sent
+    pub(crate) sent_at: Option<Instant>,
+    pub(crate) verifying: bool,
+    pub(crate) error: Option<String>,
+}
+
+impl CodeChallenge {
+    /// Time left before another code may be requested
+    pub(crate) fn resend_in(&self, now: Instant) -> Option<Duration> {
+        let elapsed = now.saturating_duration_since(self.sent_at?);
+        RESEND_COOLDOWN
+            .checked_sub(elapsed)
+            .filter(|left| !left.is_zero())
+    }
+
+    pub(crate) fn can_resend(&self, now: Instant) -> bool {
+        !self.sending && !self.verifying && self.resend_in(now).is_none()
+    }
+}
+
 pub struct LoginView {
     username_state: Entity<InputState>,
     password_state: Entity<InputState>,
+    code_state: Entity<InputState>,
     password_masked: bool,
+    environments: Vec<Environment>,
     selected_environment: usize,
+    remember_me: bool,
     login_status: LoginStatus,
+    /// Set while waiting for the browser to finish an OAuth sign-in
+    oauth_cancel: Option<Arc<AtomicBool>>,
+    /// Re-renders the resend countdown once a second
+    _cooldown_task: Option<Task<()>>,
 }
 
 impl LoginView {
@@ -95,19 +137,45 @@ impl LoginView {
                 .masked(true)
         });
 
+        let code_state = cx.new(|cx| InputState::new(window, cx).placeholder("Verification code"));
+        cx.subscribe_in(
+            &code_state,
+            window,
+            |this, _input, event: &input::InputEvent, _window, cx| {
+                if let input::InputEvent::PressEnter { .. } = event {
+                    this.verify_code(cx);
+                }
+            },
+        )
+        .detach();
+
         Self {
             username_state: email_state,
             password_state,
+            code_state,
             password_masked: true,
+            environments: environment::load(),
             selected_environment: 0, // Default to Garage
+            remember_me: false,
             login_status: LoginStatus::Idle,
+            oauth_cancel: None,
+            _cooldown_task: None,
         }
     }
 
+    fn selected_environment(&self) -> Option<Environment> {
+        self.environments.get(self.selected_environment).cloned()
+    }
+
     fn perform_login(&mut self, cx: &mut Context<Self>) {
         let email = self.username_state.read(cx).value().to_string();
         let password = self.password_state.read(cx).value().to_string();
-        let environment = Environment::from_index(self.selected_environment);
+        let remember = self.remember_me;
+        let Some(environment) = self.selected_environment() else {
+            self.login_status = LoginStatus::Error("Please select an environment".to_string());
+            cx.notify();
+            return;
+        };
 
         // Validate inputs
         if email.is_empty() || password.is_empty() {
@@ -125,32 +193,64 @@ impl LoginView {
 
             // Wrap the async reqwest call with Compat to enable Tokio context
             let result =
-                Compat::new(async { client.login(&email, &password, environment).await }).await;
+                Compat::new(async { client.login(&email, &password, &environment).await }).await;
+
+            cx.update(|cx| {
+                this.update(cx, |view, cx| match result {
+                    // The password was right; the server wants a second factor
+                    Err(AuthError::CodeRequired) => {
+                        view.login_status = LoginStatus::CodeChallenge(CodeChallenge::default());
+                        view.send_code(cx);
+                    }
+                    result => {
+                        let method = SignInMethod::Password { email };
+                        view.finish_login(result, environment, method, remember, cx)
+                    }
+                })
+            })
+        })
+        .detach();
+    }
+
+    fn challenge_mut(&mut self) -> Option<&mut CodeChallenge> {
+        match &mut self.login_status {
+            LoginStatus::CodeChallenge(challenge) => Some(challenge),
+            _ => None,
+        }
+    }
+
+    /// Have the server email a (new) verification code
+    fn send_code(&mut self, cx: &mut Context<Self>) {
+        let email = self.username_state.read(cx).value().to_string();
+        let Some(environment) = self.selected_environment() else {
+            return;
+        };
+        let Some(challenge) = self.challenge_mut() else {
+            return;
+        };
+        if !challenge.can_resend(Instant::now()) {
+            return;
+        }
+        challenge.sending = true;
+        challenge.error = None;
+        cx.notify();
+
+        cx.spawn(async move |this, cx| {
+            let client = AuthClient::new();
+            let result = Compat::new(client.request_code(&email, &environment)).await;
 
             cx.update(|cx| {
                 this.update(cx, |view, cx| {
+                    let Some(challenge) = view.challenge_mut() else {
+                        return;
+                    };
+                    challenge.sending = false;
                     match result {
-                        Ok(response) => {
-                            tracing::info!(
-                                "Login successful! Access token: {}...",
-                                &response.access_token[..20.min(response.access_token.len())]
-                            );
-                            view.login_status = LoginStatus::Success;
-                            // Emit event with tokens and environment for session creation
-                            cx.emit(LoginSuccessEvent {
-                                login_response: response,
-                                environment,
-                            });
-                        }
-                        Err(AuthError::Http {
-                            status: 401,
-                            message,
-                        }) => {
-                            view.login_status = LoginStatus::Error(message);
-                        }
-                        Err(e) => {
-                            view.login_status = LoginStatus::Error(e.to_string());
+                        Ok(()) => {
+                            challenge.sent_at = Some(Instant::now());
+                            view.start_cooldown(cx);
                         }
+                        Err(e) => challenge.error = Some(e.to_string()),
                     }
                     cx.notify();
                 })
@@ -158,19 +258,404 @@ impl LoginView {
         })
         .detach();
     }
-}
 
-impl Render for LoginView {
-    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+    /// Tick the resend countdown until it runs out
+    fn start_cooldown(&mut self, cx: &mut Context<Self>) {
+        self._cooldown_task = Some(cx.spawn(async move |this, cx| {
+            loop {
+                cx.background_executor().timer(Duration::from_secs(1)).await;
+                let counting = this.update(cx, |view, cx| {
+                    cx.notify();
+                    view.challenge_mut()
+                        .is_some_and(|challenge| challenge.resend_in(Instant::now()).is_some())
+                });
+                if !matches!(counting, Ok(true)) {
+                    break;
+                }
+            }
+        }));
+    }
+
+    /// Log in again with the password and the emailed code
+    fn verify_code(&mut self, cx: &mut Context<Self>) {
+        let email = self.username_state.read(cx).value().to_string();
+        let password = self.password_state.read(cx).value().to_string();
+        let code = self.code_state.read(cx).value().trim().to_string();
+        let remember = self.remember_me;
+        let Some(environment) = self.selected_environment() else {
+            return;
+        };
+        let Some(challenge) = self.challenge_mut() else {
+            return;
+        };
+        if challenge.verifying {
+            return;
+        }
+        if code.is_empty() {
+            challenge.error = Some("Please enter the code from the email".to_string());
+            cx.notify();
+            return;
+        }
+        challenge.verifying = true;
+        challenge.error = None;
+        cx.notify();
+
+        cx.spawn(async move |this, cx| {
+            let client = AuthClient::new();
+            let result =
+                Compat::new(client.login_with_code(&email, &password, Some(&code), &environment))
+                    .await;
+
+            cx.update(|cx| {
+                this.update(cx, |view, cx| match result {
+                    // Let the admin try again or ask for a new code
+                    Err(e @ AuthError::InvalidCode) => {
+                        if let Some(challenge) = view.challenge_mut() {
+                            challenge.verifying = false;
+                            challenge.error = Some(e.to_string());
+                        }
+                        cx.notify();
+                    }
+                    result => {
+                        let method = SignInMethod::Password { email };
+                        view.finish_login(result, environment, method, remember, cx)
+                    }
+                })
+            })
+        })
+        .detach();
+    }
+
+    /// Leave the code step and go back to email and password
+    fn cancel_code_challenge(&mut self, cx: &mut Context<Self>) {
+        self.login_status = LoginStatus::Idle;
+        self._cooldown_task = None;
+        cx.notify();
+    }
+
+    /// Sign in through the provider's page in the system browser
+    fn perform_oauth_login(&mut self, provider: OAuthProvider, cx: &mut Context<Self>) {
+        let remember = self.remember_me;
+        let Some(environment) = self.selected_environment() else {
+            self.login_status = LoginStatus::Error("Please select an environment".to_string());
+            cx.notify();
+            return;
+        };
+        let Some(client_id) = environment.oauth_client_id(provider) else {
+            return;
+        };
+
+        let attempt = match OAuthAttempt::start(provider) {
+            Ok(attempt) => attempt,
+            Err(e) => {
+                self.login_status = LoginStatus::Error(e.to_string());
+                cx.notify();
+                return;
+            }
+        };
+        cx.open_url(&attempt.authorization_url(client_id));
+
+        let cancelled = Arc::new(AtomicBool::new(false));
+        self.oauth_cancel = Some(cancelled.clone());
+        self.login_status = LoginStatus::Loading;
+        cx.notify();
+
+        cx.spawn(async move |this, cx| {
+            let wait_cancelled = cancelled.clone();
+            let code = cx
+                .background_spawn(async move { attempt.wait(&wait_cancelled) })
+                .await;
+
+            let result = match code {
+                Ok(code) => {
+                    let client = AuthClient::new();
+                    Compat::new(client.login_oauth(&code, &environment)).await
+                }
+                Err(e) => Err(e),
+            };
+
+            cx.update(|cx| {
+                this.update(cx, |view, cx| {
+                    view.oauth_cancel = None;
+                    if cancelled.load(Ordering::Relaxed) {
+                        view.login_status = LoginStatus::Idle;
+                        cx.notify();
+                        return;
+                    }
+                    let method = SignInMethod::OAuth(provider);
+                    view.finish_login(result, environment, method, remember, cx);
+                })
+            })
+        })
+        .detach();
+    }
+
+    /// Stop waiting for the browser
+    fn cancel_oauth_login(&mut self, cx: &mut Context<Self>) {
+        if let Some(cancelled) = self.oauth_cancel.take() {
+            cancelled.store(true, Ordering::Relaxed);
+        }
+        cx.notify();
+    }
+
+    fn finish_login(
+        &mut self,
+        result: Result<LoginResponse, AuthError>,
+        environment: Environment,
+        method: SignInMethod,
+        remember: bool,
+        cx: &mut Context<Self>,
+    ) {
+        match result {
+            Ok(response) => {
+                tracing::info!(
+                    "Login successful! Access token: {}...",
+                    &response.access_token[..20.min(response.access_token.len())]
+                );
+                self.login_status = LoginStatus::Success;
+                // Emit event with tokens and environment for session creation
+                cx.emit(LoginSuccessEvent {
+                    login_response: response,
+                    environment,
+                    method,
+                    remember,
+                });
+            }
+            Err(AuthError::Http {
+                status: 401,
+                message,
+            }) => {
+                self.login_status = LoginStatus::Error(message);
+            }
+            Err(e) => {
+                self.login_status = LoginStatus::Error(e.to_string());
+            }
+        }
+        cx.notify();
+    }
+    /// Email, password and the sign-in buttons
+    fn render_credentials(
+        &self,
+        oauth_providers: Vec<OAuthProvider>,
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
         let username_state = self.username_state.clone();
         let password_state = self.password_state.clone();
         let is_loading = matches!(self.login_status, LoginStatus::Loading);
 
+        div()
+            .v_flex()
+            .gap_6()
+            // Email field
+            .child(
+                Input::new(&username_state)
+                    .bg(colors::bg_surface())
+                    .border_color(colors::border())
+                    .text_color(colors::text_primary()),
+            )
+            // Password field
+            .child(
+                Input::new(&password_state)
+                    .bg(colors::bg_surface())
+                    .border_color(colors::border())
+                    .text_color(colors::text_primary())
+                    .suffix(
+                        div()
+                            .id("password-toggle")
+                            .cursor_pointer()
+                            .child(
+                                Icon::new(if self.password_masked {
+                                    IconName::Eye
+                                } else {
+                                    IconName::EyeOff
+                                })
+                                .size(px(18.0))
+                                .text_color(colors::text_primary()),
+                            )
+                            .on_click(cx.listener(|this, _, window, cx| {
+                                this.password_masked = !this.password_masked;
+                                let masked = this.password_masked;
+                                this.password_state
+                                    .update(cx, |state: &mut InputState, cx| {
+                                        state.set_masked(masked, window, cx);
+                                    });
+                                cx.notify();
+                            })),
+                    ),
+            )
+            // Keep the refresh token in the OS secret store
+            .child(
+                Checkbox::new("remember-me")
+                    .label("Remember me")
+                    .checked(self.remember_me)
+                    .on_click(cx.listener(|this, checked: &bool, _window, cx| {
+                        this.remember_me = *checked;
+                        cx.notify();
+                    })),
+            )
+            // Error message
+            .when_some(
+                match &self.login_status {
+                    LoginStatus::Error(msg) => Some(msg.clone()),
+                    _ => None,
+                },
+                |this: Div, error_msg| {
+                    this.child(div().text_sm().text_color(colors::error()).child(error_msg))
+                },
+            )
+            // Success message
+            .when(
+                matches!(self.login_status, LoginStatus::Success),
+                |this: Div| {
+                    this.child(
+                        div()
+                            .text_sm()
+                            .text_color(rgb(0x22c55e))
+                            .child("Login successful!"),
+                    )
+                },
+            )
+            // Login button
+            .child(div().mt_2().child(gradient_button(
+                "login-btn",
+                if is_loading {
+                    "Logging in..."
+                } else {
+                    "Log In"
+                },
+                is_loading,
+                cx.listener(|this, _, _window, cx| {
+                    this.perform_login(cx);
+                }),
+            )))
+            // Browser sign-in for providers set up on this environment
+            .children(oauth_providers.into_iter().map(|provider| {
+                Button::new(SharedString::from(format!("oauth-{}", provider.path())))
+                    .outline()
+                    .w_full()
+                    .label(format!("Continue with {}", provider.label()))
+                    .disabled(is_loading)
+                    .on_click(cx.listener(move |this, _, _window, cx| {
+                        this.perform_oauth_login(provider, cx);
+                    }))
+            }))
+            .when(self.oauth_cancel.is_some(), |this| {
+                this.child(
+                    div()
+                        .h_flex()
+                        .gap_2()
+                        .items_center()
+                        .justify_between()
+                        .child(
+                            div()
+                                .text_sm()
+                                .text_color(colors::text_secondary())
+                                .child("Complete the sign-in in your browser"),
+                        )
+                        .child(
+                            Button::new("oauth-cancel")
+                                .ghost()
+                                .label("Cancel")
+                                .on_click(cx.listener(|this, _, _window, cx| {
+                                    this.cancel_oauth_login(cx);
+                                })),
+                        ),
+                )
+            })
+    }
+
+    /// Verification code entry with resend and back buttons
+    fn render_code_challenge(
+        &self,
+        challenge: &CodeChallenge,
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        let email = self.username_state.read(cx).value().to_string();
+        let resend_label = match challenge.resend_in(Instant::now()) {
+            _ if challenge.sending => "Sending...".to_string(),
+            Some(left) => format!("Resend code in {}s", left.as_secs() + 1),
+            None => "Resend code".to_string(),
+        };
+
+        div()
+            .v_flex()
+            .gap_6()
+            .child(
+                div()
+                    .text_sm()
+                    .text_color(colors::text_secondary())
+                    .child(format!("Enter the verification code sent to {}.", email)),
+            )
+            .child(
+                Input::new(&self.code_state)
+                    .bg(colors::bg_surface())
+                    .border_color(colors::border())
+                    .text_color(colors::text_primary()),
+            )
+            .when_some(challenge.error.clone(), |this, error| {
+                this.child(div().text_sm().text_color(colors::error()).child(error))
+            })
+            .child(div().mt_2().child(gradient_button(
+                "verify-code-btn",
+                if challenge.verifying {
+                    "Verifying..."
+                } else {
+                    "Verify"
+                },
+                challenge.verifying,
+                cx.listener(|this, _, _window, cx| {
+                    this.verify_code(cx);
+                }),
+            )))
+            .child(
+                div()
+                    .h_flex()
+                    .justify_between()
+                    .child(
+                        Button::new("code-back")
+                            .ghost()
+                            .icon(IconName::ArrowLeft)
+                            .label("Back")
+                            .disabled(challenge.verifying)
+                            .on_click(cx.listener(|this, _, _window, cx| {
+                                this.cancel_code_challenge(cx);
+                            })),
+                    )
+                    .child(
+                        Button::new("code-resend")
+                            .ghost()
+                            .label(resend_label)
+                            .disabled(!challenge.can_resend(Instant::now()))
+                            .on_click(cx.listener(|this, _, _window, cx| {
+                                this.send_code(cx);
+                            })),
+                    ),
+            )
+    }
+}
+
+impl Render for LoginView {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let challenge = match &self.login_status {
+            LoginStatus::CodeChallenge(challenge) => Some(challenge.clone()),
+            _ => None,
+        };
+
         // Build environment radio options
-        let radio_items = vec![
-            Radio::new("garage").label("Garage"),
-            Radio::new("studio").label("Studio"),
-        ];
+        let radio_items = self
+            .environments
+            .iter()
+            .enumerate()
+            .map(|(index, environment)| {
+                Radio::new(("env", index)).label(environment.display_name().to_string())
+            });
+        let selected = self.environments.get(self.selected_environment);
+        let oauth_providers: Vec<OAuthProvider> = OAuthProvider::ALL
+            .into_iter()
+            .filter(|provider| {
+                selected.is_some_and(|environment| environment.oauth_client_id(*provider).is_some())
+            })
+            .collect();
 
         div()
             .size_full()
@@ -223,87 +708,68 @@ impl Render for LoginView {
                             .child(
                                 RadioGroup::horizontal("env-selector")
                                     .selected_index(Some(self.selected_environment))
+                                    .disabled(challenge.is_some())
                                     .on_click(cx.listener(|this, index: &usize, _window, cx| {
                                         this.selected_environment = *index;
                                         cx.notify();
                                     }))
                                     .children(radio_items),
-                            ),
-                    )
-                    // Email field
-                    .child(
-                        Input::new(&username_state)
-                            .bg(colors::bg_surface())
-                            .border_color(colors::border())
-                            .text_color(colors::text_primary()),
-                    )
-                    // Password field
-                    .child(
-                        Input::new(&password_state)
-                            .bg(colors::bg_surface())
-                            .border_color(colors::border())
-                            .text_color(colors::text_primary())
-                            .suffix(
-                                div()
-                                    .id("password-toggle")
-                                    .cursor_pointer()
-                                    .child(
-                                        Icon::new(if self.password_masked {
-                                            IconName::Eye
-                                        } else {
-                                            IconName::EyeOff
-                                        })
-                                        .size(px(18.0))
-                                        .text_color(colors::text_primary()),
-                                    )
-                                    .on_click(cx.listener(|this, _, window, cx| {
-                                        this.password_masked = !this.password_masked;
-                                        let masked = this.password_masked;
-                                        this.password_state.update(
-                                            cx,
-                                            |state: &mut InputState, cx| {
-                                                state.set_masked(masked, window, cx);
-                                            },
-                                        );
-                                        cx.notify();
-                                    })),
-                            ),
-                    )
-                    // Error message
-                    .when_some(
-                        match &self.login_status {
-                            LoginStatus::Error(msg) => Some(msg.clone()),
-                            _ => None,
-                        },
-                        |this: Div, error_msg| {
-                            this.child(div().text_sm().text_color(colors::error()).child(error_msg))
-                        },
-                    )
-                    // Success message
-                    .when(
-                        matches!(self.login_status, LoginStatus::Success),
-                        |this: Div| {
-                            this.child(
-                                div()
-                                    .text_sm()
-                                    .text_color(rgb(0x22c55e))
-                                    .child("Login successful!"),
                             )
-                        },
+                            // Where the selected environment points
+                            .when_some(selected, |this, environment| {
+                                this.child(
+                                    div()
+                                        .h_flex()
+                                        .gap_2()
+                                        .items_center()
+                                        .text_xs()
+                                        .text_color(colors::text_muted())
+                                        .child(
+                                            div()
+                                                .size(px(8.0))
+                                                .rounded_full()
+                                                .bg(environment.color()),
+                                        )
+                                        .child(format!(
+                                            "{} ({})",
+                                            environment.base_url(),
+                                            environment.network()
+                                        )),
+                                )
+                            }),
                     )
-                    // Login button
-                    .child(div().mt_2().child(gradient_button(
-                        "login-btn",
-                        if is_loading {
-                            "Logging in..."
-                        } else {
-                            "Log In"
-                        },
-                        is_loading,
-                        cx.listener(|this, _, _window, cx| {
-                            this.perform_login(cx);
-                        }),
-                    ))),
+                    .map(|this| match &challenge {
+                        Some(challenge) => this.child(self.render_code_challenge(challenge, cx)),
+                        None => this.child(self.render_credentials(oauth_providers, cx)),
+                    }),
             )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{CodeChallenge, RESEND_COOLDOWN};
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn test_resend_cooldown() {
+        let now = Instant::now();
+        let challenge = CodeChallenge::default();
+        assert_eq!(challenge.resend_in(now), None);
+        assert!(challenge.can_resend(now));
+
+        let challenge = CodeChallenge {
+            sent_at: Some(now),
+            ..CodeChallenge::default()
+        };
+        assert_eq!(challenge.resend_in(now), Some(RESEND_COOLDOWN));
+        assert!(!challenge.can_resend(now + Duration::from_secs(59)));
+        assert!(challenge.can_resend(now + RESEND_COOLDOWN));
+
+        let challenge = CodeChallenge {
+            sending: true,
+            ..CodeChallenge::default()
+        };
+        assert!(!challenge.can_resend(now));
+    }
+}
@@ -1,2 +1,50 @@
+pub mod cardano;
 pub mod dashboard;
+pub mod encryption;
+pub mod keys;
+pub mod lock;
 pub mod login;
+pub mod reprocess;
+pub mod song_earnings;
+pub mod whitelist;
+
+/// Split free-form input into unique entries, ignoring case.
+///
+/// Accepts one entry per line, or entries separated by commas or whitespace.
+pub fn parse_list(text: &str) -> Vec<String> {
+    let mut entries: Vec<String> = Vec::new();
+    for entry in text
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|s| !s.is_empty())
+    {
+        if !entries
+            .iter()
+            .any(|existing| existing.eq_ignore_ascii_case(entry))
+        {
+            entries.push(entry.to_string());
+        }
+    }
+    entries
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_list;
+
+    #[test]
+    fn test_parse_list() {
+        let entries = parse_list(
+            "550e8400-e29b-41d4-a716-446655440000\n\
+             USRC17607839, usrc17607839\n\n\
+             550E8400-E29B-41D4-A716-446655440000  QZ-ABC-24-00001",
+        );
+        assert_eq!(
+            entries,
+            vec![
+                "550e8400-e29b-41d4-a716-446655440000",
+                "USRC17607839",
+                "QZ-ABC-24-00001"
+            ]
+        );
+    }
+}
@@ -0,0 +1,367 @@
+//! Minting Reprocess View
+//!
+//! Moves stuck songs back to a chosen minting status so the server picks
+//! them up again.
+
+use async_compat::Compat;
+use gpui::prelude::FluentBuilder;
+use gpui::*;
+use gpui_component::button::{Button, ButtonVariants};
+use gpui_component::input::*;
+use gpui_component::select::{Select, SelectItem, SelectState};
+use gpui_component::table::{Column, Table, TableDelegate, TableState};
+use gpui_component::*;
+
+use crate::colors;
+use crate::session::{Session, SessionExpiredEvent};
+use crate::songs::{MintingStatus, SongsClient, SongsError};
+use crate::toast;
+use crate::views::parse_list;
+
+impl SelectItem for MintingStatus {
+    type Value = MintingStatus;
+
+    fn title(&self) -> SharedString {
+        if self.is_error() {
+            format!("{} (error)", self.as_str()).into()
+        } else {
+            self.as_str().into()
+        }
+    }
+
+    fn value(&self) -> &Self::Value {
+        self
+    }
+}
+
+// -----------------------------------------------------------------------------
+// Results Table Delegate
+// -----------------------------------------------------------------------------
+
+/// Outcome of reprocessing a single song
+#[derive(Debug, Clone)]
+struct ReprocessResult {
+    song_id_or_isrc: String,
+    minting_status: MintingStatus,
+    success: bool,
+    result: String,
+}
+
+#[derive(Clone)]
+struct ReprocessTableDelegate {
+    results: Vec<ReprocessResult>,
+    columns: Vec<Column>,
+}
+
+impl ReprocessTableDelegate {
+    fn new() -> Self {
+        Self {
+            results: Vec::new(),
+            columns: vec![
+                Column::new("song", "Song ID or ISRC").width(px(320.)),
+                Column::new("status", "Target Status").width(px(300.)),
+                Column::new("result", "Result").width(px(600.)),
+            ],
+        }
+    }
+}
+
+impl TableDelegate for ReprocessTableDelegate {
+    fn columns_count(&self, _cx: &App) -> usize {
+        self.columns.len()
+    }
+
+    fn rows_count(&self, _cx: &App) -> usize {
+        self.results.len()
+    }
+
+    fn column(&self, col_ix: usize, _cx: &App) -> &Column {
+        &self.columns[col_ix]
+    }
+
+    fn render_td(
+        &mut self,
+        row_ix: usize,
+        col_ix: usize,
+        _window: &mut Window,
+        _cx: &mut Context<TableState<Self>>,
+    ) -> impl IntoElement {
+        let result = &self.results[row_ix];
+        match col_ix {
+            0 => div().child(result.song_id_or_isrc.clone()),
+            1 => div().child(result.minting_status.as_str()),
+            2 => div()
+                .text_color(if result.success {
+                    colors::success()
+                } else {
+                    colors::error()
+                })
+                .child(result.result.clone()),
+            _ => div(),
+        }
+    }
+}
+
+pub struct ReprocessView {
+    session: Option<Session>,
+    song_ids_input: Entity<InputState>,
+    status_select: Entity<SelectState<Vec<MintingStatus>>>,
+    is_submitting: bool,
+    progress: Option<(usize, usize)>, // (current, total)
+    form_error: Option<String>,
+    results_table: Entity<TableState<ReprocessTableDelegate>>,
+}
+
+impl ReprocessView {
+    pub fn new(window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let song_ids_input = cx.new(|cx| {
+            InputState::new(window, cx)
+                .multi_line(true)
+                .rows(6)
+                .placeholder("Song IDs or ISRCs, one per line")
+        });
+        let status_select = cx.new(|cx| {
+            SelectState::new(MintingStatus::ALL.to_vec(), None, window, cx).searchable(true)
+        });
+        let results_table = cx.new(|cx| TableState::new(ReprocessTableDelegate::new(), window, cx));
+
+        Self {
+            session: None,
+            song_ids_input,
+            status_select,
+            is_submitting: false,
+            progress: None,
+            form_error: None,
+            results_table,
+        }
+    }
+
+    /// Set the session (called from DashboardView after login)
+    pub fn set_session(&mut self, session: Option<Session>, cx: &mut Context<Self>) {
+        self.session = session;
+        cx.notify();
+    }
+
+    /// Submit a reprocess request for every entered song
+    fn submit(&mut self, cx: &mut Context<Self>) {
+        let song_ids = parse_list(&self.song_ids_input.read(cx).value());
+
+        self.form_error = None;
+
+        if song_ids.is_empty() {
+            self.form_error = Some("Please enter at least one Song ID or ISRC".to_string());
+            cx.notify();
+            return;
+        }
+
+        let Some(minting_status) = self.status_select.read(cx).selected_value().copied() else {
+            self.form_error = Some("Please select a target minting status".to_string());
+            cx.notify();
+            return;
+        };
+
+        let Some(session) = self.session.clone() else {
+            self.form_error = Some("No active session".to_string());
+            cx.notify();
+            return;
+        };
+
+        let total = song_ids.len();
+        self.is_submitting = true;
+        self.progress = Some((0, total));
+        self.results_table.update(cx, |table, cx| {
+            table.delegate_mut().results.clear();
+            cx.notify();
+        });
+        cx.notify();
+
+        cx.spawn(async move |this, cx| {
+            let client = SongsClient::new();
+            let mut failed = 0usize;
+
+            for (i, song_id_or_isrc) in song_ids.into_iter().enumerate() {
+                let result = Compat::new(async {
+                    let song_id = client.resolve_song_id(&session, &song_id_or_isrc).await?;
+                    client.reprocess(&session, &song_id, minting_status).await
+                })
+                .await;
+
+                let (success, message) = match result {
+                    Ok(()) => (true, "Accepted".to_string()),
+                    Err(SongsError::SessionExpired(msg)) => {
+                        // Session expired - abort processing
+                        cx.update(|cx| {
+                            this.update(cx, |view, cx| {
+                                view.is_submitting = false;
+                                view.progress = None;
+                                cx.emit(SessionExpiredEvent { message: msg });
+                                cx.notify();
+                            })
+                        })
+                        .ok();
+                        return;
+                    }
+                    Err(e) => {
+                        failed += 1;
+                        (false, format!("Error: {}", e))
+                    }
+                };
+
+                cx.update(|cx| {
+                    this.update(cx, |view, cx| {
+                        view.progress = Some((i + 1, total));
+                        view.results_table.update(cx, |table, cx| {
+                            table.delegate_mut().results.push(ReprocessResult {
+                                song_id_or_isrc,
+                                minting_status,
+                                success,
+                                result: message,
+                            });
+                            cx.notify();
+                        });
+                        cx.notify();
+                    })
+                })
+                .ok();
+            }
+
+            cx.update(|cx| {
+                this.update(cx, |view, cx| {
+                    view.is_submitting = false;
+                    view.progress = None;
+                    cx.notify();
+                })
+                .ok();
+
+                if failed > 0 {
+                    toast::show_warning_async(
+                        cx,
+                        format!(
+                            "Reprocessed {}/{} songs ({} failed)",
+                            total - failed,
+                            total,
+                            failed
+                        ),
+                    );
+                } else {
+                    toast::show_success_async(
+                        cx,
+                        format!("Reprocessed {} songs from {}", total, minting_status),
+                    );
+                }
+            })
+            .ok();
+        })
+        .detach();
+    }
+}
+
+impl Render for ReprocessView {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        div()
+            .v_flex()
+            .size_full()
+            .overflow_hidden()
+            .gap_6()
+            .child(
+                div()
+                    .text_2xl()
+                    .font_weight(FontWeight::BOLD)
+                    .text_color(colors::text_primary())
+                    .child("Reprocess Minting"),
+            )
+            .child(div().text_color(colors::text_secondary()).child(
+                "Move stuck songs back to a minting status so the server processes them again.",
+            ))
+            // Error message
+            .when_some(self.form_error.clone(), |this, error_msg| {
+                this.child(
+                    div()
+                        .text_sm()
+                        .text_color(colors::error())
+                        .p_2()
+                        .rounded(px(4.0))
+                        .bg(rgba(0xff000020))
+                        .child(error_msg),
+                )
+            })
+            .child(
+                div()
+                    .h_flex()
+                    .gap_6()
+                    .items_start()
+                    // Song IDs field
+                    .child(
+                        div()
+                            .v_flex()
+                            .gap_1()
+                            .w(px(500.0))
+                            .child(
+                                div()
+                                    .text_sm()
+                                    .font_weight(FontWeight::MEDIUM)
+                                    .text_color(colors::text_primary())
+                                    .child("Songs"),
+                            )
+                            .child(
+                                Input::new(&self.song_ids_input)
+                                    .bg(colors::bg_surface())
+                                    .border_color(colors::border())
+                                    .text_color(colors::text_primary()),
+                            ),
+                    )
+                    // Target status and submit
+                    .child(
+                        div()
+                            .v_flex()
+                            .gap_1()
+                            .w(px(360.0))
+                            .child(
+                                div()
+                                    .text_sm()
+                                    .font_weight(FontWeight::MEDIUM)
+                                    .text_color(colors::text_primary())
+                                    .child("Target Minting Status"),
+                            )
+                            .child(
+                                Select::new(&self.status_select)
+                                    .placeholder("Select a status")
+                                    .disabled(self.is_submitting),
+                            )
+                            .child(
+                                div().mt_4().child(
+                                    Button::new("submit-reprocess-btn")
+                                        .primary()
+                                        .label(if self.is_submitting {
+                                            if let Some((current, total)) = self.progress {
+                                                format!("Reprocessing {}/{}...", current, total)
+                                            } else {
+                                                "Reprocessing...".to_string()
+                                            }
+                                        } else {
+                                            "Reprocess".to_string()
+                                        })
+                                        .icon(Icon::new(IconName::Redo).size(px(16.0)))
+                                        .disabled(self.is_submitting)
+                                        .on_click(cx.listener(|this, _, _window, cx| {
+                                            this.submit(cx);
+                                        })),
+                                ),
+                            ),
+                    ),
+            )
+            // Results
+            .child(
+                div()
+                    .h_full()
+                    .w_full()
+                    .flex_1()
+                    .rounded_lg()
+                    .bg(colors::bg_surface())
+                    .border_1()
+                    .border_color(colors::border())
+                    .overflow_hidden()
+                    .child(Table::new(&self.results_table)),
+            )
+    }
+}
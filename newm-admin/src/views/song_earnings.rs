@@ -0,0 +1,553 @@
+//! Song Earnings Drill-down View
+//!
+//! Shows the earnings of a single song, fetched from the per-song admin
+//! endpoint: totals per stake address and the royalty batches over time.
+
+use async_compat::Compat;
+use gpui::prelude::FluentBuilder;
+use gpui::*;
+use gpui_component::button::{Button, ButtonVariants};
+use gpui_component::table::{Column, Table, TableDelegate, TableState};
+use gpui_component::*;
+
+use crate::colors;
+use crate::earnings::{Earning, EarningsClient, EarningsError, format_amount};
+use crate::session::{Session, SessionExpiredEvent};
+use crate::songs::SongsClient;
+use crate::toast;
+use crate::views::dashboard::{date_part, stat_card};
+
+/// Earnings of one song summed per stake address
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct StakeAddressTotals {
+    stake_address: String,
+    count: usize,
+    total: i64,
+    claimed: i64,
+}
+
+impl StakeAddressTotals {
+    fn unclaimed(&self) -> i64 {
+        self.total - self.claimed
+    }
+}
+
+/// Earnings created together by one royalty submission
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct RoyaltyBatch {
+    created_at: String,
+    memo: String,
+    count: usize,
+    total: i64,
+    claimed: usize,
+}
+
+/// Sum earnings per stake address, largest total first
+fn totals_by_stake_address(earnings: &[Earning]) -> Vec<StakeAddressTotals> {
+    let mut totals: Vec<StakeAddressTotals> = Vec::new();
+    for earning in earnings {
+        let claimed = if earning.claimed { earning.amount } else { 0 };
+        match totals
+            .iter_mut()
+            .find(|t| t.stake_address == earning.stake_address)
+        {
+            Some(entry) => {
+                entry.count += 1;
+                entry.total += earning.amount;
+                entry.claimed += claimed;
+            }
+            None => totals.push(StakeAddressTotals {
+                stake_address: earning.stake_address.clone(),
+                count: 1,
+                total: earning.amount,
+                claimed,
+            }),
+        }
+    }
+    totals.sort_by_key(|t| std::cmp::Reverse(t.total));
+    totals
+}
+
+/// Group earnings into royalty batches, newest first.
+///
+/// The server stamps every split of one royalty submission with the same
+/// `createdAt`, so that timestamp identifies the batch.
+fn royalty_batches(earnings: &[Earning]) -> Vec<RoyaltyBatch> {
+    let mut batches: Vec<RoyaltyBatch> = Vec::new();
+    for earning in earnings {
+        let claimed = usize::from(earning.claimed);
+        match batches
+            .iter_mut()
+            .find(|b| b.created_at == earning.created_at)
+        {
+            Some(batch) => {
+                batch.count += 1;
+                batch.total += earning.amount;
+                batch.claimed += claimed;
+            }
+            None => batches.push(RoyaltyBatch {
+                created_at: earning.created_at.clone(),
+                memo: earning.memo.clone().unwrap_or_default(),
+                count: 1,
+                total: earning.amount,
+                claimed,
+            }),
+        }
+    }
+    batches.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+    batches
+}
+
+// -----------------------------------------------------------------------------
+// Table Delegates
+// -----------------------------------------------------------------------------
+
+#[derive(Clone)]
+struct StakeTotalsTableDelegate {
+    rows: Vec<StakeAddressTotals>,
+    columns: Vec<Column>,
+}
+
+impl StakeTotalsTableDelegate {
+    fn new() -> Self {
+        Self {
+            rows: Vec::new(),
+            columns: vec![
+                Column::new("stake", "Stake Address").width(px(480.)),
+                Column::new("count", "Earnings").width(px(100.)),
+                Column::new("total", "Total").width(px(180.)),
+                Column::new("claimed", "Claimed").width(px(180.)),
+                Column::new("unclaimed", "Unclaimed").width(px(180.)),
+            ],
+        }
+    }
+}
+
+impl TableDelegate for StakeTotalsTableDelegate {
+    fn columns_count(&self, _cx: &App) -> usize {
+        self.columns.len()
+    }
+
+    fn rows_count(&self, _cx: &App) -> usize {
+        self.rows.len()
+    }
+
+    fn column(&self, col_ix: usize, _cx: &App) -> &Column {
+        &self.columns[col_ix]
+    }
+
+    fn render_td(
+        &mut self,
+        row_ix: usize,
+        col_ix: usize,
+        _window: &mut Window,
+        _cx: &mut Context<TableState<Self>>,
+    ) -> impl IntoElement {
+        let row = &self.rows[row_ix];
+        match col_ix {
+            0 => div().child(row.stake_address.clone()),
+            1 => div().child(row.count.to_string()),
+            2 => div().child(format!("Ɲ {}", format_amount(row.total))),
+            3 => div().child(format!("Ɲ {}", format_amount(row.claimed))),
+            4 => div().child(format!("Ɲ {}", format_amount(row.unclaimed()))),
+            _ => div(),
+        }
+    }
+}
+
+#[derive(Clone)]
+struct BatchesTableDelegate {
+    rows: Vec<RoyaltyBatch>,
+    columns: Vec<Column>,
+}
+
+impl BatchesTableDelegate {
+    fn new() -> Self {
+        Self {
+            rows: Vec::new(),
+            columns: vec![
+                Column::new("created_at", "Created At").width(px(180.)),
+                Column::new("memo", "Memo").width(px(480.)),
+                Column::new("count", "Splits").width(px(100.)),
+                Column::new("total", "Total").width(px(180.)),
+                Column::new("claimed", "Claimed").width(px(120.)),
+            ],
+        }
+    }
+}
+
+impl TableDelegate for BatchesTableDelegate {
+    fn columns_count(&self, _cx: &App) -> usize {
+        self.columns.len()
+    }
+
+    fn rows_count(&self, _cx: &App) -> usize {
+        self.rows.len()
+    }
+
+    fn column(&self, col_ix: usize, _cx: &App) -> &Column {
+        &self.columns[col_ix]
+    }
+
+    fn render_td(
+        &mut self,
+        row_ix: usize,
+        col_ix: usize,
+        _window: &mut Window,
+        _cx: &mut Context<TableState<Self>>,
+    ) -> impl IntoElement {
+        let row = &self.rows[row_ix];
+        match col_ix {
+            0 => div().child(format!(
+                "{} {}",
+                date_part(&row.created_at),
+                row.created_at
+                    .split('T')
+                    .nth(1)
+                    .and_then(|time| time.get(..5))
+                    .unwrap_or("")
+            )),
+            1 => div()
+                .overflow_hidden()
+                .whitespace_nowrap()
+                .text_ellipsis()
+                .child(row.memo.clone()),
+            2 => div().child(row.count.to_string()),
+            3 => div().child(format!("Ɲ {}", format_amount(row.total))),
+            4 => div().child(format!("{}/{}", row.claimed, row.count)),
+            _ => div(),
+        }
+    }
+}
+
+// -----------------------------------------------------------------------------
+// View
+// -----------------------------------------------------------------------------
+
+pub struct SongEarningsView {
+    session: Option<Session>,
+    /// Song ID or ISRC the view was opened with
+    song_id_or_isrc: String,
+    song_title: Option<String>,
+    earnings: Vec<Earning>,
+    is_loading: bool,
+    error: Option<String>,
+    stake_table: Entity<TableState<StakeTotalsTableDelegate>>,
+    batches_table: Entity<TableState<BatchesTableDelegate>>,
+}
+
+impl SongEarningsView {
+    pub fn new(
+        song_id_or_isrc: String,
+        session: Option<Session>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let stake_table = cx.new(|cx| TableState::new(StakeTotalsTableDelegate::new(), window, cx));
+        let batches_table = cx.new(|cx| TableState::new(BatchesTableDelegate::new(), window, cx));
+
+        let mut view = Self {
+            session,
+            song_id_or_isrc,
+            song_title: None,
+            earnings: Vec::new(),
+            is_loading: false,
+            error: None,
+            stake_table,
+            batches_table,
+        };
+        view.fetch(cx);
+        view
+    }
+
+    /// Fetch the song's earnings (and its title, best effort)
+    fn fetch(&mut self, cx: &mut Context<Self>) {
+        let Some(session) = self.session.clone() else {
+            self.error = Some("No active session".to_string());
+            cx.notify();
+            return;
+        };
+
+        self.is_loading = true;
+        self.error = None;
+        cx.notify();
+
+        let song_id_or_isrc = self.song_id_or_isrc.clone();
+        cx.spawn(async move |this, cx| {
+            let result = Compat::new(async {
+                EarningsClient::new()
+                    .get_song_earnings(&session, &song_id_or_isrc)
+                    .await
+            })
+            .await;
+
+            // The earnings carry the resolved song ID, which also covers ISRC lookups
+            let song_id = result
+                .as_ref()
+                .ok()
+                .and_then(|earnings| earnings.iter().find_map(|e| e.song_id.clone()));
+            let song_title = match song_id {
+                Some(song_id) => {
+                    Compat::new(async { SongsClient::new().get_song(&session, &song_id).await })
+                        .await
+                        .ok()
+                        .and_then(|song| song.title)
+                }
+                None => None,
+            };
+
+            cx.update(|cx| {
+                this.update(cx, |view, cx| {
+                    view.is_loading = false;
+                    match result {
+                        Ok(earnings) => {
+                            view.song_title = song_title;
+                            view.set_earnings(earnings, cx);
+                        }
+                        Err(EarningsError::SessionExpired(msg)) => {
+                            cx.emit(SessionExpiredEvent { message: msg });
+                        }
+                        Err(e) => {
+                            tracing::warn!("Failed to fetch song earnings: {}", e);
+                            view.error = Some(e.to_string());
+                        }
+                    }
+                    cx.notify();
+                })
+            })
+        })
+        .detach();
+    }
+
+    fn set_earnings(&mut self, earnings: Vec<Earning>, cx: &mut Context<Self>) {
+        let totals = totals_by_stake_address(&earnings);
+        let batches = royalty_batches(&earnings);
+        self.earnings = earnings;
+
+        self.stake_table.update(cx, |table, cx| {
+            table.delegate_mut().rows = totals;
+            cx.notify();
+        });
+        self.batches_table.update(cx, |table, cx| {
+            table.delegate_mut().rows = batches;
+            cx.notify();
+        });
+    }
+
+    fn section(title: &str, table: impl IntoElement) -> impl IntoElement {
+        div()
+            .v_flex()
+            .flex_1()
+            .gap_2()
+            .min_h(px(160.0))
+            .child(
+                div()
+                    .text_lg()
+                    .font_weight(FontWeight::SEMIBOLD)
+                    .text_color(colors::text_primary())
+                    .child(title.to_string()),
+            )
+            .child(
+                div()
+                    .flex_1()
+                    .w_full()
+                    .rounded_lg()
+                    .bg(colors::bg_surface())
+                    .border_1()
+                    .border_color(colors::border())
+                    .overflow_hidden()
+                    .child(table),
+            )
+    }
+}
+
+impl Render for SongEarningsView {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let total: i64 = self.earnings.iter().map(|e| e.amount).sum();
+        let claimed: i64 = self
+            .earnings
+            .iter()
+            .filter(|e| e.claimed)
+            .map(|e| e.amount)
+            .sum();
+        let stake_count = self.stake_table.read(cx).delegate().rows.len();
+        let song_id = self
+            .earnings
+            .iter()
+            .find_map(|e| e.song_id.clone())
+            .unwrap_or_else(|| self.song_id_or_isrc.clone());
+
+        div()
+            .v_flex()
+            .size_full()
+            .overflow_hidden()
+            .gap_6()
+            // Header
+            .child(
+                div()
+                    .h_flex()
+                    .items_center()
+                    .justify_between()
+                    .child(
+                        div()
+                            .h_flex()
+                            .gap_4()
+                            .items_center()
+                            .child(
+                                Button::new("back-to-earnings-btn")
+                                    .icon(Icon::new(IconName::ArrowLeft).size(px(16.0)))
+                                    .tooltip("Back to all earnings")
+                                    .ghost()
+                                    .on_click(cx.listener(|_, _, _window, cx| {
+                                        cx.emit(DismissEvent);
+                                    })),
+                            )
+                            .child(
+                                div()
+                                    .v_flex()
+                                    .child(
+                                        div()
+                                            .text_2xl()
+                                            .font_weight(FontWeight::BOLD)
+                                            .text_color(colors::text_primary())
+                                            .child(
+                                                self.song_title
+                                                    .clone()
+                                                    .unwrap_or_else(|| "Song Earnings".to_string()),
+                                            ),
+                                    )
+                                    .child(
+                                        div()
+                                            .id("copy-drilldown-song-id")
+                                            .text_sm()
+                                            .text_color(colors::text_secondary())
+                                            .cursor_pointer()
+                                            .child(song_id.clone())
+                                            .on_click(cx.listener(move |_, _, window, cx| {
+                                                cx.write_to_clipboard(ClipboardItem::new_string(
+                                                    song_id.clone(),
+                                                ));
+                                                toast::show_info(
+                                                    window,
+                                                    cx,
+                                                    "Song ID copied to clipboard",
+                                                );
+                                            })),
+                                    ),
+                            ),
+                    )
+                    .child(
+                        Button::new("refresh-song-earnings-btn")
+                            .label(if self.is_loading {
+                                "Loading..."
+                            } else {
+                                "Refresh"
+                            })
+                            .ghost()
+                            .disabled(self.is_loading)
+                            .on_click(cx.listener(|this, _, _window, cx| {
+                                this.fetch(cx);
+                            })),
+                    ),
+            )
+            // Error message
+            .when_some(self.error.clone(), |this, error_msg| {
+                this.child(
+                    div()
+                        .text_sm()
+                        .text_color(colors::error())
+                        .p_2()
+                        .rounded(px(4.0))
+                        .bg(rgba(0xff000020))
+                        .child(error_msg),
+                )
+            })
+            // Summary cards
+            .child(
+                div()
+                    .h_flex()
+                    .gap_4()
+                    .child(stat_card(
+                        "Total Earnings",
+                        format!("Ɲ {}", format_amount(total)),
+                        colors::success(),
+                    ))
+                    .child(stat_card(
+                        "Claimed",
+                        format!("Ɲ {}", format_amount(claimed)),
+                        colors::text_primary(),
+                    ))
+                    .child(stat_card(
+                        "Unclaimed",
+                        format!("Ɲ {}", format_amount(total - claimed)),
+                        colors::text_secondary(),
+                    ))
+                    .child(stat_card(
+                        "Stake Addresses",
+                        stake_count.to_string(),
+                        colors::text_primary(),
+                    )),
+            )
+            .child(Self::section(
+                "By Stake Address",
+                Table::new(&self.stake_table),
+            ))
+            .child(Self::section(
+                "Royalty Batches",
+                Table::new(&self.batches_table),
+            ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{royalty_batches, totals_by_stake_address};
+    use crate::earnings::Earning;
+
+    fn earning(stake: &str, amount: i64, claimed: bool, created_at: &str) -> Earning {
+        serde_json::from_value(serde_json::json!({
+            "songId": "550e8400-e29b-41d4-a716-446655440000",
+            "stakeAddress": stake,
+            "amount": amount,
+            "memo": format!("Royalty {}", created_at),
+            "claimed": claimed,
+            "createdAt": created_at
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_totals_by_stake_address() {
+        let earnings = vec![
+            earning("stake1a", 10, true, "2025-01-01T00:00:00"),
+            earning("stake1b", 50, false, "2025-01-01T00:00:00"),
+            earning("stake1a", 5, false, "2025-02-01T00:00:00"),
+        ];
+        let totals = totals_by_stake_address(&earnings);
+        assert_eq!(totals.len(), 2);
+        assert_eq!(totals[0].stake_address, "stake1b");
+        assert_eq!(totals[1].stake_address, "stake1a");
+        assert_eq!(totals[1].count, 2);
+        assert_eq!(totals[1].total, 15);
+        assert_eq!(totals[1].claimed, 10);
+        assert_eq!(totals[1].unclaimed(), 5);
+    }
+
+    #[test]
+    fn test_royalty_batches() {
+        let earnings = vec![
+            earning("stake1a", 10, true, "2025-01-01T00:00:00.1"),
+            earning("stake1b", 30, false, "2025-01-01T00:00:00.1"),
+            earning("stake1a", 5, false, "2025-02-01T00:00:00.2"),
+        ];
+        let batches = royalty_batches(&earnings);
+        assert_eq!(batches.len(), 2);
+        // Newest first
+        assert_eq!(batches[0].created_at, "2025-02-01T00:00:00.2");
+        assert_eq!(batches[0].total, 5);
+        assert_eq!(batches[1].count, 2);
+        assert_eq!(batches[1].total, 40);
+        assert_eq!(batches[1].claimed, 1);
+        assert_eq!(batches[1].memo, "Royalty 2025-01-01T00:00:00.1");
+    }
+}
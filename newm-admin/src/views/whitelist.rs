@@ -0,0 +1,521 @@
+//! Script Address Whitelist View
+//!
+//! Adds script addresses to the server's claim whitelist, previewing the
+//! stake address the server will whitelist for each one, and shows what has
+//! been whitelisted from this machine.
+
+use async_compat::Compat;
+use gpui::prelude::FluentBuilder;
+use gpui::*;
+use gpui_component::button::{Button, ButtonVariants};
+use gpui_component::input::*;
+use gpui_component::table::{Column, Table, TableDelegate, TableState};
+use gpui_component::*;
+
+use crate::cardano::Network;
+use crate::cardano_admin::{
+    CardanoAdminClient, CardanoAdminError, WhitelistHistory, WhitelistRecord, WhitelistTarget,
+};
+use crate::colors;
+use crate::session::{Session, SessionExpiredEvent};
+use crate::toast;
+use crate::views::cardano::{environment_label, section_title};
+use crate::views::parse_list;
+
+// -----------------------------------------------------------------------------
+// Whitelist Table Delegate
+// -----------------------------------------------------------------------------
+
+/// State of an entered address
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum WhitelistStatus {
+    /// Failed local validation
+    Invalid(String),
+    /// Valid and waiting to be submitted
+    Ready,
+    /// Accepted by the server
+    Whitelisted,
+    /// Rejected by the server
+    Failed(String),
+}
+
+impl WhitelistStatus {
+    fn label(&self) -> String {
+        match self {
+            WhitelistStatus::Invalid(msg) => msg.clone(),
+            WhitelistStatus::Ready => "Ready".to_string(),
+            WhitelistStatus::Whitelisted => "Whitelisted".to_string(),
+            WhitelistStatus::Failed(msg) => format!("Error: {}", msg),
+        }
+    }
+
+    fn color(&self) -> Rgba {
+        match self {
+            WhitelistStatus::Invalid(_) | WhitelistStatus::Failed(_) => colors::error(),
+            WhitelistStatus::Ready => colors::text_secondary(),
+            WhitelistStatus::Whitelisted => colors::success(),
+        }
+    }
+}
+
+/// An entered address with its preview and outcome
+#[derive(Debug, Clone)]
+struct WhitelistRow {
+    address: String,
+    stake_address: Option<String>,
+    status: WhitelistStatus,
+}
+
+impl WhitelistRow {
+    /// Validate an address for `network`
+    fn new(address: String, network: Network) -> Self {
+        match WhitelistTarget::resolve(&address, network) {
+            Ok(target) => Self {
+                address,
+                stake_address: Some(target.stake_address.to_string()),
+                status: WhitelistStatus::Ready,
+            },
+            Err(e) => Self {
+                address,
+                stake_address: None,
+                status: WhitelistStatus::Invalid(e.to_string()),
+            },
+        }
+    }
+}
+
+#[derive(Clone)]
+struct WhitelistTableDelegate {
+    rows: Vec<WhitelistRow>,
+    columns: Vec<Column>,
+}
+
+impl WhitelistTableDelegate {
+    fn new() -> Self {
+        Self {
+            rows: Vec::new(),
+            columns: vec![
+                Column::new("address", "Address").width(px(560.)),
+                Column::new("stake_address", "Stake Address Whitelisted").width(px(480.)),
+                Column::new("status", "Status").width(px(400.)),
+            ],
+        }
+    }
+}
+
+impl TableDelegate for WhitelistTableDelegate {
+    fn columns_count(&self, _cx: &App) -> usize {
+        self.columns.len()
+    }
+
+    fn rows_count(&self, _cx: &App) -> usize {
+        self.rows.len()
+    }
+
+    fn column(&self, col_ix: usize, _cx: &App) -> &Column {
+        &self.columns[col_ix]
+    }
+
+    fn render_td(
+        &mut self,
+        row_ix: usize,
+        col_ix: usize,
+        _window: &mut Window,
+        _cx: &mut Context<TableState<Self>>,
+    ) -> impl IntoElement {
+        let row = &self.rows[row_ix];
+        match col_ix {
+            0 => div().child(row.address.clone()),
+            1 => div().child(row.stake_address.clone().unwrap_or_else(|| "-".to_string())),
+            2 => div()
+                .text_color(row.status.color())
+                .child(row.status.label()),
+            _ => div(),
+        }
+    }
+}
+
+// -----------------------------------------------------------------------------
+// History Table Delegate
+// -----------------------------------------------------------------------------
+
+#[derive(Clone)]
+struct HistoryTableDelegate {
+    records: Vec<WhitelistRecord>,
+    columns: Vec<Column>,
+}
+
+impl HistoryTableDelegate {
+    fn new() -> Self {
+        Self {
+            records: Vec::new(),
+            columns: vec![
+                Column::new("address", "Address").width(px(560.)),
+                Column::new("stake_address", "Stake Address").width(px(480.)),
+                Column::new("whitelisted_at", "Whitelisted At").width(px(200.)),
+            ],
+        }
+    }
+}
+
+impl TableDelegate for HistoryTableDelegate {
+    fn columns_count(&self, _cx: &App) -> usize {
+        self.columns.len()
+    }
+
+    fn rows_count(&self, _cx: &App) -> usize {
+        self.records.len()
+    }
+
+    fn column(&self, col_ix: usize, _cx: &App) -> &Column {
+        &self.columns[col_ix]
+    }
+
+    fn render_td(
+        &mut self,
+        row_ix: usize,
+        col_ix: usize,
+        _window: &mut Window,
+        _cx: &mut Context<TableState<Self>>,
+    ) -> impl IntoElement {
+        let record = &self.records[row_ix];
+        match col_ix {
+            0 => div().child(record.address.clone()),
+            1 => div().child(record.stake_address.clone()),
+            2 => div().child(record.whitelisted_at.clone()),
+            _ => div(),
+        }
+    }
+}
+
+pub struct WhitelistView {
+    session: Option<Session>,
+    addresses_input: Entity<InputState>,
+    is_submitting: bool,
+    progress: Option<(usize, usize)>, // (current, total)
+    form_error: Option<String>,
+    whitelist_table: Entity<TableState<WhitelistTableDelegate>>,
+    history: WhitelistHistory,
+    history_table: Entity<TableState<HistoryTableDelegate>>,
+}
+
+impl WhitelistView {
+    pub fn new(window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let addresses_input = cx.new(|cx| {
+            InputState::new(window, cx)
+                .multi_line(true)
+                .rows(6)
+                .placeholder("Script or stake addresses, one per line")
+        });
+        let whitelist_table =
+            cx.new(|cx| TableState::new(WhitelistTableDelegate::new(), window, cx));
+        let history_table = cx.new(|cx| TableState::new(HistoryTableDelegate::new(), window, cx));
+
+        // Re-validate as the user types
+        cx.observe(&addresses_input, |this: &mut Self, _, cx| {
+            if !this.is_submitting {
+                this.update_preview(cx);
+            }
+        })
+        .detach();
+
+        Self {
+            session: None,
+            addresses_input,
+            is_submitting: false,
+            progress: None,
+            form_error: None,
+            whitelist_table,
+            history: WhitelistHistory::load(),
+            history_table,
+        }
+    }
+
+    /// Set the session (called from DashboardView after login)
+    pub fn set_session(&mut self, session: Option<Session>, cx: &mut Context<Self>) {
+        self.session = session;
+        self.update_preview(cx);
+        self.update_history(cx);
+        cx.notify();
+    }
+
+    /// Validate the entered addresses against the session's network
+    fn update_preview(&mut self, cx: &mut Context<Self>) {
+        let rows = match &self.session {
+            Some(session) => {
+                let network = session.environment().network();
+                parse_list(&self.addresses_input.read(cx).value())
+                    .into_iter()
+                    .map(|address| WhitelistRow::new(address, network))
+                    .collect()
+            }
+            None => Vec::new(),
+        };
+        self.whitelist_table.update(cx, |table, cx| {
+            table.delegate_mut().rows = rows;
+            cx.notify();
+        });
+        cx.notify();
+    }
+
+    /// Show the history for the session's environment
+    fn update_history(&mut self, cx: &mut Context<Self>) {
+        let records = match &self.session {
+            Some(session) => self.history.records(session.environment()).to_vec(),
+            None => Vec::new(),
+        };
+        self.history_table.update(cx, |table, cx| {
+            table.delegate_mut().records = records;
+            cx.notify();
+        });
+    }
+
+    /// Whitelist every entered address
+    fn submit(&mut self, cx: &mut Context<Self>) {
+        self.form_error = None;
+        self.update_preview(cx);
+
+        let Some(session) = self.session.clone() else {
+            self.form_error = Some("No active session".to_string());
+            cx.notify();
+            return;
+        };
+
+        let rows = self.whitelist_table.read(cx).delegate().rows.clone();
+        if rows.is_empty() {
+            self.form_error = Some("Please enter at least one address".to_string());
+            cx.notify();
+            return;
+        }
+        let invalid = rows
+            .iter()
+            .filter(|row| matches!(row.status, WhitelistStatus::Invalid(_)))
+            .count();
+        if invalid > 0 {
+            self.form_error = Some(format!(
+                "{} address(es) are invalid for {} - fix them before submitting",
+                invalid,
+                session.environment().display_name()
+            ));
+            cx.notify();
+            return;
+        }
+
+        let total = rows.len();
+        self.is_submitting = true;
+        self.progress = Some((0, total));
+        cx.notify();
+
+        cx.spawn(async move |this, cx| {
+            let client = CardanoAdminClient::new();
+            let environment = session.environment();
+            let mut failed = 0usize;
+
+            for (i, row) in rows.into_iter().enumerate() {
+                let result =
+                    Compat::new(client.whitelist_script_address(&session, &row.address)).await;
+
+                let status = match result {
+                    Ok(target) => {
+                        let record = WhitelistRecord {
+                            address: target.address,
+                            stake_address: target.stake_address.to_string(),
+                            whitelisted_at: chrono::Local::now()
+                                .format("%Y-%m-%d %H:%M:%S")
+                                .to_string(),
+                        };
+                        cx.update(|cx| {
+                            this.update(cx, |view, cx| {
+                                view.history.add(environment, record);
+                                if let Err(e) = view.history.save() {
+                                    tracing::warn!("Failed to save whitelist history: {}", e);
+                                }
+                                view.update_history(cx);
+                            })
+                        })
+                        .ok();
+                        WhitelistStatus::Whitelisted
+                    }
+                    Err(CardanoAdminError::SessionExpired(msg)) => {
+                        // Session expired - abort processing
+                        cx.update(|cx| {
+                            this.update(cx, |view, cx| {
+                                view.is_submitting = false;
+                                view.progress = None;
+                                cx.emit(SessionExpiredEvent { message: msg });
+                                cx.notify();
+                            })
+                        })
+                        .ok();
+                        return;
+                    }
+                    Err(e) => {
+                        failed += 1;
+                        WhitelistStatus::Failed(e.to_string())
+                    }
+                };
+
+                cx.update(|cx| {
+                    this.update(cx, |view, cx| {
+                        view.progress = Some((i + 1, total));
+                        view.whitelist_table.update(cx, |table, cx| {
+                            if let Some(row) = table.delegate_mut().rows.get_mut(i) {
+                                row.status = status;
+                            }
+                            cx.notify();
+                        });
+                        cx.notify();
+                    })
+                })
+                .ok();
+            }
+
+            cx.update(|cx| {
+                this.update(cx, |view, cx| {
+                    view.is_submitting = false;
+                    view.progress = None;
+                    cx.notify();
+                })
+                .ok();
+
+                if failed > 0 {
+                    toast::show_warning_async(
+                        cx,
+                        format!(
+                            "Whitelisted {}/{} addresses ({} failed)",
+                            total - failed,
+                            total,
+                            failed
+                        ),
+                    );
+                } else {
+                    toast::show_success_async(cx, format!("Whitelisted {} addresses", total));
+                }
+            })
+            .ok();
+        })
+        .detach();
+    }
+}
+
+impl Render for WhitelistView {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let environment = environment_label(self.session.as_ref());
+
+        div()
+            .v_flex()
+            .size_full()
+            .overflow_hidden()
+            .gap_6()
+            .child(div().text_color(colors::text_secondary()).child(
+                "Allow marketplace and other script addresses to claim earnings. For a script \
+                 address the server also whitelists its stake address.",
+            ))
+            // Error message
+            .when_some(self.form_error.clone(), |this, error_msg| {
+                this.child(
+                    div()
+                        .text_sm()
+                        .text_color(colors::error())
+                        .p_2()
+                        .rounded(px(4.0))
+                        .bg(rgba(0xff000020))
+                        .child(error_msg),
+                )
+            })
+            .child(
+                div()
+                    .h_flex()
+                    .gap_6()
+                    .items_start()
+                    // Addresses field
+                    .child(
+                        div()
+                            .v_flex()
+                            .gap_1()
+                            .w(px(700.0))
+                            .child(
+                                div()
+                                    .text_sm()
+                                    .font_weight(FontWeight::MEDIUM)
+                                    .text_color(colors::text_primary())
+                                    .child(format!("Addresses - {}", environment)),
+                            )
+                            .child(
+                                Input::new(&self.addresses_input)
+                                    .bg(colors::bg_surface())
+                                    .border_color(colors::border())
+                                    .text_color(colors::text_primary()),
+                            ),
+                    )
+                    .child(
+                        div().mt_6().child(
+                            Button::new("submit-whitelist-btn")
+                                .primary()
+                                .label(if self.is_submitting {
+                                    if let Some((current, total)) = self.progress {
+                                        format!("Whitelisting {}/{}...", current, total)
+                                    } else {
+                                        "Whitelisting...".to_string()
+                                    }
+                                } else {
+                                    "Whitelist".to_string()
+                                })
+                                .icon(Icon::new(IconName::Check).size(px(16.0)))
+                                .disabled(self.is_submitting)
+                                .on_click(cx.listener(|this, _, _window, cx| {
+                                    this.submit(cx);
+                                })),
+                        ),
+                    ),
+            )
+            // Preview and results
+            .child(
+                div()
+                    .h(px(240.0))
+                    .w_full()
+                    .rounded_lg()
+                    .bg(colors::bg_surface())
+                    .border_1()
+                    .border_color(colors::border())
+                    .overflow_hidden()
+                    .child(Table::new(&self.whitelist_table)),
+            )
+            .child(section_title("Whitelisted From This Machine"))
+            .child(
+                div()
+                    .h_full()
+                    .w_full()
+                    .flex_1()
+                    .rounded_lg()
+                    .bg(colors::bg_surface())
+                    .border_1()
+                    .border_color(colors::border())
+                    .overflow_hidden()
+                    .child(Table::new(&self.history_table)),
+            )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Network, WhitelistRow, WhitelistStatus};
+
+    #[test]
+    fn test_row_previews_stake_address() {
+        let row = WhitelistRow::new(
+            "addr1z8phkx6acpnf78fuvxn0mkew3l0fd058hzquvz7w36x4gten0d3vllmyqwsx5wktcd8cc3sq835lu7drv2xwl2wywfgs9yc0hh"
+                .to_string(),
+            Network::Mainnet,
+        );
+        assert_eq!(row.status, WhitelistStatus::Ready);
+        assert_eq!(
+            row.stake_address.as_deref(),
+            Some("stake1uyehkck0lajq8gr28t9uxnuvgcqrc6070x3k9r8048z8y5gh6ffgw")
+        );
+
+        let row = WhitelistRow::new("addr1abc".to_string(), Network::Mainnet);
+        assert!(matches!(row.status, WhitelistStatus::Invalid(_)));
+        assert!(row.stake_address.is_none());
+    }
+}
@@ -1,5 +1,6 @@
 package io.newm.server.auth.jwt
 
+import io.ktor.http.HttpStatusCode
 import io.ktor.server.auth.authenticate
 import io.ktor.server.response.respond
 import io.ktor.server.routing.Routing
@@ -11,6 +12,7 @@ import io.newm.server.ktx.jwtId
 import io.newm.server.ktx.jwtPrincipal
 import io.newm.server.ktx.myUserId
 import io.newm.shared.koin.inject
+import io.newm.shared.ktx.delete
 import io.newm.shared.ktx.get
 
 fun Routing.createJwtRoutes() {
@@ -31,6 +33,10 @@ fun Routing.createJwtRoutes() {
                 )
             }
         }
+        delete("$AUTH_PATH/jwt") {
+            jwtId?.let { jwtRepository.blackList(it) }
+            respond(HttpStatusCode.NoContent)
+        }
     }
     authenticate(AUTH_JWT_REFRESH) {
         get("$AUTH_PATH/refresh") {
@@ -38,5 +44,9 @@ fun Routing.createJwtRoutes() {
             val admin = userRepository.isAdmin(myUserId)
             respond(jwtRepository.createLoginResponse(myUserId, admin))
         }
+        delete("$AUTH_PATH/refresh") {
+            jwtId?.let { jwtRepository.blackList(it) }
+            respond(HttpStatusCode.NoContent)
+        }
     }
 }
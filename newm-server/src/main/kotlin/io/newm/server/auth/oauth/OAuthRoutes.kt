@@ -1,5 +1,6 @@
 package io.newm.server.auth.oauth
 
+import io.ktor.server.plugins.origin
 import io.ktor.server.request.receive
 import io.ktor.server.response.respond
 import io.ktor.server.routing.Routing
@@ -9,11 +10,15 @@ import io.newm.server.auth.oauth.model.OAuthLoginRequest
 import io.newm.server.auth.oauth.model.OAuthType
 import io.newm.server.auth.oauth.repo.OAuthRepository
 import io.newm.server.auth.password.createLoginResponse
+import io.newm.server.config.repo.ConfigRepository
+import io.newm.server.config.repo.ConfigRepository.Companion.CONFIG_KEY_AUTH_ADMIN_TWO_FACTOR_REQUIRED
+import io.newm.server.config.repo.ConfigRepository.Companion.CONFIG_KEY_RECAPTCHA_IP_WHITELIST
 import io.newm.server.features.user.repo.UserRepository
 import io.newm.server.ktx.clientPlatform
 import io.newm.server.ktx.referrer
 import io.newm.server.recaptcha.repo.RecaptchaRepository
 import io.newm.shared.exception.HttpBadRequestException
+import io.newm.shared.exception.HttpForbiddenException
 import io.newm.shared.koin.inject
 import io.newm.shared.ktx.post
 
@@ -22,16 +27,26 @@ fun Routing.createOAuthRoutes(type: OAuthType) {
     val userRepository: UserRepository by inject()
     val oAuthRepository: OAuthRepository by inject()
     val jwtRepository: JwtRepository by inject()
+    val configRepository: ConfigRepository by inject()
 
     val typeName = type.name.lowercase()
     post("$AUTH_PATH/login/$typeName") {
-        recaptchaRepository.verify("login_$typeName", request)
+        val clientIp = request.origin.remoteHost
+        val whitelist = configRepository.getCidrWhitelist(CONFIG_KEY_RECAPTCHA_IP_WHITELIST)
+
+        if (!configRepository.isIpInCidrWhitelist(clientIp, whitelist)) {
+            recaptchaRepository.verify("login_$typeName", request)
+        }
         val req = receive<OAuthLoginRequest>()
         val oauthTokens = req.oauthTokens ?: req.code?.let { code ->
-            oAuthRepository.getTokens(type, code, req.redirectUri)
+            oAuthRepository.getTokens(type, code, req.redirectUri, req.codeVerifier)
         } ?: throw HttpBadRequestException("missing code")
         val userId = userRepository.findOrAdd(type, oauthTokens, clientPlatform, referrer)
         val isAdmin = userRepository.isAdmin(userId)
+        // OAuth logins carry no 2FA code, so admins must use the password login
+        if (isAdmin && configRepository.getBoolean(CONFIG_KEY_AUTH_ADMIN_TWO_FACTOR_REQUIRED)) {
+            throw HttpForbiddenException("Admins must log in with email, password and 2FA code")
+        }
         respond(jwtRepository.createLoginResponse(userId, isAdmin))
     }
 }
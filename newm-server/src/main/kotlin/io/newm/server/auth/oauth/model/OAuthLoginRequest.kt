@@ -7,7 +7,8 @@ data class OAuthLoginRequest(
     val accessToken: String? = null,
     val idToken: String? = null,
     val code: String? = null,
-    val redirectUri: String? = null
+    val redirectUri: String? = null,
+    val codeVerifier: String? = null
 ) {
     val oauthTokens: OAuthTokens?
         get() = if (accessToken != null || idToken != null) OAuthTokens(accessToken, idToken) else null
@@ -7,6 +7,7 @@ interface OAuthRepository {
     suspend fun getTokens(
         type: OAuthType,
         code: String,
-        redirectUri: String?
+        redirectUri: String?,
+        codeVerifier: String? = null
     ): OAuthTokens
 }
@@ -21,7 +21,8 @@ class OAuthRepositoryImpl(
     override suspend fun getTokens(
         type: OAuthType,
         code: String,
-        redirectUri: String?
+        redirectUri: String?,
+        codeVerifier: String?
     ): OAuthTokens {
         logger.debug { "getTokens: type = $type, redirectUri=$redirectUri" }
 
@@ -34,6 +35,7 @@ class OAuthRepositoryImpl(
                             append("grant_type", "authorization_code")
                             append("code", code)
                             redirectUri?.let { append("redirect_uri", it) }
+                            codeVerifier?.let { append("code_verifier", it) }
                             append("client_id", getSecureString("clientId"))
                             append("client_secret", getSecureString("clientSecret"))
                         }
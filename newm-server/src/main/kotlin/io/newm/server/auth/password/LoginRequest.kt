@@ -6,5 +6,6 @@ import kotlinx.serialization.Serializable
 @Serializable
 data class LoginRequest(
     val email: String,
-    val password: Password
+    val password: Password,
+    val authCode: String? = null
 )
@@ -7,7 +7,9 @@ import io.ktor.server.response.respond
 import io.ktor.server.routing.Routing
 import io.newm.server.auth.AUTH_PATH
 import io.newm.server.auth.jwt.repo.JwtRepository
+import io.newm.server.auth.twofactor.repo.TwoFactorAuthRepository
 import io.newm.server.config.repo.ConfigRepository
+import io.newm.server.config.repo.ConfigRepository.Companion.CONFIG_KEY_AUTH_ADMIN_TWO_FACTOR_REQUIRED
 import io.newm.server.config.repo.ConfigRepository.Companion.CONFIG_KEY_RECAPTCHA_IP_WHITELIST
 import io.newm.server.features.user.repo.UserRepository
 import io.newm.server.recaptcha.repo.RecaptchaRepository
@@ -21,6 +23,7 @@ fun Routing.createPasswordAuthRoutes() {
     val userRepository: UserRepository by inject()
     val jwtRepository: JwtRepository by inject()
     val configRepository: ConfigRepository by inject()
+    val twoFactorAuthRepository: TwoFactorAuthRepository by inject()
 
     post("$AUTH_PATH/login") {
         val clientIp = request.origin.remoteHost
@@ -30,8 +33,15 @@ fun Routing.createPasswordAuthRoutes() {
             recaptchaRepository.verify("login", request)
         }
 
-        val (email, password) = receive<LoginRequest>()
+        val (email, password, authCode) = receive<LoginRequest>()
         val (uuid, admin) = userRepository.find(email, password)
+        if (authCode != null) {
+            if (!twoFactorAuthRepository.verifyLoginCode(email, authCode)) {
+                throw TwoFactorLoginException(ERROR_CODE_2FA_FAILED, "2FA failed")
+            }
+        } else if (admin && configRepository.getBoolean(CONFIG_KEY_AUTH_ADMIN_TWO_FACTOR_REQUIRED)) {
+            throw TwoFactorLoginException(ERROR_CODE_2FA_REQUIRED, "2FA code required")
+        }
         respond(jwtRepository.createLoginResponse(uuid, admin))
     }
 }
@@ -0,0 +1,13 @@
+package io.newm.server.auth.password
+
+// Error codes of the 2FA rejections, so clients can offer a code step
+const val ERROR_CODE_2FA_REQUIRED = "2FA_REQUIRED"
+const val ERROR_CODE_2FA_FAILED = "2FA_FAILED"
+
+/**
+ * Login rejected for a missing or wrong 2FA code. Answered with 403 Forbidden and [errorCode].
+ */
+class TwoFactorLoginException(
+    val errorCode: String,
+    message: String
+) : Exception(message)
@@ -1,10 +1,13 @@
 package io.newm.server.auth.twofactor
 
 import io.ktor.http.HttpStatusCode
+import io.ktor.server.plugins.origin
 import io.ktor.server.response.respond
 import io.ktor.server.routing.Routing
 import io.newm.server.auth.AUTH_PATH
 import io.newm.server.auth.twofactor.repo.TwoFactorAuthRepository
+import io.newm.server.config.repo.ConfigRepository
+import io.newm.server.config.repo.ConfigRepository.Companion.CONFIG_KEY_RECAPTCHA_IP_WHITELIST
 import io.newm.server.ktx.requiredQueryParam
 import io.newm.server.recaptcha.repo.RecaptchaRepository
 import io.newm.shared.koin.inject
@@ -13,9 +16,15 @@ import io.newm.shared.ktx.get
 fun Routing.createTwoFactorAuthRoutes() {
     val recaptchaRepository: RecaptchaRepository by inject()
     val twoFactorAuthRepository: TwoFactorAuthRepository by inject()
+    val configRepository: ConfigRepository by inject()
 
     get("$AUTH_PATH/code") {
-        recaptchaRepository.verify("auth_code", request)
+        val clientIp = request.origin.remoteHost
+        val whitelist = configRepository.getCidrWhitelist(CONFIG_KEY_RECAPTCHA_IP_WHITELIST)
+
+        if (!configRepository.isIpInCidrWhitelist(clientIp, whitelist)) {
+            recaptchaRepository.verify("auth_code", request)
+        }
         twoFactorAuthRepository.sendCode(
             email = request.requiredQueryParam("email"),
             mustExists = request.queryParameters["mustExists"]?.toBoolean() ?: false,
@@ -15,6 +15,7 @@ class TwoFactorAuthEntity(
     var email: String by TwoFactorAuthTable.email
     var codeHash: String by TwoFactorAuthTable.codeHash
     var expiresAt: LocalDateTime by TwoFactorAuthTable.expiresAt
+    var failedAttempts: Int by TwoFactorAuthTable.failedAttempts
 
     companion object : EntityClass<Long, TwoFactorAuthEntity>(TwoFactorAuthTable) {
         fun getByEmail(email: String): TwoFactorAuthEntity? =
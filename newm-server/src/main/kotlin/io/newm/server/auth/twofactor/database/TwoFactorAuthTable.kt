@@ -9,4 +9,5 @@ object TwoFactorAuthTable : LongIdTable(name = "two_factor_auth") {
     val email: Column<String> = text("email")
     val codeHash: Column<String> = text("code_hash")
     val expiresAt: Column<LocalDateTime> = datetime("expires_at")
+    val failedAttempts: Column<Int> = integer("failed_attempts").default(0)
 }
@@ -11,4 +11,10 @@ interface TwoFactorAuthRepository {
         email: String,
         code: String
     ): Boolean
+
+    // Like verifyCode, but the code is invalidated after too many wrong guesses
+    suspend fun verifyLoginCode(
+        email: String,
+        code: String
+    ): Boolean
 }
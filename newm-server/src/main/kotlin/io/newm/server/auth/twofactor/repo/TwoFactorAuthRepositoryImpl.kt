@@ -6,6 +6,7 @@ import io.newm.server.auth.twofactor.database.TwoFactorAuthEntity
 import io.newm.server.features.email.repo.EmailRepository
 import io.newm.server.features.user.database.UserEntity
 import io.newm.shared.ktx.getConfigChild
+import io.newm.shared.ktx.getConfigInt
 import io.newm.shared.ktx.getInt
 import io.newm.shared.ktx.getLong
 import io.newm.shared.ktx.getString
@@ -73,4 +74,26 @@ internal class TwoFactorAuthRepositoryImpl(
                 true
             } ?: false
         }
+
+    override suspend fun verifyLoginCode(
+        email: String,
+        code: String
+    ): Boolean {
+        val maxAttempts = environment.getConfigInt("twoFactorAuth.maxLoginAttempts")
+        return transaction {
+            TwoFactorAuthEntity.deleteAllExpired()
+            val entity = TwoFactorAuthEntity.getByEmail(email) ?: return@transaction false
+            if (code.verify(entity.codeHash)) {
+                entity.delete()
+                return@transaction true
+            }
+            // Invalidate the code after too many wrong guesses
+            entity.failedAttempts += 1
+            if (entity.failedAttempts >= maxAttempts) {
+                logger.warn { "Too many failed 2fa login attempts for $email, invalidating code" }
+                entity.delete()
+            }
+            false
+        }
+    }
 }
@@ -68,6 +68,7 @@ interface ConfigRepository {
         const val CONFIG_KEY_RECAPTCHA_ENABLED = "recaptcha.enabled"
         const val CONFIG_KEY_RECAPTCHA_MIN_SCORE = "recaptcha.minScore"
         const val CONFIG_KEY_RECAPTCHA_IP_WHITELIST = "recaptcha.ipWhitelist"
+        const val CONFIG_KEY_AUTH_ADMIN_TWO_FACTOR_REQUIRED = "auth.adminTwoFactorRequired"
         const val CONFIG_KEY_NFTCDN_ENABLED = "nftcdn.enabled"
         const val CONFIG_KEY_MARKETPLACE_MONITORING_MULTI_MODE_ENABLED = "marketplace.monitoringMultiModeEnabled"
         const val CONFIG_KEY_MARKETPLACE_MONITORING_RETRY_DELAY = "marketplace.monitoringRetryDelay"
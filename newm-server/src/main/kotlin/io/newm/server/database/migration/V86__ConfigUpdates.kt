@@ -0,0 +1,14 @@
+package io.newm.server.database.migration
+
+import org.flywaydb.core.api.migration.BaseJavaMigration
+import org.flywaydb.core.api.migration.Context
+import org.jetbrains.exposed.sql.transactions.transaction
+
+@Suppress("unused")
+class V86__ConfigUpdates : BaseJavaMigration() {
+    override fun migrate(context: Context?) {
+        transaction {
+            exec("INSERT INTO config VALUES ('auth.adminTwoFactorRequired','false') ON CONFLICT(id) DO NOTHING")
+        }
+    }
+}
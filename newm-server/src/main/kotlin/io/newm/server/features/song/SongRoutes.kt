@@ -27,6 +27,7 @@ import io.newm.server.ktx.myUserId
 import io.newm.server.ktx.offset
 import io.newm.server.ktx.requestPaymentType
 import io.newm.server.ktx.songId
+import io.newm.shared.exception.HttpNotFoundException
 import io.newm.shared.koin.inject
 import io.newm.shared.ktx.delete
 import io.newm.shared.ktx.get
@@ -46,6 +47,10 @@ fun Routing.createSongRoutes() {
 
     authenticate(AUTH_JWT_ADMIN) {
         route(SONGS_PATH) {
+            get("isrc/{isrc}") {
+                val isrc = parameters["isrc"]!!
+                respond(songRepository.getByIsrc(isrc) ?: throw HttpNotFoundException("No song found with ISRC: $isrc"))
+            }
             route("{songId}") {
                 post("refund") {
                     val song = songRepository.get(songId)
@@ -8,6 +8,7 @@ import io.ktor.server.application.install
 import io.ktor.server.plugins.BadRequestException
 import io.ktor.server.plugins.statuspages.StatusPages
 import io.ktor.server.response.respond
+import io.newm.server.auth.password.TwoFactorLoginException
 import io.newm.server.logging.captureToSentry
 import io.newm.shared.exception.HttpStatusException
 import org.jetbrains.exposed.dao.exceptions.EntityNotFoundException
@@ -22,6 +23,10 @@ fun Application.installStatusPages() {
                     call.respondStatus(cause.statusCode, cause)
                 }
 
+                is TwoFactorLoginException -> {
+                    call.respondStatus(HttpStatusCode.Forbidden, cause)
+                }
+
                 is EntityNotFoundException -> {
                     call.respondStatus(HttpStatusCode.NotFound, cause)
                 }
@@ -56,6 +61,7 @@ private suspend fun ApplicationCall.respondStatus(
         StatusResponse(
             code = statusCode.value,
             description = statusCode.description,
-            cause = cause.message ?: cause.toString()
+            cause = cause.message ?: cause.toString(),
+            errorCode = (cause as? TwoFactorLoginException)?.errorCode
         )
 )
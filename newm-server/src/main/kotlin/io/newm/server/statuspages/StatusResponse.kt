@@ -6,5 +6,6 @@ import kotlinx.serialization.Serializable
 data class StatusResponse(
     val code: Int,
     val description: String,
-    val cause: String
+    val cause: String,
+    val errorCode: String? = null
 )
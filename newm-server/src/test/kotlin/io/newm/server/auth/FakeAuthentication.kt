@@ -10,10 +10,16 @@ import io.ktor.server.auth.AuthenticationContext
 import io.ktor.server.auth.AuthenticationProvider
 import io.ktor.server.auth.jwt.JWTPrincipal
 import io.ktor.server.auth.parseAuthorizationHeader
+import io.newm.server.auth.jwt.repo.JwtRepository
 import io.newm.server.features.idenfy.configIdenfyFakeServerAuth
+import io.newm.shared.koin.inject
+import io.newm.shared.ktx.orNull
+import io.newm.shared.ktx.toUUID
 import java.util.Date
 
-// Fake JWK authentication using User ID passed as access token
+// Fake JWK authentication using User ID passed as access token.
+// A token of the form "<userId>.<jwtId>" also carries a JWT ID, which is
+// rejected once blacklisted, like the real JWT authentication does.
 fun Application.installFakeAuthentication() {
     install(Authentication) {
         register(FakeAuthProvider("auth-jwt"))
@@ -26,16 +32,25 @@ fun Application.installFakeAuthentication() {
 private class FakeAuthProvider(
     name: String
 ) : AuthenticationProvider(object : Config(name) {}) {
+    private val jwtRepository: JwtRepository by inject()
+
     override suspend fun onAuthenticate(context: AuthenticationContext) =
         context.run {
             val authHeader = call.request.parseAuthorizationHeader() as HttpAuthHeader.Single
-            principal(JWTPrincipal(FakePayload(authHeader.blob)))
+            val payload = FakePayload(authHeader.blob)
+            val blacklisted = payload.id.orNull()?.let { jwtRepository.isBlacklisted(it.toUUID()) } ?: false
+            if (!blacklisted) {
+                principal(JWTPrincipal(payload))
+            }
         }
 }
 
 private class FakePayload(
-    private val userId: String
+    token: String
 ) : Payload {
+    private val userId = token.substringBefore('.')
+    private val jwtId = token.substringAfter('.', "")
+
     override fun getIssuer(): String = ""
 
     override fun getSubject() = userId
@@ -48,7 +63,7 @@ private class FakePayload(
 
     override fun getIssuedAt(): Date = Date()
 
-    override fun getId(): String = ""
+    override fun getId(): String = jwtId
 
     override fun getClaim(name: String?): Claim =
         object : Claim {
@@ -0,0 +1,77 @@
+package io.newm.server.auth.jwt
+
+import com.google.common.truth.Truth.assertThat
+import io.ktor.client.request.bearerAuth
+import io.ktor.client.request.delete
+import io.ktor.client.request.get
+import io.ktor.http.HttpStatusCode
+import io.newm.server.BaseApplicationTests
+import kotlinx.coroutines.runBlocking
+import org.junit.jupiter.api.Test
+import java.util.UUID
+
+class JwtRoutesTests : BaseApplicationTests() {
+    // Fake token carrying a JWT ID, see FakeAuthentication
+    private fun newToken(): String = "$testUserId.${UUID.randomUUID()}"
+
+    @Test
+    fun testDeleteJwt() =
+        runBlocking {
+            val token = newToken()
+            val otherToken = newToken()
+
+            var response = client.get("v1/auth/jwt") {
+                bearerAuth(token)
+            }
+            assertThat(response.status).isEqualTo(HttpStatusCode.OK)
+
+            // Logout
+            response = client.delete("v1/auth/jwt") {
+                bearerAuth(token)
+            }
+            assertThat(response.status).isEqualTo(HttpStatusCode.NoContent)
+
+            // Token is rejected afterwards
+            response = client.get("v1/auth/jwt") {
+                bearerAuth(token)
+            }
+            assertThat(response.status).isEqualTo(HttpStatusCode.Unauthorized)
+
+            // Second logout is harmless: still rejected, nothing else changes
+            response = client.delete("v1/auth/jwt") {
+                bearerAuth(token)
+            }
+            assertThat(response.status).isEqualTo(HttpStatusCode.Unauthorized)
+
+            // Other sessions of the same user are not affected
+            response = client.get("v1/auth/jwt") {
+                bearerAuth(otherToken)
+            }
+            assertThat(response.status).isEqualTo(HttpStatusCode.OK)
+        }
+
+    @Test
+    fun testDeleteRefresh() =
+        runBlocking {
+            val refreshToken = newToken()
+            val accessToken = newToken()
+
+            // Logout
+            var response = client.delete("v1/auth/refresh") {
+                bearerAuth(refreshToken)
+            }
+            assertThat(response.status).isEqualTo(HttpStatusCode.NoContent)
+
+            // Token is rejected afterwards, so a second logout is harmless
+            response = client.delete("v1/auth/refresh") {
+                bearerAuth(refreshToken)
+            }
+            assertThat(response.status).isEqualTo(HttpStatusCode.Unauthorized)
+
+            // Access tokens are only revoked by their own logout
+            response = client.get("v1/auth/jwt") {
+                bearerAuth(accessToken)
+            }
+            assertThat(response.status).isEqualTo(HttpStatusCode.OK)
+        }
+}
@@ -0,0 +1,136 @@
+package io.newm.server.auth.password
+
+import com.google.common.truth.Truth.assertThat
+import io.ktor.client.call.body
+import io.ktor.client.request.post
+import io.ktor.client.request.setBody
+import io.ktor.http.ContentType
+import io.ktor.http.HttpStatusCode
+import io.ktor.http.contentType
+import io.newm.server.BaseApplicationTests
+import io.newm.server.auth.twofactor.database.TwoFactorAuthEntity
+import io.newm.server.auth.twofactor.database.TwoFactorAuthTable
+import io.newm.server.auth.twofactor.repo.TwoFactorAuthRepository
+import io.newm.server.config.database.ConfigEntity
+import io.newm.server.config.database.ConfigTable
+import io.newm.server.config.repo.ConfigRepository
+import io.newm.server.config.repo.ConfigRepository.Companion.CONFIG_KEY_AUTH_ADMIN_TWO_FACTOR_REQUIRED
+import io.newm.server.config.repo.ConfigRepository.Companion.CONFIG_KEY_RECAPTCHA_IP_WHITELIST
+import io.newm.server.features.user.database.UserEntity
+import io.newm.server.features.user.database.UserTable
+import io.newm.server.statuspages.StatusResponse
+import io.newm.shared.auth.Password
+import io.newm.shared.koin.inject
+import io.newm.shared.ktx.toHash
+import kotlinx.coroutines.runBlocking
+import org.jetbrains.exposed.sql.deleteAll
+import org.jetbrains.exposed.sql.transactions.transaction
+import org.junit.jupiter.api.BeforeEach
+import org.junit.jupiter.api.Test
+import java.time.LocalDateTime
+
+class PasswordAuthRoutesTests : BaseApplicationTests() {
+    private val email = "admin@projectnewm.io"
+    private val password = Password("Password123!")
+
+    @BeforeEach
+    fun beforeEach() {
+        transaction {
+            ConfigTable.deleteAll()
+            UserTable.deleteAll()
+            TwoFactorAuthTable.deleteAll()
+            ConfigEntity.new(CONFIG_KEY_RECAPTCHA_IP_WHITELIST) {
+                value = "[]"
+            }
+            ConfigEntity.new(CONFIG_KEY_AUTH_ADMIN_TWO_FACTOR_REQUIRED) {
+                value = "true"
+            }
+            UserEntity.new {
+                email = this@PasswordAuthRoutesTests.email
+                passwordHash = password.toHash()
+                admin = true
+            }
+        }
+        val configRepository: ConfigRepository by inject()
+        configRepository.invalidateCache()
+    }
+
+    @Test
+    fun testLoginAdminRequiresCode() =
+        runBlocking {
+            val response = client.post("v1/auth/login") {
+                contentType(ContentType.Application.Json)
+                setBody(LoginRequest(email, password))
+            }
+            assertThat(response.status).isEqualTo(HttpStatusCode.Forbidden)
+            assertThat(response.body<StatusResponse>().errorCode).isEqualTo(ERROR_CODE_2FA_REQUIRED)
+        }
+
+    @Test
+    fun testLoginWrongCode() =
+        runBlocking {
+            val response = client.post("v1/auth/login") {
+                contentType(ContentType.Application.Json)
+                setBody(LoginRequest(email, password, authCode = "000000"))
+            }
+            assertThat(response.status).isEqualTo(HttpStatusCode.Forbidden)
+            assertThat(response.body<StatusResponse>().errorCode).isEqualTo(ERROR_CODE_2FA_FAILED)
+        }
+
+    @Test
+    fun testCodeInvalidatedAfterMaxAttempts() =
+        runBlocking {
+            val code = "123456"
+            transaction {
+                TwoFactorAuthEntity.new {
+                    email = this@PasswordAuthRoutesTests.email
+                    codeHash = code.toHash()
+                    expiresAt = LocalDateTime.now().plusMinutes(5)
+                }
+            }
+
+            // test-application.conf allows 3 attempts
+            repeat(2) {
+                val response = client.post("v1/auth/login") {
+                    contentType(ContentType.Application.Json)
+                    setBody(LoginRequest(email, password, authCode = "000000"))
+                }
+                assertThat(response.body<StatusResponse>().errorCode).isEqualTo(ERROR_CODE_2FA_FAILED)
+            }
+            assertThat(transaction { TwoFactorAuthEntity.getByEmail(email)?.failedAttempts }).isEqualTo(2)
+
+            client.post("v1/auth/login") {
+                contentType(ContentType.Application.Json)
+                setBody(LoginRequest(email, password, authCode = "000000"))
+            }
+            assertThat(transaction { TwoFactorAuthEntity.getByEmail(email) }).isNull()
+
+            // The right code no longer works once the code is invalidated
+            val response = client.post("v1/auth/login") {
+                contentType(ContentType.Application.Json)
+                setBody(LoginRequest(email, password, authCode = code))
+            }
+            assertThat(response.status).isEqualTo(HttpStatusCode.Forbidden)
+            assertThat(response.body<StatusResponse>().errorCode).isEqualTo(ERROR_CODE_2FA_FAILED)
+        }
+
+    @Test
+    fun testOtherCodesNotLimited() =
+        runBlocking {
+            val code = "123456"
+            transaction {
+                TwoFactorAuthEntity.new {
+                    email = this@PasswordAuthRoutesTests.email
+                    codeHash = code.toHash()
+                    expiresAt = LocalDateTime.now().plusMinutes(5)
+                }
+            }
+
+            // Signup and password reset codes keep working after wrong guesses
+            val twoFactorAuthRepository: TwoFactorAuthRepository by inject()
+            repeat(5) {
+                assertThat(twoFactorAuthRepository.verifyCode(email, "000000")).isFalse()
+            }
+            assertThat(twoFactorAuthRepository.verifyCode(email, code)).isTrue()
+        }
+}
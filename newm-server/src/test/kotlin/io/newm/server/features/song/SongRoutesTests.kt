@@ -160,6 +160,28 @@ class SongRoutesTests : BaseApplicationTests() {
             assertThat(response.body<Song>()).isEqualTo(song)
         }
 
+    @Test
+    fun testGetSongByIsrc() =
+        runBlocking {
+            // Add Song directly into database
+            val song = addSongToDatabase().copy(earnings = null)
+
+            // Get it by ISRC
+            val response = client.get("v1/songs/isrc/${song.isrc}") {
+                bearerAuth(testUserToken)
+                accept(ContentType.Application.Json)
+            }
+            assertThat(response.status).isEqualTo(HttpStatusCode.OK)
+            assertThat(response.body<Song>()).isEqualTo(song)
+
+            // Unknown ISRC
+            val missing = client.get("v1/songs/isrc/QZABC2400001") {
+                bearerAuth(testUserToken)
+                accept(ContentType.Application.Json)
+            }
+            assertThat(missing.status).isEqualTo(HttpStatusCode.NotFound)
+        }
+
     @Test
     fun testGetAllSongs() =
         runBlocking {